@@ -0,0 +1,372 @@
+#![allow(dead_code)]
+
+use crate::{
+    error::InletError,
+    inlet::{Contour, Inlet, WallState},
+    utils::{self, numerics::{OdeSolution, Termination}},
+};
+
+// turbulent boundary layer model constants
+const RECOVERY_FACTOR: f64 = 0.89;             // prandtl number to the one third
+const VISCOSITY_EXPONENT: f64 = 0.76;          // power law viscosity, mu ~ t^0.76
+const INCOMPRESSIBLE_SHAPE_FACTOR: f64 = 1.4;
+const SKIN_FRICTION_CONSTANT: f64 = 0.0256;    // cf = 0.0256 re_theta^-1/4
+
+// displacement correction iteration settings
+const CORRECTION_TOLERANCE: f64 = 1e-9;
+const MAX_CORRECTION_ITERS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct BoundaryLayer {
+    // a struct to organise the integral boundary layer properties at each contour point,
+    // with lengths non-dimensionalised by the contour length scale
+    pub arc_length: Vec<f64>,
+    pub momentum_thickness: Vec<f64>,
+    pub displacement_thickness: Vec<f64>,
+    pub skin_friction_coefficient: Vec<f64>,
+}
+
+#[derive(Debug)]
+pub struct BoundaryCorrectedBusemann {
+    // a struct to organise an inlet with its wall offset by the displacement thickness
+    pub inlet: Inlet,
+    pub boundary_layer: BoundaryLayer,
+    pub iterations: usize,
+}
+
+struct EdgeConditions {
+    // edge state along the wall relative to the freestream
+    mach_number: Vec<f64>,
+    temperature_ratio: Vec<f64>,
+    velocity_ratio: Vec<f64>,
+    density_ratio: Vec<f64>,
+    specific_heat_ratio: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EdgePoint {
+    // edge state at one station along the wall
+    mach_number: f64,
+    temperature_ratio: f64,
+    velocity_ratio: f64,
+    density_ratio: f64,
+    specific_heat_ratio: f64,
+}
+
+impl EdgeConditions {
+    fn new(edge_mach: &[f64], freestream_mach: f64, specific_heat_ratio: f64) -> Result<Self, InletError> {
+        // the edge flow is isentropic from the freestream
+        let freestream_temperature_ratio: f64 = utils::isentropic::calc_temperature_ratio_from_mach(freestream_mach, specific_heat_ratio)?;
+        let freestream_density_ratio: f64 = utils::isentropic::calc_density_ratio_from_mach(freestream_mach, specific_heat_ratio)?;
+
+        let mut temperature_ratio: Vec<f64> = Vec::with_capacity(edge_mach.len());
+        let mut velocity_ratio: Vec<f64> = Vec::with_capacity(edge_mach.len());
+        let mut density_ratio: Vec<f64> = Vec::with_capacity(edge_mach.len());
+        for mach_number in edge_mach {
+            let local_temperature_ratio: f64 = utils::isentropic::calc_temperature_ratio_from_mach(*mach_number, specific_heat_ratio)?
+                / freestream_temperature_ratio;
+            let local_density_ratio: f64 = utils::isentropic::calc_density_ratio_from_mach(*mach_number, specific_heat_ratio)?
+                / freestream_density_ratio;
+            temperature_ratio.push(local_temperature_ratio);
+            velocity_ratio.push(mach_number / freestream_mach * local_temperature_ratio.sqrt());
+            density_ratio.push(local_density_ratio);
+        }
+
+        Ok(EdgeConditions {
+            mach_number: edge_mach.to_vec(),
+            temperature_ratio,
+            velocity_ratio,
+            density_ratio,
+            specific_heat_ratio: vec![specific_heat_ratio; edge_mach.len()],
+        })
+    }
+
+    fn from_wall_states(edge_mach: &[f64], freestream_mach: f64, wall_states: &[WallState]) -> Self {
+        // the edge flow follows the gas states of the design
+        EdgeConditions {
+            mach_number: edge_mach.to_vec(),
+            temperature_ratio: wall_states.iter().map(|state| state.temperature_ratio).collect(),
+            velocity_ratio: edge_mach.iter().zip(wall_states)
+                .map(|(mach_number, state)| mach_number / freestream_mach * state.sound_speed_ratio)
+                .collect(),
+            density_ratio: wall_states.iter().map(|state| state.density_ratio).collect(),
+            specific_heat_ratio: wall_states.iter().map(|state| state.specific_heat_ratio).collect(),
+        }
+    }
+
+    fn from_inlet(inlet: &Inlet) -> Result<Self, InletError> {
+        let edge_mach: Vec<f64> = inlet.flowfield().iter().map(|result| result.velocity_vector.get_mach_number()).collect();
+        match inlet.wall_states() {
+            Some(wall_states) => Ok(EdgeConditions::from_wall_states(&edge_mach, inlet.freestream_mach(), wall_states)),
+            None => EdgeConditions::new(&edge_mach, inlet.freestream_mach(), inlet.specific_heat_ratio()),
+        }
+    }
+
+    fn at(&self, index: usize, fraction: f64) -> EdgePoint {
+        // linear between a contour point and the next one
+        let lerp = |values: &[f64]| match values.get(index + 1) {
+            Some(next) => values[index] + fraction * (next - values[index]),
+            None => values[index],
+        };
+        EdgePoint {
+            mach_number: lerp(&self.mach_number),
+            temperature_ratio: lerp(&self.temperature_ratio),
+            velocity_ratio: lerp(&self.velocity_ratio),
+            density_ratio: lerp(&self.density_ratio),
+            specific_heat_ratio: lerp(&self.specific_heat_ratio),
+        }
+    }
+}
+
+struct LocalProperties {
+    skin_friction_coefficient: f64,
+    shape_factor: f64,
+}
+
+fn calc_local_properties(
+    edge: &EdgePoint,
+    momentum_thickness: f64,
+    reynolds_number: f64,
+    wall_temperature_ratio: Option<f64>,
+) -> LocalProperties {
+    // eckert reference temperature applied to an incompressible skin friction law
+    let mach_number: f64 = edge.mach_number;
+    let compressibility: f64 = (edge.specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2);
+    let wall_to_edge_temperature: f64 = match wall_temperature_ratio {
+        Some(ratio) => ratio / edge.temperature_ratio,
+        None => 1.0 + RECOVERY_FACTOR * compressibility,
+    };
+    let reference_to_edge_temperature: f64 = 0.5 + 0.039 * mach_number.powi(2) + 0.5 * wall_to_edge_temperature;
+
+    let edge_viscosity_ratio: f64 = edge.temperature_ratio.powf(VISCOSITY_EXPONENT);
+    let reynolds_theta: f64 = reynolds_number * edge.density_ratio * edge.velocity_ratio
+        * momentum_thickness / edge_viscosity_ratio;
+    let reference_reynolds_theta: f64 = reynolds_theta * reference_to_edge_temperature.powf(-1.0 - VISCOSITY_EXPONENT);
+
+    LocalProperties {
+        skin_friction_coefficient: SKIN_FRICTION_CONSTANT * reference_reynolds_theta.powf(-0.25) / reference_to_edge_temperature,
+        shape_factor: INCOMPRESSIBLE_SHAPE_FACTOR * wall_to_edge_temperature + RECOVERY_FACTOR * compressibility,
+    }
+}
+
+pub fn calc_boundary_layer(
+    x_coords: &[f64],
+    r_coords: &[f64],
+    edge_mach: &[f64],
+    freestream_mach: f64,
+    reynolds_number: f64, // freestream reynolds number based on the contour length scale
+    wall_temperature_ratio: Option<f64>, // wall to freestream static temperature, adiabatic if none
+    specific_heat_ratio: f64,
+) -> Result<BoundaryLayer, InletError> {
+    // edge conditions for a calorically perfect gas
+    let edge: EdgeConditions = EdgeConditions::new(edge_mach, freestream_mach, specific_heat_ratio)?;
+    integrate_boundary_layer(x_coords, r_coords, &edge, reynolds_number, wall_temperature_ratio)
+}
+
+fn integrate_boundary_layer(
+    x_coords: &[f64],
+    r_coords: &[f64],
+    edge: &EdgeConditions,
+    reynolds_number: f64,
+    wall_temperature_ratio: Option<f64>,
+) -> Result<BoundaryLayer, InletError> {
+    // axisymmetric compressible momentum integral equation for a turbulent boundary layer
+    // dtheta / ds = cf / 2 - theta ((2 + h - me^2) due / (ue ds) + dr / (r ds))
+    let points: usize = x_coords.len();
+    if points < 2 || r_coords.len() != points || edge.mach_number.len() != points {
+        return Err(InletError::InvalidInlet("contour and edge conditions must have matching lengths"));
+    }
+    if reynolds_number <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "reynolds number", value: reynolds_number });
+    }
+
+    let mut arc_length: Vec<f64> = vec![0.0];
+    for i in 1..points {
+        let segment: f64 = ((x_coords[i] - x_coords[i - 1]).powi(2) + (r_coords[i] - r_coords[i - 1]).powi(2)).sqrt();
+        arc_length.push(arc_length[i - 1] + segment);
+    }
+
+    // the layer starts from zero thickness at the leading edge, where the flat plate power
+    // law solution of dtheta / ds = a theta^-1/4 carries it over the first segment
+    let unit_properties: LocalProperties = calc_local_properties(&edge.at(0, 0.0), 1.0, reynolds_number, wall_temperature_ratio);
+    let flat_plate_coefficient: f64 = 0.5 * unit_properties.skin_friction_coefficient;
+    let mut momentum_thickness: Vec<f64> = vec![0.0, (1.25 * flat_plate_coefficient * arc_length[1]).powf(0.8)];
+
+    // runge kutta over the remaining segments, with the edge state and radius linear and
+    // their gradients held over each segment
+    for i in 1..points - 1 {
+        let h: f64 = arc_length[i + 1] - arc_length[i];
+        if h <= 0.0 {
+            momentum_thickness.push(momentum_thickness[i]);
+            continue;
+        }
+        let velocity_gradient: f64 = (edge.velocity_ratio[i + 1] - edge.velocity_ratio[i]) / h;
+        let radius_gradient: f64 = (r_coords[i + 1] - r_coords[i]) / h;
+
+        let derivative = |s: f64, theta: &[f64; 1]| {
+            if theta[0] <= 0.0 {
+                return Err(InletError::NoSolution("boundary layer separated"));
+            }
+            let fraction: f64 = (s - arc_length[i]) / h;
+            let local: EdgePoint = edge.at(i, fraction);
+            let radius: f64 = r_coords[i] + fraction * (r_coords[i + 1] - r_coords[i]);
+            let properties: LocalProperties = calc_local_properties(&local, theta[0], reynolds_number, wall_temperature_ratio);
+            Ok([0.5 * properties.skin_friction_coefficient - theta[0] * (
+                (2.0 + properties.shape_factor - local.mach_number.powi(2)) * velocity_gradient / local.velocity_ratio
+                + radius_gradient / radius
+            )])
+        };
+
+        let solution: OdeSolution<1> = utils::numerics::rk4(&derivative, arc_length[i], [momentum_thickness[i]], arc_length[i + 1], 1, &[])?;
+        let next: f64 = solution.y[solution.y.len() - 1][0];
+        if solution.termination != Termination::Completed || solution.y.len() < 2 || next <= 0.0 {
+            return Err(InletError::NoSolution("boundary layer separated"));
+        }
+        momentum_thickness.push(next);
+    }
+
+    let mut displacement_thickness: Vec<f64> = Vec::with_capacity(points);
+    let mut skin_friction_coefficient: Vec<f64> = Vec::with_capacity(points);
+    for (i, theta) in momentum_thickness.iter().enumerate() {
+        let properties: LocalProperties = calc_local_properties(&edge.at(i, 0.0), *theta, reynolds_number, wall_temperature_ratio);
+        displacement_thickness.push(properties.shape_factor * theta);
+        skin_friction_coefficient.push(if *theta > 0.0 { properties.skin_friction_coefficient } else { f64::INFINITY });
+    }
+
+    Ok(BoundaryLayer {
+        arc_length,
+        momentum_thickness,
+        displacement_thickness,
+        skin_friction_coefficient,
+    })
+}
+
+pub fn correct_contour(
+    inlet: &Inlet,
+    reynolds_number: f64, // freestream reynolds number based on the exit radius
+    wall_temperature_ratio: Option<f64>,
+) -> Result<BoundaryCorrectedBusemann, InletError> {
+    // offset the inviscid wall outwards by the displacement thickness so the inviscid core
+    // still sees the design contour, repeating the boundary layer calculation on the
+    // displaced wall until the offset stops changing
+    if inlet.flowfield().len() != inlet.contour().len() {
+        return Err(InletError::InvalidInlet("inlet has no flowfield to supply edge conditions"));
+    }
+    let x_coords: &[f64] = inlet.contour().x_coords();
+    let inviscid_r_coords: &[f64] = inlet.contour().y_coords();
+    let edge: EdgeConditions = EdgeConditions::from_inlet(inlet)?;
+    let wall_angle: Vec<f64> = inlet.flowfield().iter().map(|result| result.flow_angle()).collect();
+
+    let mut r_coords: Vec<f64> = inviscid_r_coords.to_vec();
+    let mut boundary_layer: Option<BoundaryLayer> = None;
+    let mut iterations: usize = 0;
+    let mut change: f64 = f64::INFINITY;
+
+    for iteration in 1..=MAX_CORRECTION_ITERS {
+        let next: BoundaryLayer = integrate_boundary_layer(x_coords, &r_coords, &edge, reynolds_number, wall_temperature_ratio)?;
+
+        change = match &boundary_layer {
+            Some(previous) => previous.displacement_thickness.iter()
+                .zip(&next.displacement_thickness)
+                .map(|(old, new)| (new - old).abs())
+                .fold(0.0, f64::max),
+            None => f64::INFINITY,
+        };
+
+        r_coords = inviscid_r_coords.iter()
+            .zip(&next.displacement_thickness)
+            .zip(&wall_angle)
+            .map(|((r, displacement), angle)| r + displacement / angle.cos())
+            .collect();
+        boundary_layer = Some(next);
+        iterations = iteration;
+
+        if change < CORRECTION_TOLERANCE {
+            break;
+        }
+    }
+
+    if change >= CORRECTION_TOLERANCE {
+        return Err(InletError::NotConverged { iterations, residual: change });
+    }
+    let boundary_layer: BoundaryLayer = boundary_layer.ok_or(InletError::NoSolution("math error"))?;
+
+    let mut contour: Contour = Contour::new();
+    for (x, r) in x_coords.iter().zip(&r_coords) {
+        contour.push_coords(*x, *r);
+    }
+
+    Ok(BoundaryCorrectedBusemann {
+        inlet: inlet.with_contour(contour),
+        boundary_layer,
+        iterations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{busemann, utils::gas::{CaloricallyPerfectGas, ThermallyPerfectGas}};
+
+    #[test]
+    fn test_calc_boundary_layer_flat_plate() {
+        // at constant edge conditions the turbulent momentum thickness grows as s^0.8
+        let points = 201;
+        let x_coords: Vec<f64> = (0..points).map(|i| i as f64 / (points - 1) as f64).collect();
+        let r_coords = vec![1.0; points];
+        let edge_mach = vec![3.0; points];
+        let boundary_layer = calc_boundary_layer(&x_coords, &r_coords, &edge_mach, 3.0, 1e7, None, 1.4)
+            .expect("valid boundary layer");
+        let half = boundary_layer.momentum_thickness[(points - 1) / 2];
+        let full = boundary_layer.momentum_thickness[points - 1];
+        assert!(((full / half).ln() / 2.0_f64.ln() - 0.8).abs() < 1e-2);
+        assert!(boundary_layer.displacement_thickness[points - 1] > full);
+    }
+
+    #[test]
+    fn test_correct_contour() {
+        // the corrected wall lies outside the inviscid wall and converges
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let corrected = correct_contour(&inlet, 1e7, None).expect("valid corrected contour");
+        assert!(corrected.iterations < MAX_CORRECTION_ITERS);
+        let inviscid = inlet.contour().y_coords();
+        let viscous = corrected.inlet.contour().y_coords();
+        assert!(viscous.iter().zip(inviscid).skip(1).all(|(viscous, inviscid)| viscous > inviscid));
+        assert!(viscous[viscous.len() - 1] > 1.0);
+        // the terminal shock still meets the inviscid wall at unit radius
+        assert_eq!(corrected.inlet.exit_radius(), 1.0);
+
+        // a higher reynolds number gives a thinner boundary layer
+        let thinner = correct_contour(&inlet, 1e8, None).expect("valid corrected contour");
+        let last = viscous.len() - 1;
+        assert!(thinner.boundary_layer.displacement_thickness[last] < corrected.boundary_layer.displacement_thickness[last]);
+    }
+
+    #[test]
+    fn test_correct_contour_for_gas() {
+        // a perfect gas design through the gas model gives the same boundary layer, while
+        // the thermally perfect edge states are carried into the correction
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let gas_inlet = busemann::calc_contour_from_machs_for_gas(6.0, 220.0, 2.5, &CaloricallyPerfectGas::air())
+            .expect("valid busemann inlet");
+        let corrected = correct_contour(&inlet, 1e7, None).expect("valid corrected contour");
+        let gas_corrected = correct_contour(&gas_inlet, 1e7, None).expect("valid corrected contour");
+        let last = corrected.boundary_layer.displacement_thickness.len() - 1;
+        let gas_last = gas_corrected.boundary_layer.displacement_thickness.len() - 1;
+        let displacement: f64 = corrected.boundary_layer.displacement_thickness[last];
+        assert!((gas_corrected.boundary_layer.displacement_thickness[gas_last] / displacement - 1.0).abs() < 1e-3);
+        assert!(gas_corrected.inlet.wall_states().is_some());
+
+        let real_inlet = busemann::calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &ThermallyPerfectGas::air())
+            .expect("valid busemann inlet");
+        let real_corrected = correct_contour(&real_inlet, 1e7, None).expect("valid corrected contour");
+        assert!(real_corrected.iterations < MAX_CORRECTION_ITERS);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(calc_boundary_layer(&[0.0, 1.0], &[1.0, 1.0], &[2.0], 2.0, 1e6, None, 1.4).is_err());
+        assert!(calc_boundary_layer(&[0.0, 1.0], &[1.0, 1.0], &[2.0, 2.0], 2.0, -1.0, None, 1.4).is_err());
+    }
+}
@@ -1,21 +1,1207 @@
 #![allow(dead_code)]
 
-use crate::{inlet::Inlet, utils};
+use std::f64::consts::PI;
 
-pub fn calc_contour_from_machs(freestream_mach: f64, exit_mach: f64) -> Inlet {
-    let gamma: f64 = 1.4;
+use crate::{
+    error::InletError,
+    inlet::{Contour, Inlet, WallState},
+    moc::{self, CharacteristicNet, CharacteristicPoint, Streamline, StreamlineEnd},
+    taylormaccoll::{self, TaylorMaccollResult, VelocityVector},
+    utils::{
+        self,
+        atmosphere::FreestreamConditions,
+        equilibrium::{EquilibriumAir, EquilibriumState, Isentrope},
+        gas::{CaloricallyPerfectGas, GasModel},
+        normalshock::ShockJump,
+    },
+};
 
+// local error tolerance and largest step in theta when integrating from the terminal shock
+// to the leading edge, the step limit keeping the contour finely resolved for plotting,
+// truncation and the boundary layer
+const TAYLOR_MACCOLL_TOLERANCE: f64 = 1e-10;
+const TAYLOR_MACCOLL_MAX_STEP: f64 = 1e-3;
 
-    todo!()
+// points on the method of characteristics initial data line along the terminal shock, which
+// spans from near the focal point to well beyond the wall as multiples of the wall distance,
+// the outer extent being squared each time the wall streamline runs off the net
+const MOC_INITIAL_LINE_POINTS: usize = 400;
+const MOC_INNER_EXTENT: f64 = 1e-6;
+const MOC_OUTER_EXTENT: f64 = 20.0;
+const MOC_MAX_EXTENSIONS: usize = 2;
+
+// halvings towards the weak shock limit used to bracket the freestream mach number
+const MOC_BRACKET_STEPS: usize = 8;
+
+// halvings of the gap to the limiting velocity used to bracket the terminal shock in a
+// general gas model, or steps down in the logarithm of exit pressure in equilibrium air
+const TERMINAL_SHOCK_BRACKET_STEPS: usize = 50;
+const TERMINAL_SHOCK_PRESSURE_STEP: f64 = 0.05;
+
+#[derive(Debug, Clone)]
+pub struct TerminalShock {
+    // a struct to organise the flow state immediately ahead of the terminal conical shock
+    pub upstream_mach: f64,
+    pub shock_angle: f64,       // shock angle relative to the upstream flow
+    pub deflection_angle: f64,  // turning of the upstream flow back to the axial direction
+    pub conical_shock_angle: f64, // shock angle relative to the axis
+}
+
+pub fn calc_terminal_shock(exit_mach: f64, conical_shock_angle: f64, specific_heat_ratio: f64) -> Result<TerminalShock, InletError> {
+    // the flow behind the terminal shock is uniform and axial, so the shock angle
+    // relative to the downstream flow is the conical shock angle itself
+    if !utils::isentropic::valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    let normal_downstream_mach: f64 = exit_mach * conical_shock_angle.sin();
+    let min_normal_downstream_mach: f64 = ((specific_heat_ratio - 1.0) / (2.0 * specific_heat_ratio)).sqrt();
+    if normal_downstream_mach >= 1.0 || normal_downstream_mach <= min_normal_downstream_mach {
+        return Err(InletError::InvalidParameter { name: "conical shock angle", value: conical_shock_angle });
+    }
+
+    let normal_upstream_mach: f64 = utils::normalshock::calc_upstream_mach_from_downstream_mach(normal_downstream_mach, specific_heat_ratio)?;
+
+    // the tangential velocity is preserved across the shock, so the tangential mach
+    // number scales with the ratio of the speeds of sound
+    let temperature_ratio: f64 = utils::normalshock::calc_temperature_ratio(normal_upstream_mach, specific_heat_ratio)?;
+    let tangential_upstream_mach: f64 = exit_mach * conical_shock_angle.cos() * temperature_ratio.sqrt();
+
+    let upstream_mach: f64 = (normal_upstream_mach.powi(2) + tangential_upstream_mach.powi(2)).sqrt();
+    let shock_angle: f64 = normal_upstream_mach.atan2(tangential_upstream_mach);
+
+    Ok(TerminalShock {
+        upstream_mach,
+        shock_angle,
+        deflection_angle: shock_angle - conical_shock_angle,
+        conical_shock_angle,
+    })
+}
+
+fn walk_streamline(results: Vec<TaylorMaccollResult>) -> Result<(Contour, Vec<TaylorMaccollResult>), InletError> {
+    // the integration stops early once the flow is parallel to the axis again, and the
+    // streamline is walked back from the leading edge to the terminal shock
+    let leading_edge: &TaylorMaccollResult = results.last().ok_or(InletError::NoSolution("math error"))?;
+    if leading_edge.theta >= PI {
+        return Err(InletError::NoSolution("freestream conditions not reached"));
+    }
+    if results.iter().any(|result| {
+        !result.radial_distance.is_finite() || !result.velocity_vector.get_mach_number().is_finite()
+    }) {
+        return Err(InletError::NoSolution("math error"));
+    }
+
+    let mut contour: Contour = Contour::new();
+    let mut flowfield: Vec<TaylorMaccollResult> = Vec::with_capacity(results.len());
+    for result in results.into_iter().rev() {
+        contour.push_coords(
+            result.radial_distance * result.theta.cos(),
+            result.radial_distance * result.theta.sin(),
+        );
+        flowfield.push(result);
+    }
+    Ok((contour, flowfield))
+}
+
+pub fn calc_contour_from_terminal_shock(exit_mach: f64, conical_shock_angle: f64, specific_heat_ratio: f64) -> Result<Inlet, InletError> {
+    let terminal_shock: TerminalShock = calc_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)?;
+
+    // velocity ahead of the shock in spherical components, with theta measured from the
+    // downstream axis and the focal point of the conical flow at the origin
+    let initial_velocity_vector: VelocityVector = VelocityVector {
+        radial_component: terminal_shock.upstream_mach * terminal_shock.shock_angle.cos(),
+        tangential_component: -terminal_shock.upstream_mach * terminal_shock.shock_angle.sin(),
+    };
+
+    // start the wall streamline where the shock meets the wall at unit exit radius
+    let initial_r: f64 = 1.0 / conical_shock_angle.sin();
+
+    let results: Vec<TaylorMaccollResult> = taylormaccoll::solve_taylor_maccoll_adaptive(
+        initial_velocity_vector,
+        conical_shock_angle,
+        PI,
+        initial_r,
+        specific_heat_ratio,
+        TAYLOR_MACCOLL_TOLERANCE,
+        TAYLOR_MACCOLL_MAX_STEP,
+    )?;
+
+    let (contour, flowfield) = walk_streamline(results)?;
+
+    let freestream_mach: f64 = flowfield[0].velocity_vector.get_mach_number();
+
+    Ok(Inlet::new(
+        contour,
+        flowfield,
+        freestream_mach,
+        exit_mach,
+        specific_heat_ratio,
+        conical_shock_angle,
+        1.0,
+    ))
+}
+
+fn calc_conical_shock_angle_bounds(exit_mach: f64, specific_heat_ratio: f64) -> (f64, f64) {
+    // the conical shock angle lies between the strongest shock that still leaves a
+    // finite upstream mach number and the exit mach wave
+    let lower_bound: f64 = (((specific_heat_ratio - 1.0) / (2.0 * specific_heat_ratio)).sqrt() / exit_mach).asin() + 1e-6;
+    let upper_bound: f64 = (1.0 / exit_mach).asin() - 1e-6;
+    (lower_bound, upper_bound)
+}
+
+fn solve_conical_shock_angle(
+    exit_mach: f64,
+    specific_heat_ratio: f64,
+    freestream_mach_residual: impl Fn(f64) -> Result<f64, InletError>,
+) -> Result<f64, InletError> {
+    // root of the residual in the freestream mach number over the conical shock angle, where
+    // a failed integration means the shock is too strong to ever recover the freestream
+    let (lower_bound, upper_bound) = calc_conical_shock_angle_bounds(exit_mach, specific_heat_ratio);
+    let f = |conical_shock_angle: f64| freestream_mach_residual(conical_shock_angle).unwrap_or(f64::INFINITY);
+
+    if f(upper_bound) > 0.0 {
+        return Err(InletError::NoSolution("freestream mach too close to exit mach"));
+    }
+
+    Ok(utils::numerics::brent(&f, lower_bound, upper_bound, None, None)?.root)
+}
+
+pub fn calc_conical_shock_angle_from_machs(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    if !utils::isentropic::valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if freestream_mach <= exit_mach {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+
+    solve_conical_shock_angle(exit_mach, specific_heat_ratio, |conical_shock_angle: f64| {
+        calc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+            .map(|inlet| inlet.freestream_mach() - freestream_mach)
+    })
+}
+
+pub fn calc_contour_from_machs(freestream_mach: f64, exit_mach: f64, gas: &CaloricallyPerfectGas) -> Result<Inlet, InletError> {
+    // designs given only by mach numbers carry no temperature, so they hold for a calorically
+    // perfect gas, and a gas whose specific heats vary takes calc_contour_from_machs_for_gas
+    let specific_heat_ratio: f64 = gas.constant_specific_heat_ratio();
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_machs(freestream_mach, exit_mach, specific_heat_ratio)?;
+    calc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+}
+
+pub fn calc_conical_shock_angle_from_efficiency(exit_mach: f64, compression_efficiency: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the compression efficiency is the stagnation pressure ratio across the inlet, which
+    // is set entirely by the terminal shock as the compression ahead of it is isentropic
+    if !utils::isentropic::valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if compression_efficiency <= 0.0 || compression_efficiency >= 1.0 {
+        return Err(InletError::InvalidParameter { name: "compression efficiency", value: compression_efficiency });
+    }
+
+    let (lower_bound, upper_bound) = calc_conical_shock_angle_bounds(exit_mach, specific_heat_ratio);
+
+    // a weaker terminal shock always recovers more stagnation pressure
+    let f = |conical_shock_angle: f64| {
+        let stagnation_pressure_ratio: Result<f64, InletError> =
+            calc_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio).and_then(|shock| {
+                utils::obliqueshock::calc_stagnation_pressure_ratio(shock.upstream_mach, shock.shock_angle, specific_heat_ratio)
+            });
+        match stagnation_pressure_ratio {
+            Ok(value) => value - compression_efficiency,
+            Err(_) => f64::NEG_INFINITY,
+        }
+    };
+
+    if f(lower_bound) > 0.0 {
+        return Err(InletError::NoSolution("compression efficiency too low for exit mach"));
+    }
+    if f(upper_bound) < 0.0 {
+        return Err(InletError::NoSolution("compression efficiency too high for exit mach"));
+    }
+
+    Ok(utils::numerics::brent(&f, lower_bound, upper_bound, None, None)?.root)
+}
+
+pub fn calc_contour_from_exit_mach_and_efficiency(exit_mach: f64, compression_efficiency: f64, gas: &CaloricallyPerfectGas) -> Result<(Inlet, f64), InletError> {
+    // the freestream mach number follows from tracing the wall streamline upstream, and is
+    // returned with the inlet as the mach number the design converged to
+    let specific_heat_ratio: f64 = gas.constant_specific_heat_ratio();
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_efficiency(exit_mach, compression_efficiency, specific_heat_ratio)?;
+    let inlet: Inlet = calc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)?;
+    let freestream_mach: f64 = inlet.freestream_mach();
+    Ok((inlet, freestream_mach))
+}
+
+pub fn calc_terminal_shock_for_gas(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    stagnation_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<(TerminalShock, f64), InletError> {
+    // the terminal shock in a general gas model along with the static temperature ahead of
+    // it, found by searching on the upstream normal velocity, which with the stagnation
+    // enthalpy and the unchanged tangential velocity fixes the upstream state, until the
+    // shock slows it to the downstream normal velocity
+    let exit_temperature: f64 = stagnation_temperature *
+        utils::isentropic::calc_temperature_ratio_from_mach_for_gas(exit_mach, stagnation_temperature, gas)?;
+    let exit_specific_heat_ratio: f64 = gas.specific_heat_ratio(exit_temperature);
+    let perfect_gas_shock: TerminalShock = calc_terminal_shock(exit_mach, conical_shock_angle, exit_specific_heat_ratio)?;
+
+    let exit_velocity: f64 = exit_mach * gas.speed_of_sound(exit_temperature);
+    let normal_downstream_velocity: f64 = exit_velocity * conical_shock_angle.sin();
+    let tangential_velocity: f64 = exit_velocity * conical_shock_angle.cos();
+    let stagnation_enthalpy: f64 = gas.enthalpy(exit_temperature) + exit_velocity.powi(2) / 2.0;
+
+    let upstream_temperature = |normal_upstream_velocity: f64| {
+        gas.temperature_from_enthalpy(stagnation_enthalpy - (normal_upstream_velocity.powi(2) + tangential_velocity.powi(2)) / 2.0)
+    };
+    let sonic_excess = |normal_upstream_velocity: f64| {
+        upstream_temperature(normal_upstream_velocity)
+            .map_or(f64::NAN, |temperature| normal_upstream_velocity - gas.speed_of_sound(temperature))
+    };
+    let f = |normal_upstream_velocity: f64| {
+        let jump: Result<ShockJump, InletError> = upstream_temperature(normal_upstream_velocity).and_then(|temperature| {
+            // rounding at the sonic bound is taken as the vanishing shock
+            let normal_upstream_mach: f64 = (normal_upstream_velocity / gas.speed_of_sound(temperature)).max(1.0);
+            utils::normalshock::calc_shock_jump_for_gas(normal_upstream_mach, temperature, gas)
+        });
+        jump.map_or(f64::NAN, |jump| normal_upstream_velocity / jump.density_ratio / normal_downstream_velocity - 1.0)
+    };
+
+    // the upstream normal velocity lies between sonic and the limit where the upstream
+    // static enthalpy vanishes, and is bracketed outward from the perfect gas estimate
+    let max_velocity: f64 = (1.0 - 1e-9) * (2.0 * stagnation_enthalpy - tangential_velocity.powi(2)).sqrt();
+    let sonic_velocity: f64 = utils::numerics::brent(&sonic_excess, normal_downstream_velocity, max_velocity, Some(1e-12), None)?.root;
+    let estimate: f64 = normal_downstream_velocity * utils::normalshock::calc_density_ratio(
+        perfect_gas_shock.upstream_mach * perfect_gas_shock.shock_angle.sin(),
+        exit_specific_heat_ratio,
+    )?;
+    let mut upper_bound: f64 = (1.2 * estimate).min(0.5 * (sonic_velocity + max_velocity)).max(sonic_velocity);
+    let mut iterations: usize = 0;
+    // a nan where no shock jump exists also pushes the bound on
+    while f(upper_bound).is_nan() || f(upper_bound) >= 0.0 {
+        upper_bound += 0.5 * (max_velocity - upper_bound);
+        iterations += 1;
+        if iterations > TERMINAL_SHOCK_BRACKET_STEPS {
+            return Err(InletError::NoSolution("terminal shock not found for the gas model"));
+        }
+    }
+    let normal_upstream_velocity: f64 = utils::numerics::brent(&f, sonic_velocity, upper_bound, Some(1e-14), None)?.root;
+    let upstream_temperature: f64 = upstream_temperature(normal_upstream_velocity)?;
+
+    let upstream_mach: f64 = normal_upstream_velocity.hypot(tangential_velocity) / gas.speed_of_sound(upstream_temperature);
+    let shock_angle: f64 = normal_upstream_velocity.atan2(tangential_velocity);
+    Ok((
+        TerminalShock {
+            upstream_mach,
+            shock_angle,
+            deflection_angle: shock_angle - conical_shock_angle,
+            conical_shock_angle,
+        },
+        upstream_temperature,
+    ))
+}
+
+fn calc_streamline_for_gas(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    stagnation_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<(Contour, Vec<TaylorMaccollResult>), InletError> {
+    let (terminal_shock, upstream_temperature) = calc_terminal_shock_for_gas(exit_mach, conical_shock_angle, stagnation_temperature, gas)?;
+    let initial: TaylorMaccollResult = TaylorMaccollResult {
+        velocity_vector: VelocityVector {
+            radial_component: terminal_shock.upstream_mach * terminal_shock.shock_angle.cos(),
+            tangential_component: -terminal_shock.upstream_mach * terminal_shock.shock_angle.sin(),
+        },
+        radial_distance: 1.0 / conical_shock_angle.sin(),
+        theta: conical_shock_angle,
+    };
+    let results: Vec<TaylorMaccollResult> = taylormaccoll::solve_taylor_maccoll_for_gas(
+        initial,
+        PI,
+        upstream_temperature,
+        gas,
+        TAYLOR_MACCOLL_TOLERANCE,
+        TAYLOR_MACCOLL_MAX_STEP,
+    )?;
+    walk_streamline(results)
+}
+
+fn calc_wall_states_for_gas(
+    flowfield: &[TaylorMaccollResult],
+    stagnation_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<Vec<WallState>, InletError> {
+    // the flow along the wall is isentropic at the stagnation temperature, so each mach
+    // number fixes the static state relative to the freestream at the leading edge
+    let freestream_mach: f64 = flowfield.first().ok_or(InletError::NoSolution("math error"))?.velocity_vector.get_mach_number();
+    let freestream_temperature: f64 = stagnation_temperature *
+        utils::isentropic::calc_temperature_ratio_from_mach_for_gas(freestream_mach, stagnation_temperature, gas)?;
+    let freestream_pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach_for_gas(freestream_mach, stagnation_temperature, gas)?;
+    let freestream_sound_speed: f64 = gas.speed_of_sound(freestream_temperature);
+
+    let mut wall_states: Vec<WallState> = Vec::with_capacity(flowfield.len());
+    for result in flowfield {
+        let mach_number: f64 = result.velocity_vector.get_mach_number();
+        let temperature: f64 = stagnation_temperature *
+            utils::isentropic::calc_temperature_ratio_from_mach_for_gas(mach_number, stagnation_temperature, gas)?;
+        let pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach_for_gas(mach_number, stagnation_temperature, gas)?
+            / freestream_pressure_ratio;
+        let temperature_ratio: f64 = temperature / freestream_temperature;
+        wall_states.push(WallState {
+            pressure_ratio,
+            temperature_ratio,
+            density_ratio: pressure_ratio / temperature_ratio,
+            sound_speed_ratio: gas.speed_of_sound(temperature) / freestream_sound_speed,
+            temperature,
+            specific_heat_ratio: gas.specific_heat_ratio(temperature),
+            composition: None,
+        });
+    }
+    Ok(wall_states)
+}
+
+pub fn calc_contour_from_terminal_shock_for_gas(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    stagnation_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<Inlet, InletError> {
+    // as calc_contour_from_terminal_shock, with the speed of sound varying through the
+    // compression as the gas heats, and the gas state kept at each contour point
+    let (contour, flowfield) = calc_streamline_for_gas(exit_mach, conical_shock_angle, stagnation_temperature, gas)?;
+    let wall_states: Vec<WallState> = calc_wall_states_for_gas(&flowfield, stagnation_temperature, gas)?;
+
+    // the inlet records the specific heat ratio of the freestream
+    let freestream_mach: f64 = flowfield[0].velocity_vector.get_mach_number();
+    let freestream_specific_heat_ratio: f64 = wall_states[0].specific_heat_ratio;
+
+    Inlet::new(
+        contour,
+        flowfield,
+        freestream_mach,
+        exit_mach,
+        freestream_specific_heat_ratio,
+        conical_shock_angle,
+        1.0,
+    )
+    .with_wall_states(wall_states)
+}
+
+pub fn calc_conical_shock_angle_from_machs_for_gas(
+    freestream_mach: f64,
+    exit_mach: f64,
+    stagnation_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<f64, InletError> {
+    // bracketed as for a perfect gas at the exit specific heat ratio
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if freestream_mach <= exit_mach {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+    let exit_temperature: f64 = stagnation_temperature *
+        utils::isentropic::calc_temperature_ratio_from_mach_for_gas(exit_mach, stagnation_temperature, gas)?;
+    let specific_heat_ratio: f64 = gas.specific_heat_ratio(exit_temperature);
+
+    // only the streamline is needed while searching, the wall states following at the end
+    solve_conical_shock_angle(exit_mach, specific_heat_ratio, |conical_shock_angle: f64| {
+        calc_streamline_for_gas(exit_mach, conical_shock_angle, stagnation_temperature, gas)
+            .map(|(_, flowfield)| flowfield[0].velocity_vector.get_mach_number() - freestream_mach)
+    })
+}
+
+pub fn calc_contour_from_machs_for_gas(
+    freestream_mach: f64,
+    freestream_temperature: f64,
+    exit_mach: f64,
+    gas: &impl GasModel,
+) -> Result<Inlet, InletError> {
+    // the freestream static temperature sets the stagnation temperature held through the inlet
+    let stagnation_temperature: f64 = utils::isentropic::calc_stagnation_temperature(freestream_mach, freestream_temperature, gas)?;
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_machs_for_gas(freestream_mach, exit_mach, stagnation_temperature, gas)?;
+    calc_contour_from_terminal_shock_for_gas(exit_mach, conical_shock_angle, stagnation_temperature, gas)
+}
+
+pub fn calc_contour_from_freestream(freestream: &FreestreamConditions, exit_mach: f64, gas: &impl GasModel) -> Result<Inlet, InletError> {
+    // a flight condition in the standard atmosphere supplies the freestream temperature
+    calc_contour_from_machs_for_gas(freestream.mach_number, freestream.atmosphere.temperature, exit_mach, gas)
+}
+
+pub fn calc_terminal_shock_for_equilibrium_air(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    isentrope: &Isentrope,
+    air: &EquilibriumAir,
+) -> Result<(TerminalShock, EquilibriumState, EquilibriumState), InletError> {
+    // the terminal shock in equilibrium air with the states ahead of and behind it. A guess
+    // of the exit pressure fixes the exit state through the exit mach number and stagnation
+    // enthalpy, mass conservation then places the upstream state on the isentrope of the
+    // compression, and the exit pressure is searched for until momentum is also conserved
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if conical_shock_angle <= 0.0 || conical_shock_angle >= (1.0 / exit_mach).asin() {
+        return Err(InletError::InvalidParameter { name: "conical shock angle", value: conical_shock_angle });
+    }
+    let stagnation_enthalpy: f64 = isentrope.stagnation_enthalpy();
+    let upstream_enthalpy = |normal_velocity: f64, tangential_velocity: f64| {
+        stagnation_enthalpy - (normal_velocity.powi(2) + tangential_velocity.powi(2)) / 2.0
+    };
+
+    // upstream normal velocity, downstream normal velocity and tangential velocity, the
+    // upstream flow lying on the supersonic side of the normal sonic point
+    let velocities = |exit: &EquilibriumState| -> Result<(f64, f64, f64), InletError> {
+        let exit_velocity: f64 = exit_mach * air.speed_of_sound(exit)?;
+        let normal_downstream_velocity: f64 = exit_velocity * conical_shock_angle.sin();
+        let tangential_velocity: f64 = exit_velocity * conical_shock_angle.cos();
+        let mass_flux: f64 = exit.density * normal_downstream_velocity;
+        let max_velocity: f64 = (1.0 - 1e-9) * (2.0 * (stagnation_enthalpy - isentrope.min_enthalpy()) - tangential_velocity.powi(2)).sqrt();
+        let sonic_excess = |normal_velocity: f64| {
+            isentrope.sound_speed_at_enthalpy(upstream_enthalpy(normal_velocity, tangential_velocity))
+                .map_or(f64::NAN, |sound_speed| normal_velocity - sound_speed)
+        };
+        let sonic_velocity: f64 = utils::numerics::brent(&sonic_excess, 0.0, max_velocity, Some(1e-12), None)?.root;
+        let f = |normal_velocity: f64| {
+            isentrope.state_at_enthalpy(upstream_enthalpy(normal_velocity, tangential_velocity))
+                .map_or(f64::NAN, |state| state.density * normal_velocity / mass_flux - 1.0)
+        };
+        // a vanishing shock leaves the normal flow at the sonic point, where the mass flux
+        // peaks and may fall just short of the exit mass flux through rounding
+        let normal_upstream_velocity: f64 = if f(sonic_velocity) <= 0.0 {
+            sonic_velocity
+        } else {
+            utils::numerics::brent(&f, sonic_velocity, max_velocity, Some(1e-14), None)?.root
+        };
+        Ok((normal_upstream_velocity, normal_downstream_velocity, tangential_velocity))
+    };
+    let momentum_excess = |log_pressure: f64| -> Result<(f64, EquilibriumState, EquilibriumState, f64, f64), InletError> {
+        let exit: EquilibriumState = air.state_from_mach(log_pressure.exp(), stagnation_enthalpy, exit_mach)?;
+        let (normal_upstream_velocity, normal_downstream_velocity, tangential_velocity) = velocities(&exit)?;
+        let upstream: EquilibriumState = isentrope.state_at_enthalpy(upstream_enthalpy(normal_upstream_velocity, tangential_velocity))?;
+        let excess: f64 = (upstream.pressure - exit.pressure
+            - exit.density * normal_downstream_velocity * (normal_downstream_velocity - normal_upstream_velocity)) / exit.pressure;
+        Ok((excess, upstream, exit, normal_upstream_velocity, tangential_velocity))
+    };
+    let f = |log_pressure: f64| momentum_excess(log_pressure).map_or(f64::NAN, |(excess, ..)| excess);
+
+    // without a shock the exit state lies on the isentrope, and the shock loss lowers the
+    // exit pressure below that, where the upstream pressure is too high to balance
+    let upper_bound: f64 = isentrope.state_at_enthalpy(isentrope.enthalpy_at_mach(exit_mach)?)?.pressure.ln();
+    let mut lower_bound: f64 = upper_bound;
+    let mut iterations: usize = 0;
+    while f(lower_bound).is_nan() || f(lower_bound) <= 0.0 {
+        lower_bound -= TERMINAL_SHOCK_PRESSURE_STEP;
+        iterations += 1;
+        if iterations > TERMINAL_SHOCK_BRACKET_STEPS {
+            return Err(InletError::NoSolution("terminal shock not found in equilibrium air"));
+        }
+    }
+    let log_pressure: f64 = if f(upper_bound) >= 0.0 {
+        upper_bound
+    } else {
+        utils::numerics::brent(&f, lower_bound, upper_bound, Some(1e-13), None)?.root
+    };
+    let (_, upstream, exit, normal_upstream_velocity, tangential_velocity) = momentum_excess(log_pressure)?;
+
+    let upstream_mach: f64 = normal_upstream_velocity.hypot(tangential_velocity) / isentrope.sound_speed_at_enthalpy(upstream.enthalpy)?;
+    let shock_angle: f64 = normal_upstream_velocity.atan2(tangential_velocity);
+    Ok((
+        TerminalShock {
+            upstream_mach,
+            shock_angle,
+            deflection_angle: shock_angle - conical_shock_angle,
+            conical_shock_angle,
+        },
+        upstream,
+        exit,
+    ))
+}
+
+fn calc_streamline_for_equilibrium_air(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    isentrope: &Isentrope,
+    air: &EquilibriumAir,
+) -> Result<(Contour, Vec<TaylorMaccollResult>), InletError> {
+    let (terminal_shock, ..) = calc_terminal_shock_for_equilibrium_air(exit_mach, conical_shock_angle, isentrope, air)?;
+    let initial: TaylorMaccollResult = TaylorMaccollResult {
+        velocity_vector: VelocityVector {
+            radial_component: terminal_shock.upstream_mach * terminal_shock.shock_angle.cos(),
+            tangential_component: -terminal_shock.upstream_mach * terminal_shock.shock_angle.sin(),
+        },
+        radial_distance: 1.0 / conical_shock_angle.sin(),
+        theta: conical_shock_angle,
+    };
+    let results: Vec<TaylorMaccollResult> = taylormaccoll::solve_taylor_maccoll_for_equilibrium_air(
+        initial,
+        PI,
+        isentrope,
+        TAYLOR_MACCOLL_TOLERANCE,
+        TAYLOR_MACCOLL_MAX_STEP,
+    )?;
+    walk_streamline(results)
+}
+
+fn calc_wall_states_for_equilibrium_air(flowfield: &[TaylorMaccollResult], isentrope: &Isentrope) -> Result<Vec<WallState>, InletError> {
+    // each mach number fixes the enthalpy on the isentrope, and with it the state and the
+    // composition the air relaxes to, the isentropic exponent being a^2 rho / p
+    let state_at_mach = |mach_number: f64| -> Result<(EquilibriumState, f64), InletError> {
+        let enthalpy: f64 = isentrope.enthalpy_at_mach(mach_number)?;
+        Ok((isentrope.state_at_enthalpy(enthalpy)?, isentrope.sound_speed_at_enthalpy(enthalpy)?))
+    };
+    let freestream_mach: f64 = flowfield.first().ok_or(InletError::NoSolution("math error"))?.velocity_vector.get_mach_number();
+    let (freestream, freestream_sound_speed) = state_at_mach(freestream_mach)?;
+
+    let mut wall_states: Vec<WallState> = Vec::with_capacity(flowfield.len());
+    for result in flowfield {
+        let (state, sound_speed) = state_at_mach(result.velocity_vector.get_mach_number())?;
+        wall_states.push(WallState {
+            pressure_ratio: state.pressure / freestream.pressure,
+            temperature_ratio: state.temperature / freestream.temperature,
+            density_ratio: state.density / freestream.density,
+            sound_speed_ratio: sound_speed / freestream_sound_speed,
+            temperature: state.temperature,
+            specific_heat_ratio: sound_speed.powi(2) * state.density / state.pressure,
+            composition: Some(state.composition),
+        });
+    }
+    Ok(wall_states)
+}
+
+pub fn calc_contour_from_terminal_shock_for_equilibrium_air(
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    isentrope: &Isentrope,
+    air: &EquilibriumAir,
+) -> Result<Inlet, InletError> {
+    // as calc_contour_from_terminal_shock, with the compression following the isentrope of
+    // equilibrium air so the speed of sound reflects vibration and dissociation, and the
+    // equilibrium state kept at each contour point
+    let (contour, flowfield) = calc_streamline_for_equilibrium_air(exit_mach, conical_shock_angle, isentrope, air)?;
+    let wall_states: Vec<WallState> = calc_wall_states_for_equilibrium_air(&flowfield, isentrope)?;
+
+    // the inlet records the isentropic exponent of the freestream
+    let freestream_mach: f64 = flowfield[0].velocity_vector.get_mach_number();
+    let freestream_isentropic_exponent: f64 = wall_states[0].specific_heat_ratio;
+
+    Inlet::new(
+        contour,
+        flowfield,
+        freestream_mach,
+        exit_mach,
+        freestream_isentropic_exponent,
+        conical_shock_angle,
+        1.0,
+    )
+    .with_wall_states(wall_states)
 }
 
-pub fn calc_total_pressure_ratio(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
-    todo!()
+pub fn calc_conical_shock_angle_from_machs_for_equilibrium_air(
+    freestream_mach: f64,
+    exit_mach: f64,
+    isentrope: &Isentrope,
+    air: &EquilibriumAir,
+) -> Result<f64, InletError> {
+    // bracketed as for a perfect gas at the isentropic exponent of the exit
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if freestream_mach <= exit_mach {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+    let exit: EquilibriumState = isentrope.state_at_enthalpy(isentrope.enthalpy_at_mach(exit_mach)?)?;
+    let specific_heat_ratio: f64 = air.isentropic_exponent(&exit)?;
+
+    // only the streamline is needed while searching, the wall states following at the end
+    solve_conical_shock_angle(exit_mach, specific_heat_ratio, |conical_shock_angle: f64| {
+        calc_streamline_for_equilibrium_air(exit_mach, conical_shock_angle, isentrope, air)
+            .map(|(_, flowfield)| flowfield[0].velocity_vector.get_mach_number() - freestream_mach)
+    })
+}
+
+pub fn calc_freestream_isentrope(
+    freestream_mach: f64,
+    freestream_pressure: f64,
+    freestream_temperature: f64,
+    air: &EquilibriumAir,
+) -> Result<(EquilibriumState, Isentrope), InletError> {
+    // the freestream state and the isentrope it is compressed along up to its stagnation
+    // enthalpy, which every equilibrium design from the freestream conditions shares
+    if freestream_mach.is_nan() || freestream_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+    let freestream: EquilibriumState = air.state(freestream_pressure, freestream_temperature)?;
+    let velocity: f64 = freestream_mach * air.speed_of_sound(&freestream)?;
+    let isentrope: Isentrope = air.isentrope(&freestream, freestream.enthalpy + velocity.powi(2) / 2.0)?;
+    Ok((freestream, isentrope))
+}
+
+pub fn calc_contour_from_machs_for_equilibrium_air(
+    freestream_mach: f64,
+    freestream_pressure: f64,
+    freestream_temperature: f64,
+    exit_mach: f64,
+    air: &EquilibriumAir,
+) -> Result<Inlet, InletError> {
+    let (_, isentrope) = calc_freestream_isentrope(freestream_mach, freestream_pressure, freestream_temperature, air)?;
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_machs_for_equilibrium_air(freestream_mach, exit_mach, &isentrope, air)?;
+    calc_contour_from_terminal_shock_for_equilibrium_air(exit_mach, conical_shock_angle, &isentrope, air)
+}
+
+pub fn calc_contour_from_freestream_for_equilibrium_air(
+    freestream: &FreestreamConditions,
+    exit_mach: f64,
+    air: &EquilibriumAir,
+) -> Result<Inlet, InletError> {
+    calc_contour_from_machs_for_equilibrium_air(
+        freestream.mach_number,
+        freestream.atmosphere.pressure,
+        freestream.atmosphere.temperature,
+        exit_mach,
+        air,
+    )
+}
+
+fn calc_moc_contour(exit_mach: f64, conical_shock_angle: f64, specific_heat_ratio: f64, initial_line_points: usize) -> Result<Inlet, InletError> {
+    let terminal_shock: TerminalShock = calc_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)?;
+    if initial_line_points < 3 {
+        return Err(InletError::InvalidParameter { name: "number of initial line points", value: initial_line_points as f64 });
+    }
+
+    // the net is marched against the flow, which is a valid isentropic flow once mirrored
+    // about x = 0, so the converging flow ahead of the shock becomes a diverging one
+    let wall_distance: f64 = 1.0 / conical_shock_angle.sin();
+    let spacing: f64 = (MOC_OUTER_EXTENT / MOC_INNER_EXTENT).ln() / (initial_line_points - 1) as f64;
+    let wall_index: usize = ((1.0 / MOC_INNER_EXTENT).ln() / spacing).round() as usize;
+    let mut outer_points: usize = initial_line_points - 1 - wall_index;
+
+    let mut extensions: usize = 0;
+    let streamline: Streamline = loop {
+        // geometric spacing keeps every cell the same shape in the self similar conical flow
+        let initial_line: Vec<CharacteristicPoint> = (0..=wall_index + outer_points)
+            .map(|index| {
+                let distance: f64 = wall_distance * (spacing * (index as f64 - wall_index as f64)).exp();
+                CharacteristicPoint::new(
+                    -distance * conical_shock_angle.cos(),
+                    distance * conical_shock_angle.sin(),
+                    terminal_shock.deflection_angle,
+                    terminal_shock.upstream_mach,
+                    specific_heat_ratio,
+                )
+            })
+            .collect::<Result<Vec<CharacteristicPoint>, InletError>>()?;
+
+        let net: CharacteristicNet = moc::solve_initial_value_line(&initial_line, specific_heat_ratio)?;
+        let streamline: Streamline = moc::trace_streamline(&net, wall_index, specific_heat_ratio)?;
+        if streamline.end != StreamlineEnd::UpperBoundary {
+            break streamline;
+        }
+        if extensions == MOC_MAX_EXTENSIONS {
+            return Err(InletError::NoSolution("wall streamline too long for the characteristic net"));
+        }
+        extensions += 1;
+        outer_points *= 2;
+    };
+    let streamline: Vec<CharacteristicPoint> = streamline.points;
+
+    // mirror back and walk the streamline from the leading edge to the terminal shock,
+    // storing the state in the same spherical form as the taylor maccoll solution
+    let mut contour: Contour = Contour::new();
+    let mut flowfield: Vec<TaylorMaccollResult> = Vec::with_capacity(streamline.len());
+    for point in streamline.iter().rev() {
+        let x: f64 = -point.x;
+        let flow_angle: f64 = -point.flow_angle;
+        let theta: f64 = point.r.atan2(x);
+        contour.push_coords(x, point.r);
+        flowfield.push(TaylorMaccollResult {
+            velocity_vector: VelocityVector {
+                radial_component: point.mach_number * (theta - flow_angle).cos(),
+                tangential_component: point.mach_number * (flow_angle - theta).sin(),
+            },
+            radial_distance: (x.powi(2) + point.r.powi(2)).sqrt(),
+            theta,
+        });
+    }
+
+    let freestream_mach: f64 = streamline[streamline.len() - 1].mach_number;
+
+    Ok(Inlet::new(
+        contour,
+        flowfield,
+        freestream_mach,
+        exit_mach,
+        specific_heat_ratio,
+        conical_shock_angle,
+        1.0,
+    ))
+}
+
+pub fn calc_moc_contour_from_terminal_shock(exit_mach: f64, conical_shock_angle: f64, specific_heat_ratio: f64) -> Result<Inlet, InletError> {
+    calc_moc_contour(exit_mach, conical_shock_angle, specific_heat_ratio, MOC_INITIAL_LINE_POINTS)
+}
+
+pub fn calc_moc_conical_shock_angle_from_machs(freestream_mach: f64, exit_mach: f64, gamma: f64) -> Result<f64, InletError> {
+    // the conical shock angle is found with the characteristic net alone, so the
+    // contour is independent of the taylor maccoll solution
+    if !utils::isentropic::valid_specific_heat_ratio(gamma) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio: gamma });
+    }
+    if exit_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: exit_mach });
+    }
+    if freestream_mach <= exit_mach {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+
+    // the compression ahead of the shock is isentropic, so the mach number there can never
+    // exceed the freestream mach number, which gives a cheap lower bound on the shock angle
+    let (_, max_shock_angle) = calc_conical_shock_angle_bounds(exit_mach, gamma);
+    let lower_bound: f64 = solve_conical_shock_angle(exit_mach, gamma, |conical_shock_angle: f64| {
+        calc_terminal_shock(exit_mach, conical_shock_angle, gamma).map(|shock| shock.upstream_mach - freestream_mach)
+    })?;
+
+    let f = |conical_shock_angle: f64| {
+        calc_moc_contour_from_terminal_shock(exit_mach, conical_shock_angle, gamma)
+            .map(|inlet| inlet.freestream_mach() - freestream_mach)
+    };
+
+    // the weak shock limit gives arbitrarily long contours, so halve the distance towards
+    // it only as far as needed to bracket the design, tightening the lower bound on the way
+    let mut lower_bound: f64 = lower_bound;
+    let mut upper_bound: Option<f64> = None;
+    for _ in 0..MOC_BRACKET_STEPS {
+        let conical_shock_angle: f64 = 0.5 * (lower_bound + max_shock_angle);
+        if f(conical_shock_angle)? < 0.0 {
+            upper_bound = Some(conical_shock_angle);
+            break;
+        }
+        lower_bound = conical_shock_angle;
+    }
+    let upper_bound: f64 = upper_bound.ok_or(InletError::NoSolution("freestream mach too close to exit mach"))?;
+
+    let f = |conical_shock_angle: f64| f(conical_shock_angle).unwrap_or(f64::NAN);
+
+    // every evaluation marches a full net, so use a method that evaluates once per iteration
+    utils::numerics::false_position(&f, lower_bound, upper_bound, Some(1e-4), None)
 }
 
-pub fn calc_static_temperature_ratio(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_moc_contour_from_machs(freestream_mach: f64, exit_mach: f64, gas: &CaloricallyPerfectGas) -> Result<Inlet, InletError> {
+    let specific_heat_ratio: f64 = gas.constant_specific_heat_ratio();
+    let conical_shock_angle: f64 = calc_moc_conical_shock_angle_from_machs(freestream_mach, exit_mach, specific_heat_ratio)?;
+    calc_moc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+}
+
+pub fn calc_moc_contour_from_exit_mach_and_efficiency(exit_mach: f64, compression_efficiency: f64, gas: &CaloricallyPerfectGas) -> Result<(Inlet, f64), InletError> {
+    let specific_heat_ratio: f64 = gas.constant_specific_heat_ratio();
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_efficiency(exit_mach, compression_efficiency, specific_heat_ratio)?;
+    let inlet: Inlet = calc_moc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)?;
+    let freestream_mach: f64 = inlet.freestream_mach();
+    Ok((inlet, freestream_mach))
+}
+
+#[derive(Debug, Clone)]
+pub struct BusemannPerformance {
+    // a struct to organise the exit to freestream ratios across the whole inlet
+    pub total_pressure_ratio: f64,
+    pub static_pressure_ratio: f64,
+    pub static_temperature_ratio: f64,
+    pub density_ratio: f64,
+}
+
+pub fn calc_performance_from_terminal_shock(freestream_mach: f64, exit_mach: f64, conical_shock_angle: f64, specific_heat_ratio: f64) -> Result<BusemannPerformance, InletError> {
+    // the compression up to the terminal shock is isentropic, so the shock alone sets the
+    // stagnation pressure loss and the stagnation temperature is conserved throughout
+    let terminal_shock: TerminalShock = calc_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)?;
+    let total_pressure_ratio: f64 = utils::obliqueshock::calc_stagnation_pressure_ratio(
+        terminal_shock.upstream_mach,
+        terminal_shock.shock_angle,
+        specific_heat_ratio,
+    )?;
+    calc_performance_from_total_pressure_ratio(freestream_mach, exit_mach, total_pressure_ratio, specific_heat_ratio)
+}
+
+pub fn calc_performance_from_total_pressure_ratio(freestream_mach: f64, exit_mach: f64, total_pressure_ratio: f64, specific_heat_ratio: f64) -> Result<BusemannPerformance, InletError> {
+    // static ratios follow from the end mach numbers once the stagnation pressure loss is
+    // known, whatever mix of shocks caused it
+    let freestream_pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach(freestream_mach, specific_heat_ratio)?;
+    let exit_pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach(exit_mach, specific_heat_ratio)?;
+    let static_pressure_ratio: f64 = exit_pressure_ratio * total_pressure_ratio * (1.0 / freestream_pressure_ratio);
+    let static_temperature_ratio: f64 = calc_static_temperature_ratio(freestream_mach, exit_mach, specific_heat_ratio)?;
+
+    Ok(BusemannPerformance {
+        total_pressure_ratio,
+        static_pressure_ratio,
+        static_temperature_ratio,
+        density_ratio: static_pressure_ratio * (1.0 / static_temperature_ratio),
+    })
+}
+
+pub fn calc_performance(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<BusemannPerformance, InletError> {
+    let conical_shock_angle: f64 = calc_conical_shock_angle_from_machs(freestream_mach, exit_mach, specific_heat_ratio)?;
+    calc_performance_from_terminal_shock(freestream_mach, exit_mach, conical_shock_angle, specific_heat_ratio)
+}
+
+pub fn calc_total_pressure_ratio(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    Ok(calc_performance(freestream_mach, exit_mach, specific_heat_ratio)?.total_pressure_ratio)
+}
+
+pub fn calc_static_temperature_ratio(freestream_mach: f64, exit_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // exit over freestream static temperature, the stagnation temperature being conserved.
+    // Before the inlet performance was added this returned the inverse, freestream over exit
     let freestream_temperature_ratio: f64 = utils::isentropic::calc_temperature_ratio_from_mach(freestream_mach, specific_heat_ratio)?;
     let exit_temperature_ratio: f64 = utils::isentropic::calc_temperature_ratio_from_mach(exit_mach, specific_heat_ratio)?;
-    let static_temperature_ratio: f64 = freestream_temperature_ratio * (1.0 / exit_temperature_ratio);
+    let static_temperature_ratio: f64 = exit_temperature_ratio * (1.0 / freestream_temperature_ratio);
     Ok(static_temperature_ratio)
-}
\ No newline at end of file
+}
+
+pub fn calc_performance_for_gas(
+    freestream_mach: f64,
+    freestream_temperature: f64,
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    gas: &impl GasModel,
+) -> Result<BusemannPerformance, InletError> {
+    // as calc_performance_from_terminal_shock, with the ratios following the gas model
+    let stagnation_temperature: f64 = utils::isentropic::calc_stagnation_temperature(freestream_mach, freestream_temperature, gas)?;
+    let (terminal_shock, upstream_temperature) = calc_terminal_shock_for_gas(exit_mach, conical_shock_angle, stagnation_temperature, gas)?;
+    let total_pressure_ratio: f64 = utils::obliqueshock::calc_shock_jump_for_gas(
+        terminal_shock.upstream_mach,
+        terminal_shock.shock_angle,
+        upstream_temperature,
+        gas,
+    )?.stagnation_pressure_ratio;
+
+    calc_performance_from_total_pressure_ratio_for_gas(freestream_mach, freestream_temperature, exit_mach, total_pressure_ratio, gas)
+}
+
+pub fn calc_performance_from_total_pressure_ratio_for_gas(
+    freestream_mach: f64,
+    freestream_temperature: f64,
+    exit_mach: f64,
+    total_pressure_ratio: f64,
+    gas: &impl GasModel,
+) -> Result<BusemannPerformance, InletError> {
+    // as calc_performance_from_total_pressure_ratio, with the ratios following the gas model
+    let stagnation_temperature: f64 = utils::isentropic::calc_stagnation_temperature(freestream_mach, freestream_temperature, gas)?;
+    let exit_temperature_ratio: f64 = utils::isentropic::calc_temperature_ratio_from_mach_for_gas(exit_mach, stagnation_temperature, gas)?;
+    let freestream_pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach_for_gas(freestream_mach, stagnation_temperature, gas)?;
+    let exit_pressure_ratio: f64 = utils::isentropic::calc_pressure_ratio_from_mach_for_gas(exit_mach, stagnation_temperature, gas)?;
+    let static_pressure_ratio: f64 = exit_pressure_ratio * total_pressure_ratio / freestream_pressure_ratio;
+    let static_temperature_ratio: f64 = exit_temperature_ratio * stagnation_temperature / freestream_temperature;
+
+    Ok(BusemannPerformance {
+        total_pressure_ratio,
+        static_pressure_ratio,
+        static_temperature_ratio,
+        density_ratio: static_pressure_ratio / static_temperature_ratio,
+    })
+}
+
+pub fn calc_performance_for_equilibrium_air(
+    freestream_mach: f64,
+    freestream_pressure: f64,
+    freestream_temperature: f64,
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    air: &EquilibriumAir,
+) -> Result<(BusemannPerformance, EquilibriumState, EquilibriumState), InletError> {
+    // as calc_performance_for_gas, also returning the freestream and exit states so the
+    // composition change through the inlet can be read off
+    let (freestream, isentrope) = calc_freestream_isentrope(freestream_mach, freestream_pressure, freestream_temperature, air)?;
+    let (_, _, exit) = calc_terminal_shock_for_equilibrium_air(exit_mach, conical_shock_angle, &isentrope, air)?;
+    let stagnation_enthalpy: f64 = isentrope.stagnation_enthalpy();
+    let freestream_stagnation: EquilibriumState = air.stagnation_state(&freestream, (2.0 * (stagnation_enthalpy - freestream.enthalpy)).sqrt())?;
+    let exit_stagnation: EquilibriumState = air.stagnation_state(&exit, (2.0 * (stagnation_enthalpy - exit.enthalpy)).sqrt())?;
+
+    Ok((
+        BusemannPerformance {
+            total_pressure_ratio: exit_stagnation.pressure / freestream_stagnation.pressure,
+            static_pressure_ratio: exit.pressure / freestream.pressure,
+            static_temperature_ratio: exit.temperature / freestream.temperature,
+            density_ratio: exit.density / freestream.density,
+        },
+        freestream,
+        exit,
+    ))
+}
+
+pub fn calc_performance_from_freestream(
+    freestream: &FreestreamConditions,
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    gas: &impl GasModel,
+) -> Result<BusemannPerformance, InletError> {
+    calc_performance_for_gas(freestream.mach_number, freestream.atmosphere.temperature, exit_mach, conical_shock_angle, gas)
+}
+
+pub fn calc_performance_from_freestream_for_equilibrium_air(
+    freestream: &FreestreamConditions,
+    exit_mach: f64,
+    conical_shock_angle: f64,
+    air: &EquilibriumAir,
+) -> Result<(BusemannPerformance, EquilibriumState, EquilibriumState), InletError> {
+    calc_performance_for_equilibrium_air(
+        freestream.mach_number,
+        freestream.atmosphere.pressure,
+        freestream.atmosphere.temperature,
+        exit_mach,
+        conical_shock_angle,
+        air,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{equilibrium::Species, gas::{CaloricallyPerfectGas, ThermallyPerfectGas}};
+
+    #[test]
+    fn test_calc_terminal_shock() {
+        // the terminal shock must turn the upstream flow onto the axis at the exit mach number
+        let exit_mach = 2.5;
+        let specific_heat_ratio = 1.4;
+        let conical_shock_angle = 0.3;
+        let shock = calc_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+            .expect("valid terminal shock");
+        let deflection_angle = utils::obliqueshock::calc_deflection_angle(shock.upstream_mach, shock.shock_angle, specific_heat_ratio)
+            .expect("valid deflection angle");
+        assert!((deflection_angle - shock.deflection_angle).abs() < 1e-6);
+        assert!((shock.shock_angle - shock.deflection_angle - conical_shock_angle).abs() < 1e-12);
+        assert!(shock.upstream_mach > exit_mach);
+    }
+
+    #[test]
+    fn test_calc_contour_from_machs() {
+        // the converged design recovers the requested freestream mach number
+        let inlet = calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        assert!((inlet.freestream_mach() - 6.0).abs() < 1e-3);
+        assert_eq!(inlet.contour().len(), inlet.flowfield().len());
+
+        // the contour runs from the leading edge to unit exit radius at the terminal shock
+        let x_coords = inlet.contour().x_coords();
+        let y_coords = inlet.contour().y_coords();
+        assert!(x_coords.windows(2).all(|pair| pair[1] > pair[0]));
+        assert!((y_coords[y_coords.len() - 1] - 1.0).abs() < 1e-9);
+        assert!(y_coords[0] > 1.0);
+    }
+
+    #[test]
+    fn test_calc_contour_from_exit_mach_and_efficiency() {
+        // the terminal shock of the converged design delivers the requested efficiency
+        let exit_mach = 2.5;
+        let specific_heat_ratio = 1.4;
+        let (inlet, freestream_mach) = calc_contour_from_exit_mach_and_efficiency(exit_mach, 0.9, &CaloricallyPerfectGas::air())
+            .expect("valid busemann inlet");
+        assert!((freestream_mach - inlet.freestream_mach()).abs() < 1e-12);
+        let shock = calc_terminal_shock(exit_mach, inlet.terminal_shock_angle(), specific_heat_ratio)
+            .expect("valid terminal shock");
+        let stagnation_pressure_ratio = utils::obliqueshock::calc_stagnation_pressure_ratio(shock.upstream_mach, shock.shock_angle, specific_heat_ratio)
+            .expect("valid stagnation pressure ratio");
+        assert!((stagnation_pressure_ratio - 0.9).abs() < 1e-6);
+
+        // the same design is recovered when specified by its freestream mach number
+        let matched = calc_contour_from_machs(freestream_mach, exit_mach, &CaloricallyPerfectGas::air())
+            .expect("valid busemann inlet");
+        assert!((matched.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_calc_moc_contour_from_terminal_shock() {
+        // the characteristic net must reproduce the taylor maccoll contour for the same shock,
+        // to within the resolution of the net near the leading edge mach cone
+        let exit_mach = 2.5;
+        let specific_heat_ratio = 1.4;
+        let conical_shock_angle = 0.3;
+        let taylor_maccoll = calc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+            .expect("valid taylor maccoll contour");
+        let characteristics = calc_moc_contour_from_terminal_shock(exit_mach, conical_shock_angle, specific_heat_ratio)
+            .expect("valid method of characteristics contour");
+        assert!((characteristics.freestream_mach() - taylor_maccoll.freestream_mach()).abs() / taylor_maccoll.freestream_mach() < 2e-2);
+
+        // compare wall radii at the axial stations both contours cover
+        let tm_x = taylor_maccoll.contour().x_coords();
+        let tm_y = taylor_maccoll.contour().y_coords();
+        let moc_x = characteristics.contour().x_coords();
+        let moc_y = characteristics.contour().y_coords();
+        for (x, y) in moc_x.iter().zip(moc_y).filter(|(x, _)| **x > tm_x[0]) {
+            let index = tm_x.iter().position(|tm| tm >= x).expect("station within the taylor maccoll contour");
+            let fraction = (x - tm_x[index - 1]) / (tm_x[index] - tm_x[index - 1]);
+            let expected = tm_y[index - 1] + fraction * (tm_y[index] - tm_y[index - 1]);
+            assert!((y - expected).abs() / expected < 2e-2);
+        }
+        assert!((moc_y[moc_y.len() - 1] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calc_moc_contour_from_exit_mach_and_efficiency() {
+        // both methods share the terminal shock, so the freestream mach numbers must agree
+        let (taylor_maccoll, taylor_maccoll_mach) = calc_contour_from_exit_mach_and_efficiency(2.5, 0.9, &CaloricallyPerfectGas::air())
+            .expect("valid taylor maccoll contour");
+        let (characteristics, characteristics_mach) = calc_moc_contour_from_exit_mach_and_efficiency(2.5, 0.9, &CaloricallyPerfectGas::air())
+            .expect("valid method of characteristics contour");
+        assert!((characteristics.terminal_shock_angle() - taylor_maccoll.terminal_shock_angle()).abs() < 1e-9);
+        assert!((characteristics_mach - taylor_maccoll_mach).abs() / taylor_maccoll_mach < 2e-2);
+    }
+
+    #[test]
+    fn test_calc_performance() {
+        // the performance ratios must be consistent with the equation of state
+        let freestream_mach = 6.0;
+        let exit_mach = 2.5;
+        let specific_heat_ratio = 1.4;
+        let performance = calc_performance(freestream_mach, exit_mach, specific_heat_ratio)
+            .expect("valid busemann performance");
+        assert!(performance.total_pressure_ratio > 0.0 && performance.total_pressure_ratio < 1.0);
+        assert!(performance.static_temperature_ratio > 1.0);
+        assert!(performance.static_pressure_ratio > performance.static_temperature_ratio);
+        assert!((performance.density_ratio * performance.static_temperature_ratio - performance.static_pressure_ratio).abs() < 1e-9);
+
+        // for mach 6 to 2.5, t3 / t1 = (1 + 0.2 * 36) / (1 + 0.2 * 6.25) = 8.2 / 2.25
+        let expected = 8.2 / 2.25;
+        assert!((performance.static_temperature_ratio - expected).abs() < 1e-9);
+
+        let total_pressure_ratio = calc_total_pressure_ratio(freestream_mach, exit_mach, specific_heat_ratio)
+            .expect("valid total pressure ratio");
+        assert!((total_pressure_ratio - performance.total_pressure_ratio).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_terminal_shock_for_gas() {
+        // a calorically perfect gas reproduces the closed form terminal shock
+        let (shock, upstream_temperature) = calc_terminal_shock_for_gas(2.5, 0.3, 1000.0, &CaloricallyPerfectGas::air())
+            .expect("valid terminal shock");
+        let perfect = calc_terminal_shock(2.5, 0.3, 1.4).expect("valid terminal shock");
+        assert!((shock.upstream_mach - perfect.upstream_mach).abs() < 1e-10);
+        assert!((shock.shock_angle - perfect.shock_angle).abs() < 1e-10);
+        let expected_temperature: f64 = 1000.0 * utils::isentropic::calc_temperature_ratio_from_mach(perfect.upstream_mach, 1.4).expect("valid mach");
+        assert!((upstream_temperature - expected_temperature).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_calc_contour_from_machs_for_gas() {
+        // at mach 8 vibration lowers gamma through the compression, so reaching the same exit
+        // mach takes a markedly stronger compression than the perfect gas predicts
+        let air = ThermallyPerfectGas::air();
+        let perfect = CaloricallyPerfectGas::air();
+        let real_inlet = calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &air).expect("valid busemann inlet");
+        let ideal_inlet = calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &perfect).expect("valid busemann inlet");
+        assert!((real_inlet.freestream_mach() - 8.0).abs() < 1e-6);
+        assert!((ideal_inlet.freestream_mach() - 8.0).abs() < 1e-6);
+        let real = calc_performance_for_gas(8.0, 220.0, 3.0, real_inlet.terminal_shock_angle(), &air).expect("valid performance");
+        let ideal = calc_performance_for_gas(8.0, 220.0, 3.0, ideal_inlet.terminal_shock_angle(), &perfect).expect("valid performance");
+        let closed_form = calc_performance(8.0, 3.0, 1.4).expect("valid performance");
+        assert!((ideal.static_temperature_ratio - closed_form.static_temperature_ratio).abs() < 1e-9);
+        assert!((ideal.total_pressure_ratio - closed_form.total_pressure_ratio).abs() < 1e-6);
+        assert!(real.static_pressure_ratio > 1.2 * ideal.static_pressure_ratio);
+        assert!(real.density_ratio > 1.2 * ideal.density_ratio);
+
+        // the wall states follow the closed form relations in a perfect gas and start from
+        // the freestream
+        let states = ideal_inlet.wall_states().expect("gas model wall states");
+        assert_eq!(states.len(), ideal_inlet.contour().len());
+        assert!((ideal_inlet.freestream_temperature().expect("freestream temperature") - 220.0).abs() < 1e-3);
+        let last = states.len() - 1;
+        let wall_mach: f64 = ideal_inlet.flowfield()[last].velocity_vector.get_mach_number();
+        let expected_temperature_ratio: f64 = calc_static_temperature_ratio(8.0, wall_mach, 1.4).expect("valid mach");
+        assert!((states[last].temperature_ratio - expected_temperature_ratio).abs() < 1e-9);
+        assert!((states[last].pressure_ratio - expected_temperature_ratio.powf(3.5)).abs() < 1e-6 * states[last].pressure_ratio);
+        let real_states = real_inlet.wall_states().expect("gas model wall states");
+        assert!(real_states[last.min(real_states.len() - 1)].specific_heat_ratio < 1.4);
+    }
+
+    #[test]
+    fn test_calc_contour_from_machs_for_equilibrium_air() {
+        // at mach 8 the compressed air barely dissociates, so equilibrium air follows the
+        // thermally perfect gas
+        let air = EquilibriumAir::air();
+        let gas = ThermallyPerfectGas::air();
+        let inlet = calc_contour_from_machs_for_equilibrium_air(8.0, 1000.0, 220.0, 3.0, &air).expect("valid busemann inlet");
+        let gas_inlet = calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &gas).expect("valid busemann inlet");
+        assert!((inlet.freestream_mach() - 8.0).abs() < 1e-6);
+        assert!((inlet.terminal_shock_angle() - gas_inlet.terminal_shock_angle()).abs() < 1e-3);
+        let (performance, freestream, exit) = calc_performance_for_equilibrium_air(8.0, 1000.0, 220.0, 3.0, inlet.terminal_shock_angle(), &air)
+            .expect("valid performance");
+        let gas_performance = calc_performance_for_gas(8.0, 220.0, 3.0, gas_inlet.terminal_shock_angle(), &gas).expect("valid performance");
+        assert!((performance.total_pressure_ratio / gas_performance.total_pressure_ratio - 1.0).abs() < 1e-3);
+        assert!((performance.static_pressure_ratio / gas_performance.static_pressure_ratio - 1.0).abs() < 1e-2);
+        assert!((exit.pressure / freestream.pressure - performance.static_pressure_ratio).abs() < 1e-9);
+        assert!(exit.composition.mole_fraction(Species::AtomicOxygen) < 1e-6);
+
+        // at mach 15 the exit air is partly dissociated, which absorbs energy and takes a
+        // stronger compression to reach the same exit mach number
+        let inlet = calc_contour_from_machs_for_equilibrium_air(15.0, 1000.0, 220.0, 3.0, &air).expect("valid busemann inlet");
+        let gas_inlet = calc_contour_from_machs_for_gas(15.0, 220.0, 3.0, &gas).expect("valid busemann inlet");
+        let (performance, _, exit) = calc_performance_for_equilibrium_air(15.0, 1000.0, 220.0, 3.0, inlet.terminal_shock_angle(), &air)
+            .expect("valid performance");
+        let gas_performance = calc_performance_for_gas(15.0, 220.0, 3.0, gas_inlet.terminal_shock_angle(), &gas).expect("valid performance");
+        assert!(exit.composition.mole_fraction(Species::NitricOxide) > 0.05);
+        assert!(exit.composition.mole_fraction(Species::AtomicOxygen) > 0.005);
+        assert!(performance.static_pressure_ratio > 1.2 * gas_performance.static_pressure_ratio);
+
+        // the wall states follow the equilibrium composition up to the terminal shock, whose
+        // upstream state is the last of them
+        let states = inlet.wall_states().expect("equilibrium wall states");
+        assert!((inlet.freestream_temperature().expect("freestream temperature") - 220.0).abs() < 1e-3);
+        assert!(states[0].composition.is_some_and(|composition| composition.mole_fraction(Species::NitricOxide) < 1e-6));
+        let last = &states[states.len() - 1];
+        let (freestream, isentrope) = calc_freestream_isentrope(15.0, 1000.0, 220.0, &air).expect("valid freestream");
+        let (_, upstream, _) = calc_terminal_shock_for_equilibrium_air(3.0, inlet.terminal_shock_angle(), &isentrope, &air)
+            .expect("valid terminal shock");
+        assert!((last.pressure_ratio / (upstream.pressure / freestream.pressure) - 1.0).abs() < 1e-3);
+        assert!((last.temperature / upstream.temperature - 1.0).abs() < 1e-3);
+        assert!(last.composition.is_some_and(|composition| composition.mole_fraction(Species::NitricOxide) > 0.01));
+        assert!(last.specific_heat_ratio < 1.3);
+    }
+
+    #[test]
+    fn test_calc_contour_from_freestream() {
+        // a flight at 30 km with 40 kPa dynamic pressure, cold enough that equilibrium air
+        // and the thermally perfect gas give the same inlet
+        let freestream = FreestreamConditions::from_altitude_and_dynamic_pressure(30000.0, 4e4).expect("valid freestream");
+        let gas = ThermallyPerfectGas::air();
+        let inlet = calc_contour_from_freestream(&freestream, 2.5, &gas).expect("valid busemann inlet");
+        assert!((inlet.freestream_mach() - freestream.mach_number).abs() < 1e-6);
+        let performance = calc_performance_from_freestream(&freestream, 2.5, inlet.terminal_shock_angle(), &gas).expect("valid performance");
+        let air = EquilibriumAir::air();
+        let equilibrium_inlet = calc_contour_from_freestream_for_equilibrium_air(&freestream, 2.5, &air).expect("valid busemann inlet");
+        assert!((equilibrium_inlet.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-3);
+        let (equilibrium_performance, freestream_state, _) =
+            calc_performance_from_freestream_for_equilibrium_air(&freestream, 2.5, equilibrium_inlet.terminal_shock_angle(), &air)
+                .expect("valid performance");
+        assert!((freestream_state.pressure / freestream.atmosphere.pressure - 1.0).abs() < 1e-9);
+        assert!((equilibrium_performance.static_pressure_ratio / performance.static_pressure_ratio - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_calc_static_temperature_ratio() {
+        // the ratio is the exit static temperature over the freestream static temperature
+        let static_temperature_ratio = calc_static_temperature_ratio(6.0, 2.5, 1.4)
+            .expect("valid static temperature ratio");
+        assert!((static_temperature_ratio - 8.2 / 2.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(calc_contour_from_machs(2.0, 2.5, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_contour_from_machs(6.0, 1.0, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_terminal_shock(2.5, PI / 2.0, 1.4).is_err());
+        assert!(calc_contour_from_exit_mach_and_efficiency(2.5, 1.2, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_contour_from_exit_mach_and_efficiency(2.5, 0.0, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_moc_contour_from_machs(2.0, 2.5, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_contour_from_machs_for_equilibrium_air(2.0, 1000.0, 220.0, 2.5, &EquilibriumAir::air()).is_err());
+        assert!(calc_contour_from_machs_for_equilibrium_air(8.0, -1.0, 220.0, 3.0, &EquilibriumAir::air()).is_err());
+        assert!(calc_freestream_isentrope(0.5, 1000.0, 220.0, &EquilibriumAir::air()).is_err());
+    }
+}
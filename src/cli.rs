@@ -0,0 +1,614 @@
+use inlet_designer::{
+    boundarylayer,
+    busemann::{self, BusemannPerformance},
+    icfa,
+    truncatedbusemann::{self, TruncatedBusemann, Truncation},
+    utils::{
+        atmosphere::FreestreamConditions,
+        gas::{CaloricallyPerfectGas, GasModel, AIR_GAS_CONSTANT},
+        species::{GasMixture, GasSpecies},
+    },
+    Inlet, InletError,
+};
+
+// the boundary corrected design reports the performance of the contour it started from
+const INVISCID_PERFORMANCE: &str = "performance of the inviscid contour, without boundary layer losses";
+
+pub const USAGE: &str = "usage:
+    inlet-designer [design | analyze | export] [inlet type] [flags]
+    inlet-designer run [spec file]...
+    inlet-designer [inlet type]                  (interactive prompts)
+
+inlet types:
+    busemann | b, icfa, truncated-busemann | tb, boundary-corrected-busemann | bcb
+
+commands:
+    design    design the inlet, print a summary and write any --out files
+    analyze   design the inlet and print its performance only
+    export    design the inlet and write the --out files only
+
+flags:
+    --freestream-mach <mach>         --exit-mach <mach>
+    --altitude <km>                  --dynamic-pressure <Pa> (instead of the freestream mach)
+    --efficiency <p0 ratio>          --gamma <ratio> (default 1.4)
+    --method <tm | moc>              (default tm)
+    --freestream-temperature <K>     follow the gas model through the compression, as --altitude does,
+                                     for all but icfa inlets
+    --gas-constant <J/(kg K)>        (default 287.05, air)
+    --gas <species[:fraction],...>   air, n2, o2, co2, co, h2o, h2, he or ar, instead of --gamma and
+                                     --gas-constant, needs --freestream-temperature or --altitude
+    --incident-shock-angle <deg>     icfa
+    --truncation-angle <deg>         --truncation-fraction <fraction>
+    --reynolds-number <re>           --wall-temperature-ratio <ratio>
+    --scale <exit radius>            (default 1)
+    --out <file>                     .csv, .svg or .png, may be repeated
+
+spec files:
+    inlet-designer run <spec file>...
+    each [name] section holds one design as key = value lines, where the keys are
+    the flags above without dashes plus inlet = <inlet type>";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InletType {
+    Busemann,
+    Icfa,
+    TruncatedBusemann,
+    BoundaryCorrectedBusemann,
+}
+
+impl InletType {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "b" | "busemann" => Some(InletType::Busemann),
+            "icfa" => Some(InletType::Icfa),
+            "tb" | "truncated-busemann" => Some(InletType::TruncatedBusemann),
+            "bcb" | "boundary-corrected-busemann" => Some(InletType::BoundaryCorrectedBusemann),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InletType::Busemann => "busemann",
+            InletType::Icfa => "icfa",
+            InletType::TruncatedBusemann => "truncated-busemann",
+            InletType::BoundaryCorrectedBusemann => "boundary-corrected-busemann",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    TaylorMaccoll,
+    MethodOfCharacteristics,
+}
+
+#[derive(Debug, Clone)]
+pub struct DesignSpec {
+    // everything needed to reproduce a design, whichever way it was given
+    pub name: String,
+    pub inlet_type: InletType,
+    pub method: Method,
+    pub freestream_mach: Option<f64>,
+    pub exit_mach: Option<f64>,
+    pub efficiency: Option<f64>,
+    pub altitude: Option<f64>, // geometric, in metres
+    pub dynamic_pressure: Option<f64>,
+    pub freestream_temperature: Option<f64>, // static, in kelvin
+    pub gamma: Option<f64>,
+    pub gas_constant: Option<f64>,
+    pub gas: Option<GasMixture>,
+    pub incident_shock_angle: Option<f64>,
+    pub truncation: Option<Truncation>,
+    pub reynolds_number: Option<f64>,
+    pub wall_temperature_ratio: Option<f64>,
+    pub scale: f64, // exit radius of the finished inlet
+    pub outputs: Vec<String>,
+}
+
+impl DesignSpec {
+    pub fn new(inlet_type: InletType) -> Self {
+        DesignSpec {
+            name: inlet_type.name().to_string(),
+            inlet_type,
+            method: Method::TaylorMaccoll,
+            freestream_mach: None,
+            exit_mach: None,
+            efficiency: None,
+            altitude: None,
+            dynamic_pressure: None,
+            freestream_temperature: None,
+            gamma: None,
+            gas_constant: None,
+            gas: None,
+            incident_shock_angle: None,
+            truncation: None,
+            reynolds_number: None,
+            wall_temperature_ratio: None,
+            scale: 1.0,
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        // keys match the command line flags without their leading dashes
+        let number = || value.parse::<f64>().map_err(|_| format!("invalid value '{}' for {}", value, key));
+        match key {
+            "freestream-mach" => self.freestream_mach = Some(number()?),
+            "exit-mach" => self.exit_mach = Some(number()?),
+            "efficiency" => self.efficiency = Some(number()?),
+            "altitude" => self.altitude = Some(number()? * 1000.0),
+            "dynamic-pressure" => self.dynamic_pressure = Some(number()?),
+            "freestream-temperature" => self.freestream_temperature = Some(number()?),
+            "gamma" => self.gamma = Some(number()?),
+            "gas-constant" => self.gas_constant = Some(number()?),
+            "gas" => self.gas = Some(parse_gas(value)?),
+            "method" => {
+                self.method = match value {
+                    "tm" | "taylor-maccoll" => Method::TaylorMaccoll,
+                    "moc" | "method-of-characteristics" => Method::MethodOfCharacteristics,
+                    _ => return Err(format!("unknown method '{}', use tm or moc", value)),
+                }
+            }
+            "incident-shock-angle" => self.incident_shock_angle = Some(number()?.to_radians()),
+            "truncation-angle" => self.truncation = Some(Truncation::WallAngle(number()?.to_radians())),
+            "truncation-fraction" => self.truncation = Some(Truncation::LengthFraction(number()?)),
+            "reynolds-number" => self.reynolds_number = Some(number()?),
+            "wall-temperature-ratio" => self.wall_temperature_ratio = Some(number()?),
+            "scale" => self.scale = number()?,
+            "out" => self.outputs.push(value.to_string()),
+            _ => return Err(format!("unknown option '{}'", key)),
+        }
+        Ok(())
+    }
+
+    pub fn freestream_mach(&self) -> Result<Option<f64>, String> {
+        // given directly, or from the dynamic pressure at the altitude in the standard atmosphere
+        match (self.altitude, self.freestream_mach, self.dynamic_pressure) {
+            (_, Some(_), Some(_)) => Err("give only one of --freestream-mach and --dynamic-pressure".to_string()),
+            (None, _, Some(_)) => Err("--dynamic-pressure needs --altitude".to_string()),
+            (Some(altitude), None, Some(dynamic_pressure)) => {
+                let freestream: FreestreamConditions = FreestreamConditions::from_altitude_and_dynamic_pressure(altitude, dynamic_pressure)
+                    .map_err(|err| format!("invalid flight condition: {}", err))?;
+                Ok(Some(freestream.mach_number))
+            }
+            (_, freestream_mach, None) => Ok(freestream_mach),
+        }
+    }
+
+    pub fn freestream(&self) -> Result<Option<FreestreamConditions>, String> {
+        // the flight condition in the standard atmosphere when an altitude is given
+        let altitude: f64 = match self.altitude {
+            Some(altitude) => altitude,
+            None => return Ok(None),
+        };
+        let freestream_mach: f64 = required(self.freestream_mach()?, "freestream-mach or --dynamic-pressure")?;
+        FreestreamConditions::from_altitude_and_mach(altitude, freestream_mach)
+            .map(Some)
+            .map_err(|err| format!("invalid flight condition: {}", err))
+    }
+
+    pub fn perfect_gas(&self) -> Result<CaloricallyPerfectGas, String> {
+        // air unless given otherwise, and the only gas a design without a temperature can follow
+        if self.gas.is_some() {
+            return Err("--gas needs --freestream-temperature or --altitude".to_string());
+        }
+        CaloricallyPerfectGas::new(self.gamma.unwrap_or(1.4), self.gas_constant.unwrap_or(AIR_GAS_CONSTANT))
+            .map_err(|err| err.to_string())
+    }
+}
+
+fn parse_gas(value: &str) -> Result<GasMixture, String> {
+    // a single species, or a mixture as species:mole fraction pairs
+    let species = |name: &str| GasSpecies::parse(name.trim()).ok_or(format!("unknown gas species '{}'", name.trim()));
+    if !value.contains(':') {
+        return Ok(GasMixture::pure(species(value)?));
+    }
+    let mut mole_fractions: Vec<(GasSpecies, f64)> = Vec::new();
+    for component in value.split(',') {
+        let (name, fraction) = component.split_once(':').ok_or(format!("expected species:fraction, got '{}'", component))?;
+        let fraction: f64 = fraction.trim().parse().map_err(|_| format!("invalid mole fraction '{}'", fraction.trim()))?;
+        mole_fractions.push((species(name)?, fraction));
+    }
+    GasMixture::new(&mole_fractions).map_err(|err| err.to_string())
+}
+
+pub struct Design {
+    // a designed inlet with its performance and a short description for printing
+    pub inlet: Inlet,
+    pub performance: BusemannPerformance,
+    pub summary: String,
+}
+
+fn required(value: Option<f64>, name: &str) -> Result<f64, String> {
+    value.ok_or(format!("missing --{}", name))
+}
+
+fn design_busemann(spec: &DesignSpec, gas: &impl GasModel) -> Result<Inlet, String> {
+    // busemann contour from the exit mach and either the freestream mach or the efficiency
+    let exit_mach: f64 = required(spec.exit_mach, "exit-mach")?;
+    if spec.altitude.is_some() || spec.freestream_temperature.is_some() {
+        return design_busemann_for_gas(spec, exit_mach, gas);
+    }
+    let gas: CaloricallyPerfectGas = spec.perfect_gas()?;
+    let inlet: Result<Inlet, InletError> = match (spec.freestream_mach()?, spec.efficiency, spec.method) {
+        (Some(_), Some(_), _) => return Err("give only one of --freestream-mach and --efficiency".to_string()),
+        (None, None, _) => return Err("missing --freestream-mach or --efficiency".to_string()),
+        (Some(freestream_mach), None, Method::TaylorMaccoll) => busemann::calc_contour_from_machs(freestream_mach, exit_mach, &gas),
+        (Some(freestream_mach), None, Method::MethodOfCharacteristics) => busemann::calc_moc_contour_from_machs(freestream_mach, exit_mach, &gas),
+        (None, Some(efficiency), Method::TaylorMaccoll) => busemann::calc_contour_from_exit_mach_and_efficiency(exit_mach, efficiency, &gas).map(|(inlet, _)| inlet),
+        (None, Some(efficiency), Method::MethodOfCharacteristics) => busemann::calc_moc_contour_from_exit_mach_and_efficiency(exit_mach, efficiency, &gas).map(|(inlet, _)| inlet),
+    };
+    inlet.map_err(|err| format!("failed to design busemann inlet: {}", err))
+}
+
+fn design_busemann_for_gas(spec: &DesignSpec, exit_mach: f64, gas: &impl GasModel) -> Result<Inlet, String> {
+    // a flight condition or a freestream temperature lets the taylor maccoll solution follow
+    // the gas model, which only the freestream mach number design supports
+    if spec.efficiency.is_some() {
+        return Err("--efficiency cannot be combined with --altitude or --freestream-temperature".to_string());
+    }
+    if spec.method == Method::MethodOfCharacteristics {
+        return Err("--method moc cannot be combined with --altitude or --freestream-temperature".to_string());
+    }
+    let inlet: Result<Inlet, InletError> = match (spec.freestream()?, spec.freestream_temperature) {
+        (Some(_), Some(_)) => return Err("give only one of --altitude and --freestream-temperature".to_string()),
+        (None, None) => return Err("missing --altitude or --freestream-temperature".to_string()),
+        (Some(freestream), None) => busemann::calc_contour_from_freestream(&freestream, exit_mach, gas),
+        (None, Some(freestream_temperature)) => {
+            let freestream_mach: f64 = required(spec.freestream_mach()?, "freestream-mach")?;
+            busemann::calc_contour_from_machs_for_gas(freestream_mach, freestream_temperature, exit_mach, gas)
+        }
+    };
+    inlet.map_err(|err| format!("failed to design busemann inlet: {}", err))
+}
+
+fn busemann_performance(spec: &DesignSpec, inlet: &Inlet, gas: &impl GasModel) -> Result<BusemannPerformance, String> {
+    let performance: Result<BusemannPerformance, InletError> = match (spec.freestream()?, inlet.freestream_temperature()) {
+        (Some(freestream), Some(_)) => busemann::calc_performance_from_freestream(
+            &freestream,
+            inlet.exit_mach(),
+            inlet.terminal_shock_angle(),
+            gas,
+        ),
+        (None, Some(freestream_temperature)) => busemann::calc_performance_for_gas(
+            inlet.freestream_mach(),
+            freestream_temperature,
+            inlet.exit_mach(),
+            inlet.terminal_shock_angle(),
+            gas,
+        ),
+        (_, None) => busemann::calc_performance_from_terminal_shock(
+            inlet.freestream_mach(),
+            inlet.exit_mach(),
+            inlet.terminal_shock_angle(),
+            inlet.specific_heat_ratio(),
+        ),
+    };
+    performance.map_err(|err| format!("failed to evaluate performance: {}", err))
+}
+
+fn truncate_busemann(full: &Inlet, truncation: Truncation, gas: &impl GasModel) -> Result<(TruncatedBusemann, BusemannPerformance), String> {
+    // the stagnation pressure lost at both shocks sets the static ratios at the exit
+    let truncated: Result<(TruncatedBusemann, BusemannPerformance), InletError> = match full.freestream_temperature() {
+        Some(freestream_temperature) => {
+            truncatedbusemann::truncate_for_gas(full, truncation, gas).and_then(|truncated| {
+                let performance: BusemannPerformance = busemann::calc_performance_from_total_pressure_ratio_for_gas(
+                    full.freestream_mach(),
+                    freestream_temperature,
+                    full.exit_mach(),
+                    truncated.total_pressure_ratio,
+                    gas,
+                )?;
+                Ok((truncated, performance))
+            })
+        }
+        None => truncatedbusemann::truncate(full, truncation).and_then(|truncated| {
+            let performance: BusemannPerformance = busemann::calc_performance_from_total_pressure_ratio(
+                full.freestream_mach(),
+                full.exit_mach(),
+                truncated.total_pressure_ratio,
+                full.specific_heat_ratio(),
+            )?;
+            Ok((truncated, performance))
+        }),
+    };
+    truncated.map_err(|err| format!("failed to truncate busemann inlet: {}", err))
+}
+
+pub fn design(spec: &DesignSpec) -> Result<Design, String> {
+    // every inlet is designed at unit exit radius and scaled afterwards, following a named gas
+    // or mixture when one is given and a calorically perfect gas otherwise
+    if spec.gas.is_some() && (spec.gamma.is_some() || spec.gas_constant.is_some()) {
+        return Err("give only one of --gas and --gamma or --gas-constant".to_string());
+    }
+    let mut design: Design = match &spec.gas {
+        Some(mixture) => design_unit_inlet(spec, mixture)?,
+        None => design_unit_inlet(spec, &spec.perfect_gas()?)?,
+    };
+    if let Some(freestream) = spec.freestream()? {
+        design.summary += &format!(
+            "\nflight condition: altitude {:.3} km, static pressure {:.4} Pa, static temperature {:.4} K, velocity {:.2} m/s, dynamic pressure {:.1} Pa",
+            freestream.altitude / 1000.0, freestream.atmosphere.pressure, freestream.atmosphere.temperature,
+            freestream.velocity(), freestream.dynamic_pressure()
+        );
+    }
+    if spec.scale == 1.0 {
+        return Ok(design);
+    }
+    let inlet: Inlet = design.inlet.scale(spec.scale).map_err(|err| format!("failed to scale inlet: {}", err))?;
+    Ok(Design { inlet, ..design })
+}
+
+fn design_unit_inlet(spec: &DesignSpec, gas: &impl GasModel) -> Result<Design, String> {
+    match spec.inlet_type {
+        InletType::Busemann => {
+            let inlet: Inlet = design_busemann(spec, gas)?;
+            let performance: BusemannPerformance = busemann_performance(spec, &inlet, gas)?;
+            let summary: String = format!(
+                "busemann inlet designed: freestream mach {:.4}, exit mach {:.4}, terminal shock angle {:.4} deg",
+                inlet.freestream_mach(), inlet.exit_mach(), inlet.terminal_shock_angle().to_degrees()
+            );
+            Ok(Design { inlet, performance, summary })
+        }
+        InletType::Icfa => {
+            // the icfa relations hold for a perfect gas, so a static temperature has no use here
+            if spec.freestream_temperature.is_some() || spec.gas.is_some() || spec.altitude.is_some() {
+                return Err("icfa inlets take --gamma rather than --gas, --freestream-temperature or --altitude".to_string());
+            }
+            let freestream_mach: f64 = required(spec.freestream_mach()?, "freestream-mach")?;
+            let incident_shock_angle: f64 = required(spec.incident_shock_angle, "incident-shock-angle")?;
+            let icfa = icfa::calc_icfa_from_shock_angle(freestream_mach, incident_shock_angle, spec.perfect_gas()?.constant_specific_heat_ratio())
+                .map_err(|err| format!("failed to design icfa inlet: {}", err))?;
+            let summary: String = format!(
+                "icfa inlet designed: freestream mach {:.4}, exit mach {:.4}, incident deflection angle {:.4} deg, reflected deflection angle {:.4} deg",
+                icfa.inlet.freestream_mach(), icfa.inlet.exit_mach(),
+                icfa.incident_deflection_angle.to_degrees(), icfa.reflected_deflection_angle.to_degrees()
+            );
+            Ok(Design { inlet: icfa.inlet, performance: icfa.performance, summary })
+        }
+        InletType::TruncatedBusemann => {
+            let truncation: Truncation = spec.truncation.ok_or("missing --truncation-angle or --truncation-fraction")?;
+            let full: Inlet = design_busemann(spec, gas)?;
+            let (truncated, performance) = truncate_busemann(&full, truncation, gas)?;
+            let summary: String = format!(
+                "truncated busemann inlet designed: leading edge angle {:.4} deg, leading edge shock angle {:.4} deg, capture area ratio {:.4}, length saving {:.4}",
+                truncated.leading_edge_deflection_angle.to_degrees(), truncated.leading_edge_shock_angle.to_degrees(),
+                truncated.capture_area_ratio, truncated.length_saving
+            );
+            Ok(Design { inlet: truncated.inlet, performance, summary })
+        }
+        InletType::BoundaryCorrectedBusemann => {
+            let reynolds_number: f64 = required(spec.reynolds_number, "reynolds-number")?;
+            let inviscid: Inlet = design_busemann(spec, gas)?;
+            let performance: BusemannPerformance = busemann_performance(spec, &inviscid, gas)?;
+            let corrected = boundarylayer::correct_contour(&inviscid, reynolds_number, spec.wall_temperature_ratio)
+                .map_err(|err| format!("failed to correct busemann inlet: {}", err))?;
+            let last_index: usize = corrected.boundary_layer.displacement_thickness.len() - 1;
+            let summary: String = format!(
+                "boundary corrected busemann inlet designed in {} iterations: exit displacement thickness {:.6}, exit momentum thickness {:.6}\n{}",
+                corrected.iterations,
+                corrected.boundary_layer.displacement_thickness[last_index],
+                corrected.boundary_layer.momentum_thickness[last_index],
+                INVISCID_PERFORMANCE
+            );
+            Ok(Design { inlet: corrected.inlet, performance, summary })
+        }
+    }
+}
+
+pub fn write_outputs(inlet: &Inlet, outputs: &[String]) -> Result<(), String> {
+    // csv for the contour data, anything else is handed to the plotter
+    for output in outputs {
+        let result: Result<(), InletError> = if output.to_lowercase().ends_with(".csv") {
+            inlet.export_csv(output)
+        } else {
+            inlet.plot(output)
+        };
+        result.map_err(|err| format!("failed to write {}: {}", output, err))?;
+    }
+    Ok(())
+}
+
+pub fn format_performance(performance: &BusemannPerformance) -> String {
+    format!(
+        "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+        performance.total_pressure_ratio, performance.static_pressure_ratio,
+        performance.static_temperature_ratio, performance.density_ratio
+    )
+}
+
+fn parse_flags(spec: &mut DesignSpec, flags: &[String]) -> Result<(), String> {
+    // flags as --key value or --key=value
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        let flag_name: &str = flag.strip_prefix("--").ok_or(format!("unexpected argument '{}'", flag))?;
+        match flag_name.split_once('=') {
+            Some((key, value)) => spec.set(key, value)?,
+            None => {
+                let value: &String = flags.next().ok_or(format!("missing value for --{}", flag_name))?;
+                spec.set(flag_name, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn run(command: &str, args: &[String]) -> Result<(), String> {
+    // non-interactive entry point, args being everything after the command
+    let inlet_type_name: &String = args.first().ok_or("missing inlet type")?;
+    let inlet_type: InletType = InletType::parse(inlet_type_name).ok_or(format!("unknown inlet type '{}'", inlet_type_name))?;
+    let mut spec: DesignSpec = DesignSpec::new(inlet_type);
+    parse_flags(&mut spec, &args[1..])?;
+
+    match command {
+        "design" => {
+            let design: Design = design(&spec)?;
+            println!("{}", design.summary);
+            println!("{}", format_performance(&design.performance));
+            write_outputs(&design.inlet, &spec.outputs)?;
+            for output in &spec.outputs {
+                println!("wrote {}", output);
+            }
+        }
+        "analyze" => {
+            let design: Design = design(&spec)?;
+            if spec.inlet_type == InletType::BoundaryCorrectedBusemann {
+                println!("{}", INVISCID_PERFORMANCE);
+            }
+            println!("{}", format_performance(&design.performance));
+        }
+        "export" => {
+            if spec.outputs.is_empty() {
+                return Err("missing --out".to_string());
+            }
+            let design: Design = design(&spec)?;
+            write_outputs(&design.inlet, &spec.outputs)?;
+        }
+        _ => return Err(format!("unknown command '{}'", command)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        // both flag forms fill the spec, with angles converted to radians
+        let mut spec = DesignSpec::new(InletType::TruncatedBusemann);
+        parse_flags(&mut spec, &args("--freestream-mach 6 --exit-mach=2.5 --truncation-angle 5 --method moc --out a.csv --out a.svg"))
+            .expect("valid flags");
+        assert_eq!(spec.freestream_mach, Some(6.0));
+        assert_eq!(spec.exit_mach, Some(2.5));
+        assert_eq!(spec.method, Method::MethodOfCharacteristics);
+        assert!(matches!(spec.truncation, Some(Truncation::WallAngle(angle)) if (angle - 5.0_f64.to_radians()).abs() < 1e-12));
+        assert_eq!(spec.outputs, vec!["a.csv".to_string(), "a.svg".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_gas() {
+        // a named gas or a mixture is kept as the gas model of the design
+        let mut spec = DesignSpec::new(InletType::Busemann);
+        parse_flags(&mut spec, &args("--gas helium")).expect("valid flags");
+        let gas = spec.gas.as_ref().expect("mixture");
+        assert!((gas.specific_heat_ratio(1000.0) - 5.0 / 3.0).abs() < 1e-12);
+        assert!((gas.gas_constant() - 2077.26).abs() < 1e-1);
+        parse_flags(&mut spec, &args("--gas n2:0.79,o2:0.21")).expect("valid flags");
+        let gas = spec.gas.as_ref().expect("mixture");
+        assert!((gas.gas_constant() - 288.5).abs() < 1.0);
+        assert!(gas.specific_heat_ratio(1500.0) < 1.34);
+
+        // the design follows the mixture through the compression
+        parse_flags(&mut spec, &args("--freestream-mach 8 --exit-mach 3 --freestream-temperature 220")).expect("valid flags");
+        let mixture_design = design(&spec).expect("valid design");
+        let mixture = GasMixture::new(&[(GasSpecies::Nitrogen, 0.79), (GasSpecies::Oxygen, 0.21)]).expect("valid mixture");
+        let inlet = busemann::calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &mixture).expect("valid busemann inlet");
+        assert!((mixture_design.inlet.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-12);
+        let perfect = busemann::calc_contour_from_machs(8.0, 3.0, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        assert!((mixture_design.inlet.terminal_shock_angle() - perfect.terminal_shock_angle()).abs() > 1e-3);
+    }
+
+    #[test]
+    fn test_flight_condition() {
+        // the dynamic pressure at altitude sets the freestream mach number
+        let mut spec = DesignSpec::new(InletType::Busemann);
+        parse_flags(&mut spec, &args("--altitude 25 --dynamic-pressure 64244 --exit-mach 2.5")).expect("valid flags");
+        let freestream_mach: f64 = spec.freestream_mach().expect("valid flight condition").expect("freestream mach");
+        assert!((freestream_mach - 6.0).abs() < 1e-3);
+        let flight_design = design(&spec).expect("valid design");
+        assert!((flight_design.inlet.freestream_mach() - freestream_mach).abs() < 1e-6);
+        assert!(flight_design.summary.contains("flight condition: altitude 25.000 km"));
+
+        // the design and its performance follow the static temperature of the atmosphere
+        let freestream = FreestreamConditions::from_altitude_and_mach(25000.0, freestream_mach).expect("valid freestream");
+        assert!((flight_design.inlet.freestream_temperature().expect("freestream temperature") - freestream.atmosphere.temperature).abs() < 1e-3);
+        let gas: CaloricallyPerfectGas = CaloricallyPerfectGas::new(1.4, AIR_GAS_CONSTANT).expect("valid gas");
+        let inlet = busemann::calc_contour_from_freestream(&freestream, 2.5, &gas).expect("valid busemann inlet");
+        assert!((flight_design.inlet.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-9);
+        let performance = busemann::calc_performance_from_freestream(&freestream, 2.5, inlet.terminal_shock_angle(), &gas)
+            .expect("valid performance");
+        assert!((flight_design.performance.total_pressure_ratio - performance.total_pressure_ratio).abs() < 1e-9);
+
+        // a mixture needs no freestream temperature once the altitude gives it
+        parse_flags(&mut spec, &args("--gas air")).expect("valid flags");
+        let mixture_design = design(&spec).expect("valid design");
+        assert!(mixture_design.inlet.wall_states().is_some());
+    }
+
+    #[test]
+    fn test_freestream_temperature() {
+        // a freestream temperature designs through the gas model, giving the same perfect gas
+        // inlet along with the static temperature at each wall point
+        let mut spec = DesignSpec::new(InletType::Busemann);
+        parse_flags(&mut spec, &args("--freestream-mach 6 --exit-mach 2.5 --freestream-temperature 220")).expect("valid flags");
+        let busemann_design = design(&spec).expect("valid design");
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        assert!((busemann_design.inlet.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-6);
+        assert!((busemann_design.inlet.freestream_temperature().expect("freestream temperature") - 220.0).abs() < 1e-3);
+        let performance = busemann::calc_performance(6.0, 2.5, 1.4).expect("valid performance");
+        assert!((busemann_design.performance.total_pressure_ratio - performance.total_pressure_ratio).abs() < 1e-5);
+
+        spec.inlet_type = InletType::TruncatedBusemann;
+        parse_flags(&mut spec, &args("--truncation-fraction 0.4")).expect("valid flags");
+        let truncated = design(&spec).expect("valid design");
+        assert!(truncated.inlet.wall_states().is_some());
+        assert!(truncated.performance.total_pressure_ratio < busemann_design.performance.total_pressure_ratio);
+    }
+
+    #[test]
+    fn test_design() {
+        // a busemann design through the cli matches the library entry point
+        let mut spec = DesignSpec::new(InletType::Busemann);
+        parse_flags(&mut spec, &args("--freestream-mach 6 --exit-mach 2.5 --gamma 1.4")).expect("valid flags");
+        let busemann_design = design(&spec).expect("valid design");
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        assert!((busemann_design.inlet.terminal_shock_angle() - inlet.terminal_shock_angle()).abs() < 1e-12);
+        assert!(busemann_design.performance.total_pressure_ratio < 1.0);
+
+        // the boundary corrected design says its performance is that of the inviscid contour
+        spec.inlet_type = InletType::BoundaryCorrectedBusemann;
+        parse_flags(&mut spec, &args("--reynolds-number 1e7")).expect("valid flags");
+        let corrected = design(&spec).expect("valid design");
+        assert!(corrected.summary.ends_with(INVISCID_PERFORMANCE));
+        assert!((corrected.performance.total_pressure_ratio - busemann_design.performance.total_pressure_ratio).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        let mut spec = DesignSpec::new(InletType::Busemann);
+        assert!(parse_flags(&mut spec, &args("--exit-mach")).is_err());
+        assert!(parse_flags(&mut spec, &args("--exit-mach two")).is_err());
+        assert!(parse_flags(&mut spec, &args("--colour red")).is_err());
+        assert!(parse_flags(&mut spec, &args("exit-mach 2")).is_err());
+        assert!(parse_flags(&mut spec, &args("--gas methane")).is_err());
+        assert!(parse_flags(&mut spec, &args("--gas n2:0.8,o2")).is_err());
+        assert!(parse_flags(&mut spec, &args("--gas n2:-1")).is_err());
+        // the gas is given one way only, whichever flag comes first
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --freestream-temperature 220 --gas helium --gamma 1.4")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --freestream-temperature 220 --gamma 1.4 --gas helium")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --freestream-temperature 220 --gas air --gas-constant 287")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --gas helium")).is_err());
+        assert!(run("analyze", &args("icfa --freestream-mach 6 --incident-shock-angle 15 --gas helium")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --dynamic-pressure 5e4")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --altitude 25 --freestream-mach 6 --dynamic-pressure 5e4")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --altitude 100 --dynamic-pressure 5e4")).is_err());
+        assert!(design(&DesignSpec::new(InletType::Busemann)).is_err());
+        assert!(run("design", &args("scramjet")).is_err());
+        assert!(run("export", &args("busemann --exit-mach 2.5 --freestream-mach 6")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --gas-constant 0")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --efficiency 0.9 --freestream-temperature 220")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --efficiency 0.9 --altitude 25")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --altitude 25 --freestream-temperature 220")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --altitude 25 --method moc")).is_err());
+        assert!(run("analyze", &args("busemann --exit-mach 2.5 --freestream-mach 6 --method moc --freestream-temperature 220")).is_err());
+        assert!(run("analyze", &args("icfa --freestream-mach 6 --incident-shock-angle 15 --freestream-temperature 220")).is_err());
+        assert!(run("analyze", &args("icfa --freestream-mach 6 --incident-shock-angle 15 --altitude 25")).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use std::{error, fmt, io};
+
+#[derive(Debug)]
+pub enum InletError {
+    // every way a calculation in this crate can fail, carrying the values that caused it
+    InvalidMachNumber { mach_number: f64 },
+    InvalidSpecificHeatRatio { specific_heat_ratio: f64 },
+    InvalidParameter { name: &'static str, value: f64 },
+    DetachedShock { upstream_mach: f64, deflection_angle: f64 },
+    NotBracketed { lower_bound: f64, upper_bound: f64 },
+    NotConverged { iterations: usize, residual: f64 },
+    ZeroDerivative { x: f64 },
+    NoSolution(&'static str),
+    InvalidInlet(&'static str),
+    UnsupportedFormat { filename: String },
+    Io { filename: String, source: io::Error },
+}
+
+impl fmt::Display for InletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InletError::InvalidMachNumber { mach_number } => write!(f, "invalid mach number {}", mach_number),
+            InletError::InvalidSpecificHeatRatio { specific_heat_ratio } => {
+                write!(f, "invalid specific heat ratio {}, it must be greater than 1", specific_heat_ratio)
+            }
+            InletError::InvalidParameter { name, value } => write!(f, "invalid {} {}", name, value),
+            InletError::DetachedShock { upstream_mach, deflection_angle } => write!(
+                f,
+                "deflection angle {:.4} deg exceeds the maximum for an attached shock at mach {}",
+                deflection_angle.to_degrees(), upstream_mach
+            ),
+            InletError::NotBracketed { lower_bound, upper_bound } => {
+                write!(f, "solution not bracketed between {} and {}", lower_bound, upper_bound)
+            }
+            InletError::NotConverged { iterations, residual } => {
+                write!(f, "solution not converged after {} iterations, residual {:e}", iterations, residual)
+            }
+            InletError::ZeroDerivative { x } => write!(f, "derivative too small at {}", x),
+            InletError::NoSolution(reason) => write!(f, "{}", reason),
+            InletError::InvalidInlet(reason) => write!(f, "{}", reason),
+            InletError::UnsupportedFormat { filename } => {
+                write!(f, "unsupported file format for '{}', use .svg or .png", filename)
+            }
+            InletError::Io { filename, source } => write!(f, "failed to write '{}': {}", filename, source),
+        }
+    }
+}
+
+impl error::Error for InletError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            InletError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        // messages carry the offending values
+        let error = InletError::InvalidMachNumber { mach_number: 0.8 };
+        assert_eq!(error.to_string(), "invalid mach number 0.8");
+        let error = InletError::NotConverged { iterations: 200, residual: 1e-3 };
+        assert_eq!(error.to_string(), "solution not converged after 200 iterations, residual 1e-3");
+    }
+
+    #[test]
+    fn test_source() {
+        // only io failures wrap an underlying error
+        let source = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let error = InletError::Io { filename: "inlet.csv".to_string(), source };
+        assert!(error::Error::source(&error).is_some());
+        assert!(error::Error::source(&InletError::NoSolution("math error")).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+#![allow(dead_code)]
+
+use std::f64::consts::PI;
+use crate::{
+    error::InletError,
+    busemann::{self, BusemannPerformance},
+    inlet::{Contour, Inlet},
+    taylormaccoll::{self, TaylorMaccollResult, VelocityVector},
+    utils,
+};
+
+// local error tolerance and largest step in theta between the incident shock and the
+// singular ray, which are usually less than a degree apart
+const TAYLOR_MACCOLL_TOLERANCE: f64 = 1e-10;
+const TAYLOR_MACCOLL_MAX_STEP: f64 = 1e-5;
+
+#[derive(Debug)]
+pub struct Icfa {
+    // a struct to organise an internal conical flow a inlet, where an incident conical shock
+    // at the leading edge replaces the mach wave of a busemann inlet
+    pub inlet: Inlet,
+    pub incident_shock_angle: f64,      // relative to the freestream
+    pub incident_deflection_angle: f64,
+    pub singular_ray_angle: f64,        // ray from the focal point where the conical flow ends
+    pub reflected_shock_angle: f64,     // relative to the flow arriving at the singular ray
+    pub reflected_deflection_angle: f64,
+    pub performance: BusemannPerformance,
+}
+
+pub fn calc_icfa_from_shock_angle(freestream_mach: f64, incident_shock_angle: f64, specific_heat_ratio: f64) -> Result<Icfa, InletError> {
+    // the incident shock lies on the ray pi - beta from the focal point, behind which the
+    // taylor maccoll flow is followed downstream until it becomes singular, where the normal
+    // mach number to the ray is sonic, and a reflected shock turns the flow back onto the axis
+    if freestream_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: freestream_mach });
+    }
+    let mach_angle: f64 = utils::isentropic::calc_mach_angle_from_mach(freestream_mach)?;
+    if incident_shock_angle <= mach_angle || incident_shock_angle >= PI / 2.0 {
+        return Err(InletError::InvalidParameter { name: "incident shock angle", value: incident_shock_angle });
+    }
+    let incident_deflection_angle: f64 = utils::obliqueshock::calc_deflection_angle(freestream_mach, incident_shock_angle, specific_heat_ratio)?;
+    if incident_deflection_angle <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "incident shock angle", value: incident_shock_angle });
+    }
+    let post_shock_mach: f64 = utils::obliqueshock::calc_downstream_mach_from_shock_angle(freestream_mach, incident_shock_angle, specific_heat_ratio)?;
+    if post_shock_mach <= 1.0 {
+        return Err(InletError::NoSolution("incident shock leaves subsonic flow"));
+    }
+
+    // flow behind the shock is turned towards the axis by the deflection angle
+    let leading_edge_theta: f64 = PI - incident_shock_angle;
+    let initial_velocity_vector: VelocityVector = VelocityVector {
+        radial_component: post_shock_mach * (leading_edge_theta + incident_deflection_angle).cos(),
+        tangential_component: -post_shock_mach * (leading_edge_theta + incident_deflection_angle).sin(),
+    };
+    let results: Vec<TaylorMaccollResult> = taylormaccoll::solve_taylor_maccoll_adaptive(
+        initial_velocity_vector,
+        leading_edge_theta,
+        PI / 2.0,
+        1.0,
+        specific_heat_ratio,
+        TAYLOR_MACCOLL_TOLERANCE,
+        TAYLOR_MACCOLL_MAX_STEP,
+    )?;
+    let last: &TaylorMaccollResult = results.last().ok_or(InletError::NoSolution("math error"))?;
+    if results.len() < 2 || last.velocity_vector.tangential_component.abs() < 1.0 - 1e-2 {
+        return Err(InletError::NoSolution("conical flow did not reach the singular ray"));
+    }
+    if results.iter().any(|result| !result.radial_distance.is_finite() || !result.velocity_vector.get_mach_number().is_finite()) {
+        return Err(InletError::NoSolution("math error"));
+    }
+
+    // reflected shock turning the flow at the singular ray back to axial
+    let singular_ray_angle: f64 = last.theta;
+    let singular_ray_mach: f64 = last.velocity_vector.get_mach_number();
+    let reflected_deflection_angle: f64 = -last.flow_angle();
+    let (reflected_shock_angle, exit_mach, reflected_total_pressure_ratio) = if reflected_deflection_angle > 0.0 {
+        let shock_angle: f64 = utils::obliqueshock::calc_shock_angle(singular_ray_mach, reflected_deflection_angle, specific_heat_ratio)?;
+        (
+            shock_angle,
+            utils::obliqueshock::calc_downstream_mach_from_shock_angle(singular_ray_mach, shock_angle, specific_heat_ratio)?,
+            utils::obliqueshock::calc_stagnation_pressure_ratio(singular_ray_mach, shock_angle, specific_heat_ratio)?,
+        )
+    } else {
+        (utils::isentropic::calc_mach_angle_from_mach(singular_ray_mach)?, singular_ray_mach, 1.0)
+    };
+
+    // scale the solution to unit exit radius
+    let exit_radius: f64 = last.radial_distance * singular_ray_angle.sin();
+    let mut contour: Contour = Contour::new();
+    let mut flowfield: Vec<TaylorMaccollResult> = Vec::with_capacity(results.len());
+    for result in results.iter() {
+        let radial_distance: f64 = result.radial_distance / exit_radius;
+        contour.push_coords(radial_distance * result.theta.cos(), radial_distance * result.theta.sin());
+        flowfield.push(TaylorMaccollResult {
+            velocity_vector: result.velocity_vector.clone(),
+            radial_distance,
+            theta: result.theta,
+        });
+    }
+
+    // both shocks lose stagnation pressure while the stagnation temperature is conserved
+    let incident_total_pressure_ratio: f64 = utils::obliqueshock::calc_stagnation_pressure_ratio(freestream_mach, incident_shock_angle, specific_heat_ratio)?;
+    let total_pressure_ratio: f64 = incident_total_pressure_ratio * reflected_total_pressure_ratio;
+    let performance: BusemannPerformance = busemann::calc_performance_from_total_pressure_ratio(
+        freestream_mach,
+        exit_mach,
+        total_pressure_ratio,
+        specific_heat_ratio,
+    )?;
+
+    Ok(Icfa {
+        inlet: Inlet::new(contour, flowfield, freestream_mach, exit_mach, specific_heat_ratio, singular_ray_angle, incident_total_pressure_ratio),
+        incident_shock_angle,
+        incident_deflection_angle,
+        singular_ray_angle,
+        reflected_shock_angle,
+        reflected_deflection_angle,
+        performance,
+    })
+}
+
+pub fn calc_icfa_from_deflection_angle(freestream_mach: f64, incident_deflection_angle: f64, specific_heat_ratio: f64) -> Result<Icfa, InletError> {
+    if incident_deflection_angle <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "deflection angle", value: incident_deflection_angle });
+    }
+    let incident_shock_angle: f64 = utils::obliqueshock::calc_shock_angle(freestream_mach, incident_deflection_angle, specific_heat_ratio)?;
+    calc_icfa_from_shock_angle(freestream_mach, incident_shock_angle, specific_heat_ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_icfa_from_shock_angle() {
+        // the contour starts on the incident shock and the exit flow is axial at unit radius
+        let incident_shock_angle = 12.0_f64.to_radians();
+        let icfa = calc_icfa_from_shock_angle(6.0, incident_shock_angle, 1.4).expect("valid icfa inlet");
+        let flowfield = icfa.inlet.flowfield();
+        assert!((flowfield[0].flow_angle() + icfa.incident_deflection_angle).abs() < 1e-9);
+        assert!((flowfield[0].theta - (PI - incident_shock_angle)).abs() < 1e-12);
+        let y_coords = icfa.inlet.contour().y_coords();
+        assert!((y_coords[y_coords.len() - 1] - 1.0).abs() < 1e-12);
+        assert!(icfa.singular_ray_angle < flowfield[0].theta);
+        assert!(icfa.inlet.exit_mach() > 1.0 && icfa.inlet.exit_mach() < 6.0);
+        assert!(icfa.performance.total_pressure_ratio < 1.0);
+        assert!(icfa.performance.static_pressure_ratio > 1.0);
+        assert!(icfa.performance.static_temperature_ratio > 1.0);
+    }
+
+    #[test]
+    fn test_calc_icfa_from_deflection_angle() {
+        // designing from the deflection angle recovers the same incident shock
+        let by_shock = calc_icfa_from_shock_angle(6.0, 12.0_f64.to_radians(), 1.4).expect("valid icfa inlet");
+        let by_deflection = calc_icfa_from_deflection_angle(6.0, by_shock.incident_deflection_angle, 1.4).expect("valid icfa inlet");
+        assert!((by_shock.incident_shock_angle - by_deflection.incident_shock_angle).abs() < 1e-6);
+        assert!((by_shock.inlet.exit_mach() - by_deflection.inlet.exit_mach()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_stronger_incident_shock() {
+        // a stronger incident shock compresses more and recovers less
+        let weak = calc_icfa_from_shock_angle(6.0, 12.0_f64.to_radians(), 1.4).expect("valid icfa inlet");
+        let strong = calc_icfa_from_shock_angle(6.0, 20.0_f64.to_radians(), 1.4).expect("valid icfa inlet");
+        assert!(strong.inlet.exit_mach() < weak.inlet.exit_mach());
+        assert!(strong.performance.total_pressure_ratio < weak.performance.total_pressure_ratio);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(calc_icfa_from_shock_angle(0.8, 0.3, 1.4).is_err());
+        assert!(calc_icfa_from_shock_angle(6.0, 0.1, 1.4).is_err());
+        assert!(calc_icfa_from_shock_angle(6.0, PI / 2.0, 1.4).is_err());
+        assert!(calc_icfa_from_deflection_angle(6.0, -0.1, 1.4).is_err());
+    }
+}
@@ -1,35 +1,419 @@
-#[derive(Debug)]
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use crate::{
+    error::InletError,
+    plot::{Figure, LineStyle},
+    taylormaccoll::TaylorMaccollResult,
+    utils::{equilibrium::{Composition, Species}, isentropic},
+};
+
+const WALL_COLOUR: [u8; 3] = [0, 0, 0];
+const CENTRE_LINE_COLOUR: [u8; 3] = [110, 110, 110];
+const SHOCK_COLOUR: [u8; 3] = [200, 30, 30];
+
+#[derive(Debug, Default)]
 pub struct Contour {
     x_coords: Vec<f64>,
     y_coords: Vec<f64>,
 }
 
 impl Contour {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Contour { x_coords: Vec::new(), y_coords: Vec::new() }
     }
 
-    fn push_coords(&mut self, x: f64, y: f64) {
+    pub fn push_coords(&mut self, x: f64, y: f64) {
         self.x_coords.push(x);
         self.y_coords.push(y);
     }
 
-    fn plot(&self, filename: &str) {
-        todo!("not implemented plot for contours just yet")
+    pub fn x_coords(&self) -> &[f64] {
+        &self.x_coords
     }
+
+    pub fn y_coords(&self) -> &[f64] {
+        &self.y_coords
+    }
+
+    pub fn len(&self) -> usize {
+        self.x_coords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x_coords.is_empty()
+    }
+
+    fn figure(&self) -> Figure {
+        // wall and its mirror image about the centre line of the axisymmetric inlet
+        let mut figure: Figure = Figure::new("x", "r");
+        let mirrored_y_coords: Vec<f64> = self.y_coords.iter().map(|y| -y).collect();
+        figure.add_series(&self.x_coords, &self.y_coords, WALL_COLOUR, LineStyle::Solid);
+        figure.add_series(&self.x_coords, &mirrored_y_coords, WALL_COLOUR, LineStyle::Solid);
+        let x_min: f64 = self.x_coords.iter().cloned().fold(0.0, f64::min);
+        let x_max: f64 = self.x_coords.iter().cloned().fold(0.0, f64::max);
+        figure.add_series(&[x_min, x_max], &[0.0, 0.0], CENTRE_LINE_COLOUR, LineStyle::DashDot);
+        figure
+    }
+
+    pub fn plot(&self, filename: &str) -> Result<(), InletError> {
+        // svg or png according to the file extension
+        if self.is_empty() {
+            return Err(InletError::InvalidInlet("cannot plot an empty contour"));
+        }
+        self.figure().save(filename)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallState {
+    // static state of the inviscid flow at a contour point, as ratios to the freestream
+    // static values, for designs whose specific heats vary through the compression
+    pub pressure_ratio: f64,
+    pub temperature_ratio: f64,
+    pub density_ratio: f64,
+    pub sound_speed_ratio: f64,
+    pub temperature: f64,         // static temperature in kelvin
+    pub specific_heat_ratio: f64, // local value, or the isentropic exponent
+    pub composition: Option<Composition>, // equilibrium air only
 }
 
 #[derive(Debug)]
 pub struct Inlet {
+    // wall contour running from the leading edge to the terminal shock, with the focal
+    // point of the conical flow at the origin and unit exit radius unless scaled
     contour: Contour,
+    // taylor maccoll solution at each contour point
+    flowfield: Vec<TaylorMaccollResult>,
+    freestream_mach: f64,
+    exit_mach: f64,
+    specific_heat_ratio: f64,
+    // angle of the terminal conical shock relative to the axis, and the radius at which it
+    // meets the inviscid wall
+    terminal_shock_angle: f64,
+    exit_radius: f64,
+    // stagnation pressure ratio from the freestream to the flow along the contour,
+    // below one when a leading edge shock comes first
+    contour_total_pressure_ratio: f64,
+    // gas state at each contour point when the design followed a gas model, the constant
+    // specific heat ratio relations holding otherwise
+    wall_states: Option<Vec<WallState>>,
 }
 
 impl Inlet {
-    pub fn export_csv(&self) {
-        todo!()
+    pub fn new(
+        contour: Contour,
+        flowfield: Vec<TaylorMaccollResult>,
+        freestream_mach: f64,
+        exit_mach: f64,
+        specific_heat_ratio: f64,
+        terminal_shock_angle: f64,
+        contour_total_pressure_ratio: f64,
+    ) -> Self {
+        Inlet {
+            contour,
+            flowfield,
+            freestream_mach,
+            exit_mach,
+            specific_heat_ratio,
+            terminal_shock_angle,
+            exit_radius: 1.0,
+            contour_total_pressure_ratio,
+            wall_states: None,
+        }
+    }
+
+    pub fn with_wall_states(self, wall_states: Vec<WallState>) -> Result<Inlet, InletError> {
+        // one state per contour point, the first being the freestream
+        if wall_states.len() != self.flowfield.len() {
+            return Err(InletError::InvalidInlet("wall states must match the flowfield"));
+        }
+        Ok(Inlet { wall_states: Some(wall_states), ..self })
+    }
+
+    pub fn contour(&self) -> &Contour {
+        &self.contour
+    }
+
+    pub fn flowfield(&self) -> &[TaylorMaccollResult] {
+        &self.flowfield
+    }
+
+    pub fn freestream_mach(&self) -> f64 {
+        self.freestream_mach
+    }
+
+    pub fn exit_mach(&self) -> f64 {
+        self.exit_mach
+    }
+
+    pub fn specific_heat_ratio(&self) -> f64 {
+        self.specific_heat_ratio
+    }
+
+    pub fn terminal_shock_angle(&self) -> f64 {
+        self.terminal_shock_angle
+    }
+
+    pub fn exit_radius(&self) -> f64 {
+        self.exit_radius
+    }
+
+    pub fn contour_total_pressure_ratio(&self) -> f64 {
+        self.contour_total_pressure_ratio
+    }
+
+    pub fn wall_states(&self) -> Option<&[WallState]> {
+        self.wall_states.as_deref()
+    }
+
+    pub fn freestream_temperature(&self) -> Option<f64> {
+        // static temperature of the freestream in kelvin, known only from a gas model design
+        self.wall_states().and_then(|states| states.first()).map(|state| state.temperature / state.temperature_ratio)
+    }
+
+    pub fn truncate(&self, start_index: usize, contour_total_pressure_ratio: f64) -> Inlet {
+        // copy of the inlet with the contour starting at the given point, where the flow
+        // now reaching the contour has the given stagnation pressure ratio
+        let mut contour: Contour = Contour::new();
+        for (x, y) in self.contour.x_coords.iter().zip(&self.contour.y_coords).skip(start_index) {
+            contour.push_coords(*x, *y);
+        }
+        Inlet {
+            contour,
+            flowfield: self.flowfield[start_index.min(self.flowfield.len())..].to_vec(),
+            freestream_mach: self.freestream_mach,
+            exit_mach: self.exit_mach,
+            specific_heat_ratio: self.specific_heat_ratio,
+            terminal_shock_angle: self.terminal_shock_angle,
+            exit_radius: self.exit_radius,
+            contour_total_pressure_ratio,
+            wall_states: self.wall_states.as_ref().map(|states| states[start_index.min(states.len())..].to_vec()),
+        }
+    }
+
+    pub fn with_contour(&self, contour: Contour) -> Inlet {
+        // copy of the inlet with the wall moved, keeping the inviscid flowfield and the
+        // terminal shock it was designed with
+        Inlet {
+            contour,
+            flowfield: self.flowfield.clone(),
+            wall_states: self.wall_states.clone(),
+            ..*self
+        }
     }
 
-    pub fn plot(&self, filename: &str) {
-        self.contour.plot(filename);
+    pub fn scale(&self, factor: f64) -> Result<Inlet, InletError> {
+        // copy of the inlet with every length multiplied by the factor, about the focal point
+        if !(factor > 0.0 && factor.is_finite()) {
+            return Err(InletError::InvalidParameter { name: "scale factor", value: factor });
+        }
+        let mut contour: Contour = Contour::new();
+        for (x, y) in self.contour.x_coords.iter().zip(&self.contour.y_coords) {
+            contour.push_coords(x * factor, y * factor);
+        }
+        let flowfield: Vec<TaylorMaccollResult> = self.flowfield.iter()
+            .map(|result| TaylorMaccollResult {
+                velocity_vector: result.velocity_vector.clone(),
+                radial_distance: result.radial_distance * factor,
+                theta: result.theta,
+            })
+            .collect();
+        Ok(Inlet {
+            contour,
+            flowfield,
+            exit_radius: self.exit_radius * factor,
+            wall_states: self.wall_states.clone(),
+            ..*self
+        })
     }
-}
\ No newline at end of file
+
+    pub fn export_csv(&self, filename: &str) -> Result<(), InletError> {
+        // metadata preamble as comment lines, then the wall geometry and flow state with
+        // pressure and temperature relative to the freestream static values, followed by the
+        // density, temperature and specific heat ratio when a gas model set the states, and
+        // the species mole fractions for equilibrium air
+        if self.flowfield.len() != self.contour.len() {
+            return Err(InletError::InvalidInlet("inlet has no flowfield to export"));
+        }
+        let freestream_pressure_ratio: f64 = isentropic::calc_pressure_ratio_from_mach(self.freestream_mach, self.specific_heat_ratio)?;
+        let freestream_temperature_ratio: f64 = isentropic::calc_temperature_ratio_from_mach(self.freestream_mach, self.specific_heat_ratio)?;
+        let has_composition: bool = self.wall_states().is_some_and(|states| states[0].composition.is_some());
+
+        let io_error = |source: std::io::Error| InletError::Io { filename: filename.to_string(), source };
+        let mut csv: BufWriter<File> = BufWriter::new(File::create(filename).map_err(io_error)?);
+        writeln!(csv, "# specific_heat_ratio = {}", self.specific_heat_ratio).map_err(io_error)?;
+        writeln!(csv, "# freestream_mach = {}", self.freestream_mach).map_err(io_error)?;
+        if let Some(freestream_temperature) = self.freestream_temperature() {
+            writeln!(csv, "# freestream_temperature_k = {}", freestream_temperature).map_err(io_error)?;
+        }
+        writeln!(csv, "# exit_mach = {}", self.exit_mach).map_err(io_error)?;
+        writeln!(csv, "# terminal_shock_angle_deg = {}", self.terminal_shock_angle.to_degrees()).map_err(io_error)?;
+        writeln!(csv, "# contour_total_pressure_ratio = {}", self.contour_total_pressure_ratio).map_err(io_error)?;
+        let mut header: String = "x,r,mach,pressure_ratio,temperature_ratio,flow_angle_deg,theta_deg".to_string();
+        if self.wall_states.is_some() {
+            header += ",density_ratio,temperature_k,specific_heat_ratio";
+        }
+        if has_composition {
+            for species in Species::ALL {
+                header += &format!(",x_{}", species.name().to_lowercase());
+            }
+        }
+        writeln!(csv, "{}", header).map_err(io_error)?;
+        for (i, ((x, r), result)) in self.contour.x_coords.iter().zip(&self.contour.y_coords).zip(&self.flowfield).enumerate() {
+            let mach_number: f64 = result.velocity_vector.get_mach_number();
+            let flow_angle: f64 = result.flow_angle().to_degrees();
+            let theta: f64 = result.theta.to_degrees();
+            // the stagnation pressure lost ahead of the contour lowers the pressure and density,
+            // while the stagnation temperature and so the temperature at each mach number holds
+            match &self.wall_states {
+                Some(states) => {
+                    let state: &WallState = &states[i];
+                    write!(
+                        csv,
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        x, r, mach_number, state.pressure_ratio * self.contour_total_pressure_ratio, state.temperature_ratio,
+                        flow_angle, theta, state.density_ratio * self.contour_total_pressure_ratio, state.temperature, state.specific_heat_ratio
+                    ).map_err(io_error)?;
+                    if let Some(composition) = state.composition {
+                        for species in Species::ALL {
+                            write!(csv, ",{}", composition.mole_fraction(species)).map_err(io_error)?;
+                        }
+                    }
+                    writeln!(csv).map_err(io_error)?;
+                }
+                None => {
+                    let pressure_ratio: f64 = isentropic::calc_pressure_ratio_from_mach(mach_number, self.specific_heat_ratio)?
+                        * self.contour_total_pressure_ratio / freestream_pressure_ratio;
+                    let temperature_ratio: f64 = isentropic::calc_temperature_ratio_from_mach(mach_number, self.specific_heat_ratio)?
+                        / freestream_temperature_ratio;
+                    writeln!(csv, "{},{},{},{},{},{},{}", x, r, mach_number, pressure_ratio, temperature_ratio, flow_angle, theta)
+                        .map_err(io_error)?;
+                }
+            }
+        }
+        csv.flush().map_err(io_error)
+    }
+
+    pub fn plot(&self, filename: &str) -> Result<(), InletError> {
+        // contour with the terminal shock running from the focal point to the exit radius,
+        // which a displaced viscous wall no longer ends on
+        if self.contour.is_empty() {
+            return Err(InletError::InvalidInlet("cannot plot an empty contour"));
+        }
+        let mut figure: Figure = self.contour.figure();
+        let shock_x: f64 = self.exit_radius / self.terminal_shock_angle.tan();
+        figure.add_series(&[0.0, shock_x], &[0.0, self.exit_radius], SHOCK_COLOUR, LineStyle::Dashed);
+        figure.add_series(&[0.0, shock_x], &[0.0, -self.exit_radius], SHOCK_COLOUR, LineStyle::Dashed);
+        figure.save(filename)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{busemann, error::InletError, utils::{equilibrium::EquilibriumAir, gas::{CaloricallyPerfectGas, ThermallyPerfectGas}}};
+
+    #[test]
+    fn test_scale() {
+        // lengths scale about the focal point while the flow is unchanged
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let scaled = inlet.scale(0.25).expect("valid scale factor");
+        let last = inlet.contour().len() - 1;
+        assert!((scaled.contour().y_coords()[last] - 0.25).abs() < 1e-12);
+        assert!((scaled.contour().x_coords()[0] - 0.25 * inlet.contour().x_coords()[0]).abs() < 1e-12);
+        assert!((scaled.flowfield()[0].radial_distance - 0.25 * inlet.flowfield()[0].radial_distance).abs() < 1e-12);
+        assert_eq!(scaled.terminal_shock_angle(), inlet.terminal_shock_angle());
+        assert!((scaled.exit_radius() - 0.25).abs() < 1e-12);
+        assert!(inlet.scale(0.0).is_err());
+        assert!(inlet.scale(f64::NAN).is_err());
+    }
+
+    fn temp_csv(name: &str) -> std::path::PathBuf {
+        // the process id keeps suites running in parallel or from other checkouts apart
+        std::env::temp_dir().join(format!("inlet-designer-test-{}-{}.csv", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_export_csv() {
+        // one row per contour point after the preamble and header
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let path = temp_csv("export");
+        let filename = path.to_str().expect("valid path");
+        inlet.export_csv(filename).expect("valid export");
+        let csv = std::fs::read_to_string(&path).expect("readable csv");
+        std::fs::remove_file(&path).expect("removable csv");
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("# specific_heat_ratio = 1.4"));
+        let header_index = lines.iter().position(|line| line.starts_with("x,r,")).expect("header row");
+        assert!(lines[..header_index].iter().all(|line| line.starts_with('#')));
+        assert_eq!(lines.len() - header_index - 1, inlet.contour().len());
+
+        // the leading edge sits in the undisturbed freestream
+        let first: Vec<f64> = lines[header_index + 1].split(',').map(|value| value.parse().expect("number")).collect();
+        assert_eq!(first.len(), 7);
+        assert!((first[2] - inlet.freestream_mach()).abs() < 1e-12);
+        assert!((first[3] - 1.0).abs() < 1e-9);
+        assert!((first[4] - 1.0).abs() < 1e-9);
+
+        // a file that cannot be written reports the failure rather than dropping it
+        let missing = std::env::temp_dir().join(format!("inlet-designer-test-missing-{}", std::process::id())).join("export.csv");
+        let result = inlet.export_csv(missing.to_str().expect("valid path"));
+        assert!(matches!(result, Err(InletError::Io { .. })));
+    }
+
+    #[test]
+    fn test_export_csv_for_gas() {
+        // a gas model design adds its density, temperature and specific heat ratio columns
+        let inlet = busemann::calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &ThermallyPerfectGas::air()).expect("valid busemann inlet");
+        let path = temp_csv("export-gas");
+        let filename = path.to_str().expect("valid path");
+        inlet.export_csv(filename).expect("valid export");
+        let csv = std::fs::read_to_string(&path).expect("readable csv");
+        std::fs::remove_file(&path).expect("removable csv");
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines.iter().any(|line| line.starts_with("# freestream_temperature_k = ")));
+        let header_index = lines.iter().position(|line| line.starts_with("x,r,")).expect("header row");
+        assert!(lines[header_index].ends_with(",density_ratio,temperature_k,specific_heat_ratio"));
+        let rows: Vec<Vec<f64>> = lines[header_index + 1..].iter()
+            .map(|line| line.split(',').map(|value| value.parse().expect("number")).collect())
+            .collect();
+        assert_eq!(rows[0].len(), 10);
+        assert!((rows[0][8] - 220.0).abs() < 1e-6);
+        assert!((rows[0][7] - 1.0).abs() < 1e-9);
+
+        // the compressed gas is hot enough for vibration to lower the specific heat ratio
+        // below its perfect gas value
+        let last = &rows[rows.len() - 1];
+        assert!(last[9] < 1.39);
+        assert!((last[8] / rows[0][8] - last[4]).abs() < 1e-9 * last[4]);
+        assert!((last[7] - last[3] / last[4]).abs() < 1e-9 * last[7]);
+    }
+
+    #[test]
+    fn test_export_csv_for_equilibrium_air() {
+        // equilibrium air adds the species mole fractions, which at mach 15 show the air
+        // dissociating as it is compressed
+        let inlet = busemann::calc_contour_from_machs_for_equilibrium_air(15.0, 1000.0, 220.0, 3.0, &EquilibriumAir::air())
+            .expect("valid busemann inlet");
+        let path = temp_csv("export-equilibrium");
+        let filename = path.to_str().expect("valid path");
+        inlet.export_csv(filename).expect("valid export");
+        let csv = std::fs::read_to_string(&path).expect("readable csv");
+        std::fs::remove_file(&path).expect("removable csv");
+
+        let lines: Vec<&str> = csv.lines().collect();
+        let header_index = lines.iter().position(|line| line.starts_with("x,r,")).expect("header row");
+        assert!(lines[header_index].ends_with(",specific_heat_ratio,x_n2,x_o2,x_no,x_n,x_o"));
+        let rows: Vec<Vec<f64>> = lines[header_index + 1..].iter()
+            .map(|line| line.split(',').map(|value| value.parse().expect("number")).collect())
+            .collect();
+        assert!(rows.iter().all(|row| row.len() == 15 && (row[10..].iter().sum::<f64>() - 1.0).abs() < 1e-9));
+        assert!((rows[0][8] - 220.0).abs() < 1e-3);
+        let last = &rows[rows.len() - 1];
+        assert!(last[12] > rows[0][12] + 0.01);
+        assert!(last[14] > rows[0][14]);
+    }
+}
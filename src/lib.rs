@@ -0,0 +1,16 @@
+// inlet design methods and the gas dynamics behind them, used by the interactive
+// designer binary and by any other tool depending on this crate
+
+pub mod taylormaccoll;
+pub mod busemann;
+pub mod moc;
+pub mod icfa;
+pub mod truncatedbusemann;
+pub mod boundarylayer;
+pub mod inlet;
+pub mod plot;
+pub mod utils;
+pub mod error;
+
+pub use error::InletError;
+pub use inlet::{Contour, Inlet};
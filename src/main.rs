@@ -1,24 +1,54 @@
+mod cli;
+mod spec;
+
 use std::env;
 use std::io;
 use std::io::Write;
 use std::process::exit;
 
-use inlet::Inlet;
-
-mod taylormaccoll;
-mod busemann;
-mod inlet;
-mod utils;
+use inlet_designer::{
+    boundarylayer::{self, BoundaryCorrectedBusemann},
+    busemann,
+    icfa::{self, Icfa},
+    truncatedbusemann::{self, TruncatedBusemann, Truncation},
+    utils::gas::CaloricallyPerfectGas,
+    Inlet,
+};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        eprintln!("usage: {} [inlet type]", args[0]);
-        std::process::exit(1);
+        eprintln!("{}", cli::USAGE);
+        exit(1);
     }
 
     let inlet_type: &String = &args[1];
+    // the prompts design for air, other gases are reached through the --gamma and --gas flags
+    let gas: CaloricallyPerfectGas = CaloricallyPerfectGas::air();
+
+    // subcommands run without prompting, anything else falls back to the prompts
+    match inlet_type.as_str() {
+        "design" | "analyze" | "export" => {
+            if let Err(err) = cli::run(inlet_type, &args[2..]) {
+                eprintln!("{}", err);
+                exit(2);
+            }
+            return;
+        }
+        "run" => {
+            if let Err(err) = spec::run(&args[2..]) {
+                eprintln!("{}", err);
+                exit(2);
+            }
+            return;
+        }
+        "-h" | "--help" | "help" => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        _ => {}
+    }
 
     match inlet_type.as_str() {
         "b" | "busemann" => {
@@ -35,9 +65,117 @@ fn main() {
             io::stdin().read_line(&mut input)
                 .expect("failed to read input method");
 
-            match input.trim().parse().unwrap() {
-                1 => todo!(),
-                2 => todo!(),
+            match input.trim().parse().unwrap_or(0) {
+                1 => {
+                    print!("enter the design exit mach number: ");
+                    io::stdout().flush().unwrap();
+                    input.clear();
+
+                    io::stdin().read_line(&mut input)
+                        .expect("failed to read input mach");
+                    let exit_mach: f64 = match input.trim().parse() {
+                        Ok(num) => num,
+                        Err(_) => {
+                            eprintln!("invalid exit mach number");
+                            exit(1);
+                        }
+                    };
+
+                    print!("enter the design free stream mach number: ");
+                    io::stdout().flush().unwrap();
+                    input.clear();
+
+                    io::stdin().read_line(&mut input)
+                        .expect("failed to read input");
+                    let freestream_mach: f64 = match input.trim().parse() {
+                        Ok(num) => num,
+                        Err(_) => {
+                            eprintln!("invalid freestream mach number");
+                            exit(1);
+                        }
+                    };
+                    let busemann: Inlet = match busemann::calc_moc_contour_from_machs(freestream_mach, exit_mach, &gas) {
+                        Ok(inlet) => inlet,
+                        Err(err) => {
+                            eprintln!("failed to design busemann inlet: {}", err);
+                            exit(1);
+                        }
+                    };
+                    println!(
+                        "busemann inlet designed: freestream mach {:.4}, exit mach {:.4}, terminal shock angle {:.4} deg",
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle().to_degrees()
+                    );
+                    if let Ok(performance) = busemann::calc_performance_from_terminal_shock(
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle(), busemann.specific_heat_ratio()
+                    ) {
+                        println!(
+                            "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+                            performance.total_pressure_ratio, performance.static_pressure_ratio,
+                            performance.static_temperature_ratio, performance.density_ratio
+                        );
+                    }
+                    if let Err(err) = busemann.export_csv("busemann.csv") {
+                        eprintln!("failed to export inlet: {}", err);
+                    }
+                    if let Err(err) = busemann.plot("busemann.png") {
+                        eprintln!("failed to plot inlet: {}", err);
+                    }
+                }
+                2 => {
+                    print!("enter the design exit mach number: ");
+                    io::stdout().flush().unwrap();
+                    input.clear();
+
+                    io::stdin().read_line(&mut input)
+                        .expect("failed to read input mach number");
+                    let exit_mach: f64 = match input.trim().parse() {
+                        Ok(num) => num,
+                        Err(_) => {
+                            eprintln!("invalid exit mach number");
+                            exit(1);
+                        }
+                    };
+
+                    print!("enter the design compression efficiency: ");
+                    io::stdout().flush().unwrap();
+                    input.clear();
+
+                    io::stdin().read_line(&mut input)
+                        .expect("failed to read line");
+                    let compression_efficiency: f64 = match input.trim().parse() {
+                        Ok(num) => num,
+                        Err(_) => {
+                            eprintln!("invalid compression efficiency");
+                            exit(1);
+                        }
+                    };
+                    let (busemann, freestream_mach): (Inlet, f64) = match busemann::calc_moc_contour_from_exit_mach_and_efficiency(exit_mach, compression_efficiency, &gas) {
+                        Ok(design) => design,
+                        Err(err) => {
+                            eprintln!("failed to design busemann inlet: {}", err);
+                            exit(1);
+                        }
+                    };
+                    println!(
+                        "busemann inlet designed: freestream mach {:.4}, exit mach {:.4}, terminal shock angle {:.4} deg",
+                        freestream_mach, busemann.exit_mach(), busemann.terminal_shock_angle().to_degrees()
+                    );
+                    if let Ok(performance) = busemann::calc_performance_from_terminal_shock(
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle(), busemann.specific_heat_ratio()
+                    ) {
+                        println!(
+                            "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+                            performance.total_pressure_ratio, performance.static_pressure_ratio,
+                            performance.static_temperature_ratio, performance.density_ratio
+                        );
+                    }
+                    if let Err(err) = busemann.plot("busemann.png") {
+                        eprintln!("failed to plot inlet: {}", err);
+                    }
+                    if let Err(err) = busemann.export_csv("busemann.csv") {
+                        eprintln!("failed to export inlet: {}", err);
+                    }
+                },
                 3 => {
                     print!("enter the design exit mach number: ");
                     io::stdout().flush().unwrap();
@@ -66,10 +204,32 @@ fn main() {
                             exit(1);
                         }
                     };
-                    println!("{}, {}", exit_mach, freestream_mach);
-                    let busemann: Inlet = busemann::calc_contour(exit_mach);
-                    busemann.export_csv();
-                    busemann.plot("busemann.png");
+                    let busemann: Inlet = match busemann::calc_contour_from_machs(freestream_mach, exit_mach, &gas) {
+                        Ok(inlet) => inlet,
+                        Err(err) => {
+                            eprintln!("failed to design busemann inlet: {}", err);
+                            exit(1);
+                        }
+                    };
+                    println!(
+                        "busemann inlet designed: freestream mach {:.4}, exit mach {:.4}, terminal shock angle {:.4} deg",
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle().to_degrees()
+                    );
+                    if let Ok(performance) = busemann::calc_performance_from_terminal_shock(
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle(), busemann.specific_heat_ratio()
+                    ) {
+                        println!(
+                            "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+                            performance.total_pressure_ratio, performance.static_pressure_ratio,
+                            performance.static_temperature_ratio, performance.density_ratio
+                        );
+                    }
+                    if let Err(err) = busemann.export_csv("busemann.csv") {
+                        eprintln!("failed to export inlet: {}", err);
+                    }
+                    if let Err(err) = busemann.plot("busemann.png") {
+                        eprintln!("failed to plot inlet: {}", err);
+                    }
                 }
                 4 => {
                     print!("enter the design exit mach number: ");
@@ -86,7 +246,7 @@ fn main() {
                         }
                     };
 
-                    print!("enter the design compression efficiency");
+                    print!("enter the design compression efficiency: ");
                     io::stdout().flush().unwrap();
                     input.clear();
 
@@ -99,22 +259,271 @@ fn main() {
                             exit(1);
                         }
                     };
-                    println!("{}, {}", exit_mach, compression_efficiency);
-                    let busemann: Inlet = busemann::calc_contour(exit_mach, None, Some(compression_efficiency));
-                    busemann.plot("busemann.png");
-                    busemann.export_csv();
+                    let (busemann, freestream_mach): (Inlet, f64) = match busemann::calc_contour_from_exit_mach_and_efficiency(exit_mach, compression_efficiency, &gas) {
+                        Ok(design) => design,
+                        Err(err) => {
+                            eprintln!("failed to design busemann inlet: {}", err);
+                            exit(1);
+                        }
+                    };
+                    println!(
+                        "busemann inlet designed: freestream mach {:.4}, exit mach {:.4}, terminal shock angle {:.4} deg",
+                        freestream_mach, busemann.exit_mach(), busemann.terminal_shock_angle().to_degrees()
+                    );
+                    if let Ok(performance) = busemann::calc_performance_from_terminal_shock(
+                        busemann.freestream_mach(), busemann.exit_mach(), busemann.terminal_shock_angle(), busemann.specific_heat_ratio()
+                    ) {
+                        println!(
+                            "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+                            performance.total_pressure_ratio, performance.static_pressure_ratio,
+                            performance.static_temperature_ratio, performance.density_ratio
+                        );
+                    }
+                    if let Err(err) = busemann.plot("busemann.png") {
+                        eprintln!("failed to plot inlet: {}", err);
+                    }
+                    if let Err(err) = busemann.export_csv("busemann.csv") {
+                        eprintln!("failed to export inlet: {}", err);
+                    }
                 },
-                _ => panic!("unknown method for designing busemann inlet, select [1], [2], [3], or [4]")
+                _ => {
+                    eprintln!("unknown method for designing busemann inlet, select [1], [2], [3], or [4]");
+                    exit(1);
+                }
             }
         }
         "icfa" => {
-            todo!("icfa inlet")
+            let mut input = String::new();
+
+            print!("enter the design free stream mach number: ");
+            io::stdout().flush().unwrap();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input");
+            let freestream_mach: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid freestream mach number");
+                    exit(1);
+                }
+            };
+
+            print!("enter the incident shock angle in degrees: ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input shock angle");
+            let incident_shock_angle: f64 = match input.trim().parse::<f64>() {
+                Ok(num) => num.to_radians(),
+                Err(_) => {
+                    eprintln!("invalid incident shock angle");
+                    exit(1);
+                }
+            };
+
+            let icfa: Icfa = match icfa::calc_icfa_from_shock_angle(freestream_mach, incident_shock_angle, gas.constant_specific_heat_ratio()) {
+                Ok(icfa) => icfa,
+                Err(err) => {
+                    eprintln!("failed to design icfa inlet: {}", err);
+                    exit(1);
+                }
+            };
+            println!(
+                "icfa inlet designed: freestream mach {:.4}, exit mach {:.4}, incident deflection angle {:.4} deg, reflected deflection angle {:.4} deg",
+                icfa.inlet.freestream_mach(), icfa.inlet.exit_mach(),
+                icfa.incident_deflection_angle.to_degrees(), icfa.reflected_deflection_angle.to_degrees()
+            );
+            println!(
+                "total pressure ratio {:.4}, static pressure ratio {:.4}, static temperature ratio {:.4}, density ratio {:.4}",
+                icfa.performance.total_pressure_ratio, icfa.performance.static_pressure_ratio,
+                icfa.performance.static_temperature_ratio, icfa.performance.density_ratio
+            );
+            if let Err(err) = icfa.inlet.plot("icfa.png") {
+                eprintln!("failed to plot inlet: {}", err);
+            }
+            if let Err(err) = icfa.inlet.export_csv("icfa.csv") {
+                eprintln!("failed to export inlet: {}", err);
+            }
         }
         "tb" | "truncated-busemann" => {
-            todo!("truncated busemann inlet")
+            let mut input = String::new();
+
+            print!("enter the design exit mach number: ");
+            io::stdout().flush().unwrap();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input mach");
+            let exit_mach: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid exit mach number");
+                    exit(1);
+                }
+            };
+
+            print!("enter the design free stream mach number: ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input");
+            let freestream_mach: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid freestream mach number");
+                    exit(1);
+                }
+            };
+
+            print!(
+                "please select truncation:
+                 - wall angle in degrees [1]
+                 - fraction of length removed [2]\n: "
+            );
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input truncation");
+            let method: u8 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid truncation method");
+                    exit(1);
+                }
+            };
+
+            print!("enter the truncation value: ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input truncation value");
+            let value: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid truncation value");
+                    exit(1);
+                }
+            };
+            let truncation: Truncation = match method {
+                1 => Truncation::WallAngle(value.to_radians()),
+                2 => Truncation::LengthFraction(value),
+                _ => {
+                    eprintln!("unknown truncation method, select [1] or [2]");
+                    exit(1);
+                }
+            };
+
+            let busemann: Inlet = match busemann::calc_contour_from_machs(freestream_mach, exit_mach, &gas) {
+                Ok(inlet) => inlet,
+                Err(err) => {
+                    eprintln!("failed to design busemann inlet: {}", err);
+                    exit(1);
+                }
+            };
+            let truncated: TruncatedBusemann = match truncatedbusemann::truncate(&busemann, truncation) {
+                Ok(truncated) => truncated,
+                Err(err) => {
+                    eprintln!("failed to truncate busemann inlet: {}", err);
+                    exit(1);
+                }
+            };
+            println!(
+                "truncated busemann inlet designed: leading edge angle {:.4} deg, leading edge shock angle {:.4} deg",
+                truncated.leading_edge_deflection_angle.to_degrees(), truncated.leading_edge_shock_angle.to_degrees()
+            );
+            println!(
+                "capture area ratio {:.4}, length saving {:.4}, total pressure ratio {:.4} (leading edge shock {:.4})",
+                truncated.capture_area_ratio, truncated.length_saving,
+                truncated.total_pressure_ratio, truncated.leading_edge_total_pressure_ratio
+            );
+            if let Err(err) = truncated.inlet.plot("truncated-busemann.png") {
+                eprintln!("failed to plot inlet: {}", err);
+            }
+            if let Err(err) = truncated.inlet.export_csv("truncated-busemann.csv") {
+                eprintln!("failed to export inlet: {}", err);
+            }
         }
         "bcb" | "boundary-corrected-busemann" => {
-            todo!()
+            let mut input = String::new();
+
+            print!("enter the design exit mach number: ");
+            io::stdout().flush().unwrap();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input mach");
+            let exit_mach: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid exit mach number");
+                    exit(1);
+                }
+            };
+
+            print!("enter the design free stream mach number: ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input");
+            let freestream_mach: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid freestream mach number");
+                    exit(1);
+                }
+            };
+
+            print!("enter the free stream reynolds number based on exit radius: ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input reynolds number");
+            let reynolds_number: f64 = match input.trim().parse() {
+                Ok(num) => num,
+                Err(_) => {
+                    eprintln!("invalid reynolds number");
+                    exit(1);
+                }
+            };
+
+            print!("enter the wall to free stream temperature ratio (blank for adiabatic): ");
+            io::stdout().flush().unwrap();
+            input.clear();
+            io::stdin().read_line(&mut input)
+                .expect("failed to read input wall temperature ratio");
+            let wall_temperature_ratio: Option<f64> = match input.trim() {
+                "" => None,
+                ratio => match ratio.parse() {
+                    Ok(num) => Some(num),
+                    Err(_) => {
+                        eprintln!("invalid wall temperature ratio");
+                        exit(1);
+                    }
+                },
+            };
+
+            let busemann: Inlet = match busemann::calc_contour_from_machs(freestream_mach, exit_mach, &gas) {
+                Ok(inlet) => inlet,
+                Err(err) => {
+                    eprintln!("failed to design busemann inlet: {}", err);
+                    exit(1);
+                }
+            };
+            let corrected: BoundaryCorrectedBusemann = match boundarylayer::correct_contour(&busemann, reynolds_number, wall_temperature_ratio) {
+                Ok(corrected) => corrected,
+                Err(err) => {
+                    eprintln!("failed to correct busemann inlet: {}", err);
+                    exit(1);
+                }
+            };
+            let last_index: usize = corrected.boundary_layer.displacement_thickness.len() - 1;
+            println!(
+                "boundary corrected busemann inlet designed in {} iterations: exit displacement thickness {:.6}, exit momentum thickness {:.6}",
+                corrected.iterations,
+                corrected.boundary_layer.displacement_thickness[last_index],
+                corrected.boundary_layer.momentum_thickness[last_index]
+            );
+            if let Err(err) = corrected.inlet.plot("boundary-corrected-busemann.png") {
+                eprintln!("failed to plot inlet: {}", err);
+            }
+            if let Err(err) = corrected.inlet.export_csv("boundary-corrected-busemann.csv") {
+                eprintln!("failed to export inlet: {}", err);
+            }
         }
         _ => {
             eprintln!("unknown inlet type '{}'", inlet_type);
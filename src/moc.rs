@@ -0,0 +1,446 @@
+#![allow(dead_code)]
+
+use crate::{error::InletError, utils};
+
+// predictor-corrector settings shared by every unit process
+const CORRECTOR_TOLERANCE: f64 = 1e-10;
+const MAX_CORRECTOR_ITERS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct CharacteristicPoint {
+    // a struct to organise the flow state at a node of the characteristic net, with the
+    // flow angle measured counterclockwise from the x axis and r the distance from the axis
+    pub x: f64,
+    pub r: f64,
+    pub flow_angle: f64,
+    pub prandtl_meyer_angle: f64,
+    pub mach_number: f64,
+}
+
+impl CharacteristicPoint {
+    pub fn new(x: f64, r: f64, flow_angle: f64, mach_number: f64, specific_heat_ratio: f64) -> Result<Self, InletError> {
+        let prandtl_meyer_angle: f64 = utils::isentropic::prandtl_meyer_function(mach_number, specific_heat_ratio)?;
+        Ok(CharacteristicPoint { x, r, flow_angle, prandtl_meyer_angle, mach_number })
+    }
+
+    fn from_prandtl_meyer_angle(x: f64, r: f64, flow_angle: f64, prandtl_meyer_angle: f64, specific_heat_ratio: f64) -> Result<Self, InletError> {
+        if prandtl_meyer_angle <= 0.0 {
+            return Err(InletError::NoSolution("flow is no longer supersonic"));
+        }
+        let gamma_ratio: f64 = ((specific_heat_ratio + 1.0) / (specific_heat_ratio - 1.0)).sqrt();
+        if prandtl_meyer_angle >= std::f64::consts::PI / 2.0 * (gamma_ratio - 1.0) {
+            return Err(InletError::NoSolution("flow has expanded to vacuum"));
+        }
+        let mach_number: f64 = utils::isentropic::calc_mach_from_prandtl_meyer_angle(prandtl_meyer_angle, specific_heat_ratio)?;
+        Ok(CharacteristicPoint { x, r, flow_angle, prandtl_meyer_angle, mach_number })
+    }
+
+    pub fn mach_angle(&self) -> f64 {
+        (1.0 / self.mach_number).asin()
+    }
+
+    fn left_running_slope(&self) -> f64 {
+        // dr / dx along the c+ characteristic
+        (self.flow_angle + self.mach_angle()).tan()
+    }
+
+    fn right_running_slope(&self) -> f64 {
+        // dr / dx along the c- characteristic
+        (self.flow_angle - self.mach_angle()).tan()
+    }
+
+    fn axisymmetric_coefficient(&self) -> f64 {
+        // source term of the compatibility relations per unit length along a characteristic
+        self.mach_angle().sin() * self.flow_angle.sin() / self.r
+    }
+}
+
+fn distance(x1: f64, r1: f64, x2: f64, r2: f64) -> f64 {
+    ((x2 - x1).powi(2) + (r2 - r1).powi(2)).sqrt()
+}
+
+fn intersect_lines(x1: f64, r1: f64, slope1: f64, x2: f64, r2: f64, slope2: f64) -> Result<(f64, f64), InletError> {
+    if (slope1 - slope2).abs() < 1e-12 {
+        return Err(InletError::NoSolution("characteristics do not intersect"));
+    }
+    let x: f64 = (r2 - r1 + slope1 * x1 - slope2 * x2) / (slope1 - slope2);
+    let r: f64 = r1 + slope1 * (x - x1);
+    Ok((x, r))
+}
+
+fn averaged(a: &CharacteristicPoint, b: &CharacteristicPoint) -> CharacteristicPoint {
+    // mean state used to evaluate coefficients in the corrector
+    CharacteristicPoint {
+        x: 0.5 * (a.x + b.x),
+        r: 0.5 * (a.r + b.r),
+        flow_angle: 0.5 * (a.flow_angle + b.flow_angle),
+        prandtl_meyer_angle: 0.5 * (a.prandtl_meyer_angle + b.prandtl_meyer_angle),
+        mach_number: 0.5 * (a.mach_number + b.mach_number),
+    }
+}
+
+pub fn interior_point(
+    left_running: &CharacteristicPoint,  // point the c+ characteristic runs from
+    right_running: &CharacteristicPoint, // point the c- characteristic runs from
+    specific_heat_ratio: f64,
+) -> Result<CharacteristicPoint, InletError> {
+    // along c+ : d(theta - nu) = -sin(mu) sin(theta) dl / r
+    // along c- : d(theta + nu) =  sin(mu) sin(theta) dl / r
+    let a: &CharacteristicPoint = left_running;
+    let b: &CharacteristicPoint = right_running;
+
+    // predictor uses the coefficients at the known points
+    let mut mean_a: CharacteristicPoint = a.clone();
+    let mut mean_b: CharacteristicPoint = b.clone();
+    let mut point: Option<CharacteristicPoint> = None;
+
+    for _ in 0..MAX_CORRECTOR_ITERS {
+        let (x, r) = intersect_lines(
+            a.x, a.r, mean_a.left_running_slope(),
+            b.x, b.r, mean_b.right_running_slope(),
+        )?;
+        let length_a: f64 = distance(a.x, a.r, x, r);
+        let length_b: f64 = distance(b.x, b.r, x, r);
+        let source_a: f64 = mean_a.axisymmetric_coefficient() * length_a;
+        let source_b: f64 = mean_b.axisymmetric_coefficient() * length_b;
+
+        let flow_angle: f64 = 0.5 * (
+            (b.flow_angle + b.prandtl_meyer_angle) + (a.flow_angle - a.prandtl_meyer_angle)
+            + source_b - source_a
+        );
+        let prandtl_meyer_angle: f64 = 0.5 * (
+            (b.flow_angle + b.prandtl_meyer_angle) - (a.flow_angle - a.prandtl_meyer_angle)
+            + source_b + source_a
+        );
+        let next: CharacteristicPoint = CharacteristicPoint::from_prandtl_meyer_angle(x, r, flow_angle, prandtl_meyer_angle, specific_heat_ratio)?;
+
+        let converged: bool = match &point {
+            Some(previous) => {
+                (previous.x - next.x).abs() < CORRECTOR_TOLERANCE
+                    && (previous.r - next.r).abs() < CORRECTOR_TOLERANCE
+                    && (previous.flow_angle - next.flow_angle).abs() < CORRECTOR_TOLERANCE
+                    && (previous.prandtl_meyer_angle - next.prandtl_meyer_angle).abs() < CORRECTOR_TOLERANCE
+            }
+            None => false,
+        };
+
+        // corrector uses the mean of the known and new points
+        mean_a = averaged(a, &next);
+        mean_b = averaged(b, &next);
+        point = Some(next);
+
+        if converged {
+            break;
+        }
+    }
+
+    point.ok_or(InletError::NoSolution("math error"))
+}
+
+pub fn wall_point(
+    left_running: &CharacteristicPoint, // point the c+ characteristic runs from up to the wall
+    wall_x: f64,
+    wall_r: f64,
+    wall_angle: f64, // wall treated as straight through (wall_x, wall_r) at this angle
+    specific_heat_ratio: f64,
+) -> Result<CharacteristicPoint, InletError> {
+    // flow tangency sets the flow angle, the c+ compatibility relation sets the mach number
+    let a: &CharacteristicPoint = left_running;
+    let mut mean_a: CharacteristicPoint = a.clone();
+    let mut point: Option<CharacteristicPoint> = None;
+
+    for _ in 0..MAX_CORRECTOR_ITERS {
+        let (x, r) = intersect_lines(a.x, a.r, mean_a.left_running_slope(), wall_x, wall_r, wall_angle.tan())?;
+        let source_a: f64 = mean_a.axisymmetric_coefficient() * distance(a.x, a.r, x, r);
+        let prandtl_meyer_angle: f64 = wall_angle - (a.flow_angle - a.prandtl_meyer_angle) + source_a;
+        let next: CharacteristicPoint = CharacteristicPoint::from_prandtl_meyer_angle(x, r, wall_angle, prandtl_meyer_angle, specific_heat_ratio)?;
+
+        let converged: bool = match &point {
+            Some(previous) => (previous.prandtl_meyer_angle - next.prandtl_meyer_angle).abs() < CORRECTOR_TOLERANCE,
+            None => false,
+        };
+        mean_a = averaged(a, &next);
+        point = Some(next);
+        if converged {
+            break;
+        }
+    }
+
+    point.ok_or(InletError::NoSolution("math error"))
+}
+
+pub fn axis_point(
+    right_running: &CharacteristicPoint, // point the c- characteristic runs from down to the axis
+    specific_heat_ratio: f64,
+) -> Result<CharacteristicPoint, InletError> {
+    // the flow is axial on the axis, where the source term is indeterminate and is
+    // taken from the known point alone
+    let b: &CharacteristicPoint = right_running;
+    if b.r <= 0.0 {
+        return Err(InletError::NoSolution("point must lie off the axis"));
+    }
+    let source_coefficient: f64 = b.axisymmetric_coefficient();
+    let mut mean_b: CharacteristicPoint = b.clone();
+    let mut point: Option<CharacteristicPoint> = None;
+
+    for _ in 0..MAX_CORRECTOR_ITERS {
+        let (x, r) = intersect_lines(b.x, b.r, mean_b.right_running_slope(), 0.0, 0.0, 0.0)?;
+        let source_b: f64 = source_coefficient * distance(b.x, b.r, x, r);
+        let prandtl_meyer_angle: f64 = b.flow_angle + b.prandtl_meyer_angle + source_b;
+        let next: CharacteristicPoint = CharacteristicPoint::from_prandtl_meyer_angle(x, r, 0.0, prandtl_meyer_angle, specific_heat_ratio)?;
+
+        let converged: bool = match &point {
+            Some(previous) => (previous.x - next.x).abs() < CORRECTOR_TOLERANCE,
+            None => false,
+        };
+        // only the slope is averaged, the source coefficient stays fixed
+        mean_b = averaged(b, &next);
+        point = Some(next);
+        if converged {
+            break;
+        }
+    }
+
+    point.ok_or(InletError::NoSolution("math error"))
+}
+
+pub fn shock_point(
+    x: f64,
+    r: f64,
+    upstream_mach: f64,
+    upstream_flow_angle: f64,
+    shock_angle: f64, // measured counterclockwise from the upstream flow direction
+    specific_heat_ratio: f64,
+) -> Result<CharacteristicPoint, InletError> {
+    // state immediately behind an oblique shock, the flow turning towards the shock
+    let deflection_angle: f64 = utils::obliqueshock::calc_deflection_angle(upstream_mach, shock_angle, specific_heat_ratio)?;
+    let downstream_mach: f64 = utils::obliqueshock::calc_downstream_mach_from_shock_angle(upstream_mach, shock_angle, specific_heat_ratio)?;
+    CharacteristicPoint::new(x, r, upstream_flow_angle + deflection_angle, downstream_mach, specific_heat_ratio)
+}
+
+pub fn streamline_point(
+    start: &CharacteristicPoint,
+    first: &CharacteristicPoint,  // ends of a characteristic segment the streamline crosses
+    second: &CharacteristicPoint,
+    specific_heat_ratio: f64,
+) -> Result<Option<CharacteristicPoint>, InletError> {
+    // the streamline from start is intersected with the segment and the flow state is
+    // interpolated along it, returning none if the streamline misses the segment
+    let segment_x: f64 = second.x - first.x;
+    let segment_r: f64 = second.r - first.r;
+    let mut flow_angle: f64 = start.flow_angle;
+    let mut point: Option<CharacteristicPoint> = None;
+
+    for _ in 0..MAX_CORRECTOR_ITERS {
+        // solve start + t (cos, sin) = first + s (segment) for the segment fraction s
+        let (sin_angle, cos_angle) = flow_angle.sin_cos();
+        let determinant: f64 = segment_x * sin_angle - segment_r * cos_angle;
+        if determinant.abs() < 1e-15 {
+            return Ok(None);
+        }
+        let mut s: f64 = ((start.x - first.x) * sin_angle - (start.r - first.r) * cos_angle) / determinant;
+        if point.is_none() && !(0.0..=1.0).contains(&s) {
+            return Ok(None);
+        }
+        // the predictor decides which segment is crossed, the corrector stays on it
+        s = s.clamp(0.0, 1.0);
+        let next: CharacteristicPoint = CharacteristicPoint::from_prandtl_meyer_angle(
+            first.x + s * segment_x,
+            first.r + s * segment_r,
+            first.flow_angle + s * (second.flow_angle - first.flow_angle),
+            first.prandtl_meyer_angle + s * (second.prandtl_meyer_angle - first.prandtl_meyer_angle),
+            specific_heat_ratio,
+        )?;
+
+        let converged: bool = match &point {
+            Some(previous) => (previous.flow_angle - next.flow_angle).abs() < CORRECTOR_TOLERANCE,
+            None => false,
+        };
+        flow_angle = 0.5 * (start.flow_angle + next.flow_angle);
+        point = Some(next);
+        if converged {
+            break;
+        }
+    }
+
+    Ok(point)
+}
+
+#[derive(Debug)]
+pub struct CharacteristicNet {
+    // each line is the c- characteristic from one point of the initial data line, listed
+    // from that point downstream, so lines[m][j] lies on the c+ characteristic from
+    // initial point m - j
+    lines: Vec<Vec<CharacteristicPoint>>,
+}
+
+impl CharacteristicNet {
+    pub fn lines(&self) -> &[Vec<CharacteristicPoint>] {
+        &self.lines
+    }
+}
+
+pub fn solve_initial_value_line(
+    initial_line: &[CharacteristicPoint], // ordered so each c+ characteristic runs below the next
+    specific_heat_ratio: f64,
+) -> Result<CharacteristicNet, InletError> {
+    // march the net over the domain of dependence of a non-characteristic data line
+    let mut lines: Vec<Vec<CharacteristicPoint>> = Vec::with_capacity(initial_line.len());
+
+    for (m, initial_point) in initial_line.iter().enumerate() {
+        let mut line: Vec<CharacteristicPoint> = Vec::with_capacity(m + 1);
+        line.push(initial_point.clone());
+        for j in 1..=m {
+            let point: CharacteristicPoint = interior_point(&lines[m - 1][j - 1], &line[j - 1], specific_heat_ratio)?;
+            line.push(point);
+        }
+        lines.push(line);
+    }
+
+    Ok(CharacteristicNet { lines })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamlineEnd {
+    AxialFlow,      // the flow angle passed through zero
+    LowerBoundary,  // left below the c+ characteristic from the first initial point
+    UpperBoundary,  // left above the c- characteristic from the last initial point
+}
+
+#[derive(Debug)]
+pub struct Streamline {
+    pub points: Vec<CharacteristicPoint>,
+    pub end: StreamlineEnd,
+}
+
+pub fn trace_streamline(
+    net: &CharacteristicNet,
+    start_index: usize, // index of the initial data point the streamline leaves from
+    specific_heat_ratio: f64,
+) -> Result<Streamline, InletError> {
+    // follow the streamline across successive c- characteristics until it leaves the net
+    // or the flow becomes axial
+    let lines: &[Vec<CharacteristicPoint>] = &net.lines;
+    if start_index >= lines.len() {
+        return Err(InletError::InvalidParameter { name: "streamline start index", value: start_index as f64 });
+    }
+
+    let mut points: Vec<CharacteristicPoint> = vec![lines[start_index][0].clone()];
+    let mut segment: usize = 0;
+
+    for line in lines.iter().skip(start_index + 1) {
+        let start: &CharacteristicPoint = &points[points.len() - 1];
+        let mut crossing: Option<CharacteristicPoint> = None;
+        for j in segment..line.len() - 1 {
+            if let Some(point) = streamline_point(start, &line[j], &line[j + 1], specific_heat_ratio)? {
+                crossing = Some(point);
+                segment = j;
+                break;
+            }
+        }
+
+        let point: CharacteristicPoint = match crossing {
+            Some(point) => point,
+            None => return Ok(Streamline { points, end: StreamlineEnd::LowerBoundary }),
+        };
+
+        if point.flow_angle.signum() != start.flow_angle.signum() {
+            // interpolate onto the point where the flow is axial
+            let fraction: f64 = start.flow_angle / (start.flow_angle - point.flow_angle);
+            let axial: CharacteristicPoint = CharacteristicPoint::from_prandtl_meyer_angle(
+                start.x + fraction * (point.x - start.x),
+                start.r + fraction * (point.r - start.r),
+                0.0,
+                start.prandtl_meyer_angle + fraction * (point.prandtl_meyer_angle - start.prandtl_meyer_angle),
+                specific_heat_ratio,
+            )?;
+            points.push(axial);
+            return Ok(Streamline { points, end: StreamlineEnd::AxialFlow });
+        }
+        points.push(point);
+    }
+
+    Ok(Streamline { points, end: StreamlineEnd::UpperBoundary })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interior_point_uniform_flow() {
+        // axial uniform flow has no source term and must be left unchanged
+        let specific_heat_ratio = 1.4;
+        let a = CharacteristicPoint::new(0.0, 1.0, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let b = CharacteristicPoint::new(0.0, 2.0, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let p = interior_point(&a, &b, specific_heat_ratio).expect("valid interior point");
+        // characteristics at +-30 degrees meet half way between the points
+        let expected_x = 0.5 / (PI_6).tan();
+        assert!((p.x - expected_x).abs() < 1e-9);
+        assert!((p.r - 1.5).abs() < 1e-9);
+        assert!(p.flow_angle.abs() < 1e-12);
+        assert!((p.mach_number - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_axis_point() {
+        // uniform axial flow reaches the axis along the mach line
+        let specific_heat_ratio = 1.4;
+        let b = CharacteristicPoint::new(0.0, 1.0, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let p = axis_point(&b, specific_heat_ratio).expect("valid axis point");
+        assert!(p.r.abs() < 1e-12);
+        assert!((p.x - 1.0 / PI_6.tan()).abs() < 1e-9);
+        assert!((p.mach_number - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wall_point() {
+        // a wall aligned with uniform axial flow leaves the flow unchanged
+        let specific_heat_ratio = 1.4;
+        let a = CharacteristicPoint::new(0.0, 1.0, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let p = wall_point(&a, 0.0, 2.0, 0.0, specific_heat_ratio).expect("valid wall point");
+        assert!((p.r - 2.0).abs() < 1e-12);
+        assert!((p.x - 1.0 / PI_6.tan()).abs() < 1e-9);
+        assert!((p.mach_number - 2.0).abs() < 1e-6);
+
+        // turning the upper wall down into the flow compresses it
+        let p = wall_point(&a, 0.0, 2.0, -0.05, specific_heat_ratio).expect("valid wall point");
+        assert!(p.mach_number < 2.0);
+        assert!((p.flow_angle + 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_shock_point() {
+        // the shock point reproduces the oblique shock relations
+        let specific_heat_ratio = 1.4;
+        let shock_angle = std::f64::consts::PI / 4.0;
+        let p = shock_point(0.0, 1.0, 2.0, 0.0, shock_angle, specific_heat_ratio).expect("valid shock point");
+        let deflection_angle = utils::obliqueshock::calc_deflection_angle(2.0, shock_angle, specific_heat_ratio)
+            .expect("valid deflection angle");
+        assert!((p.flow_angle - deflection_angle).abs() < 1e-12);
+        assert!(p.mach_number > 1.0 && p.mach_number < 2.0);
+    }
+
+    #[test]
+    fn test_streamline_point() {
+        // a horizontal streamline crosses a vertical segment at the start height
+        let specific_heat_ratio = 1.4;
+        let start = CharacteristicPoint::new(0.0, 1.5, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let first = CharacteristicPoint::new(1.0, 2.0, 0.0, 2.0, specific_heat_ratio).expect("valid point");
+        let second = CharacteristicPoint::new(1.0, 1.0, 0.0, 3.0, specific_heat_ratio).expect("valid point");
+        let p = streamline_point(&start, &first, &second, specific_heat_ratio)
+            .expect("valid streamline point")
+            .expect("streamline crosses the segment");
+        assert!((p.x - 1.0).abs() < 1e-12);
+        assert!((p.r - 1.5).abs() < 1e-12);
+        assert!(p.mach_number > 2.0 && p.mach_number < 3.0);
+
+        // a segment entirely above the streamline is missed
+        let second = CharacteristicPoint::new(1.0, 1.8, 0.0, 3.0, specific_heat_ratio).expect("valid point");
+        assert!(streamline_point(&start, &first, &second, specific_heat_ratio)
+            .expect("valid streamline point")
+            .is_none());
+    }
+
+    const PI_6: f64 = std::f64::consts::PI / 6.0;
+}
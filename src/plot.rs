@@ -0,0 +1,624 @@
+#![allow(dead_code)]
+
+use std::fmt::Write;
+use crate::error::InletError;
+
+// figure layout in pixels
+const PLOT_SIZE: f64 = 1000.0;
+const MIN_PLOT_HEIGHT: f64 = 200.0;
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 30.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 70.0;
+const TICK_LENGTH: f64 = 6.0;
+const TARGET_TICKS: f64 = 6.0;
+const LINE_WIDTH: f64 = 2.0;
+const FONT_SCALE: usize = 2;
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const FOREGROUND: [u8; 3] = [0, 0, 0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+    DashDot,
+}
+
+impl LineStyle {
+    fn dash_pattern(&self) -> &'static [f64] {
+        // alternating on and off lengths in pixels
+        match self {
+            LineStyle::Solid => &[],
+            LineStyle::Dashed => &[12.0, 8.0],
+            LineStyle::DashDot => &[18.0, 6.0, 3.0, 6.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    x_coords: Vec<f64>,
+    y_coords: Vec<f64>,
+    colour: [u8; 3],
+    style: LineStyle,
+}
+
+#[derive(Debug, Default)]
+pub struct Figure {
+    // a line plot drawn with equal axis scales, so contours keep their true shape
+    series: Vec<Series>,
+    x_label: String,
+    y_label: String,
+}
+
+struct Layout {
+    // data bounds and the pixel geometry they map onto
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+    plot_width: f64,
+    plot_height: f64,
+}
+
+impl Layout {
+    fn width(&self) -> usize {
+        (MARGIN_LEFT + self.plot_width + MARGIN_RIGHT).ceil() as usize
+    }
+
+    fn height(&self) -> usize {
+        (MARGIN_TOP + self.plot_height + MARGIN_BOTTOM).ceil() as usize
+    }
+
+    fn to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            MARGIN_LEFT + (x - self.x_min) / (self.x_max - self.x_min) * self.plot_width,
+            MARGIN_TOP + (self.y_max - y) / (self.y_max - self.y_min) * self.plot_height,
+        )
+    }
+}
+
+impl Figure {
+    pub fn new(x_label: &str, y_label: &str) -> Self {
+        Figure {
+            series: Vec::new(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+        }
+    }
+
+    pub fn add_series(&mut self, x_coords: &[f64], y_coords: &[f64], colour: [u8; 3], style: LineStyle) {
+        self.series.push(Series {
+            x_coords: x_coords.to_vec(),
+            y_coords: y_coords.to_vec(),
+            colour,
+            style,
+        });
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), InletError> {
+        // pick the format from the file extension
+        let extension: String = filename.rsplit('.').next().unwrap_or("").to_lowercase();
+        let bytes: Vec<u8> = match extension.as_str() {
+            "svg" => self.render_svg()?.into_bytes(),
+            "png" => self.render_png()?,
+            _ => return Err(InletError::UnsupportedFormat { filename: filename.to_string() }),
+        };
+        std::fs::write(filename, bytes).map_err(|source| InletError::Io { filename: filename.to_string(), source })
+    }
+
+    fn layout(&self) -> Result<Layout, InletError> {
+        let points = || self.series.iter().flat_map(|series| series.x_coords.iter().zip(&series.y_coords));
+        if points().any(|(x, y)| !x.is_finite() || !y.is_finite()) {
+            return Err(InletError::NoSolution("cannot plot non-finite values"));
+        }
+        let x_min: f64 = points().map(|(x, _)| *x).fold(f64::INFINITY, f64::min);
+        let x_max: f64 = points().map(|(x, _)| *x).fold(f64::NEG_INFINITY, f64::max);
+        let y_min: f64 = points().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+        let y_max: f64 = points().map(|(_, y)| *y).fold(f64::NEG_INFINITY, f64::max);
+        if x_min >= x_max && y_min >= y_max {
+            return Err(InletError::InvalidInlet("nothing to plot"));
+        }
+
+        // pad the data and keep one scale for both axes
+        let padding: f64 = 0.05 * (x_max - x_min).max(y_max - y_min);
+        let (mut x_min, mut x_max) = (x_min - padding, x_max + padding);
+        let (mut y_min, mut y_max) = (y_min - padding, y_max + padding);
+        let mut scale: f64 = PLOT_SIZE / (x_max - x_min);
+        if (y_max - y_min) * scale > PLOT_SIZE {
+            scale = PLOT_SIZE / (y_max - y_min);
+            let x_centre: f64 = 0.5 * (x_min + x_max);
+            x_min = x_centre - 0.5 * PLOT_SIZE / scale;
+            x_max = x_centre + 0.5 * PLOT_SIZE / scale;
+        }
+        if (y_max - y_min) * scale < MIN_PLOT_HEIGHT {
+            let y_centre: f64 = 0.5 * (y_min + y_max);
+            y_min = y_centre - 0.5 * MIN_PLOT_HEIGHT / scale;
+            y_max = y_centre + 0.5 * MIN_PLOT_HEIGHT / scale;
+        }
+
+        Ok(Layout {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+            plot_width: (x_max - x_min) * scale,
+            plot_height: (y_max - y_min) * scale,
+        })
+    }
+
+    pub fn render_svg(&self) -> Result<String, InletError> {
+        let layout: Layout = self.layout()?;
+        let (width, height) = (layout.width(), layout.height());
+        let mut svg: String = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#);
+        let _ = writeln!(svg, r#"<rect width="{width}" height="{height}" fill="{}"/>"#, svg_colour(BACKGROUND));
+
+        for series in &self.series {
+            let points: Vec<String> = series.x_coords.iter()
+                .zip(&series.y_coords)
+                .map(|(x, y)| {
+                    let (px, py) = layout.to_pixel(*x, *y);
+                    format!("{px:.2},{py:.2}")
+                })
+                .collect();
+            let dash_array: String = series.style.dash_pattern()
+                .iter()
+                .map(|length| length.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            let _ = write!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{LINE_WIDTH}""#,
+                points.join(" "), svg_colour(series.colour)
+            );
+            if !dash_array.is_empty() {
+                let _ = write!(svg, r#" stroke-dasharray="{dash_array}""#);
+            }
+            let _ = writeln!(svg, "/>");
+        }
+
+        // frame, ticks and labels
+        let (left, top) = (MARGIN_LEFT, MARGIN_TOP);
+        let (right, bottom) = (left + layout.plot_width, top + layout.plot_height);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{left}" y="{top}" width="{:.2}" height="{:.2}" fill="none" stroke="{}"/>"#,
+            layout.plot_width, layout.plot_height, svg_colour(FOREGROUND)
+        );
+        for (tick, label) in ticks(layout.x_min, layout.x_max) {
+            let (px, _) = layout.to_pixel(tick, layout.y_min);
+            let _ = writeln!(svg, r#"<line x1="{px:.2}" y1="{bottom:.2}" x2="{px:.2}" y2="{:.2}" stroke="black"/>"#, bottom + TICK_LENGTH);
+            let _ = writeln!(
+                svg,
+                r#"<text x="{px:.2}" y="{:.2}" font-family="monospace" font-size="14" text-anchor="middle">{label}</text>"#,
+                bottom + TICK_LENGTH + 16.0
+            );
+        }
+        for (tick, label) in ticks(layout.y_min, layout.y_max) {
+            let (_, py) = layout.to_pixel(layout.x_min, tick);
+            let _ = writeln!(svg, r#"<line x1="{:.2}" y1="{py:.2}" x2="{left:.2}" y2="{py:.2}" stroke="black"/>"#, left - TICK_LENGTH);
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" font-family="monospace" font-size="14" text-anchor="end">{label}</text>"#,
+                left - TICK_LENGTH - 4.0, py + 5.0
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" font-family="monospace" font-size="16" text-anchor="middle">{}</text>"#,
+            0.5 * (left + right), bottom + MARGIN_BOTTOM - 16.0, escape_xml(&self.x_label)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" font-family="monospace" font-size="16" text-anchor="middle" transform="rotate(-90 {:.2} {:.2})">{}</text>"#,
+            20.0, 0.5 * (top + bottom), 20.0, 0.5 * (top + bottom), escape_xml(&self.y_label)
+        );
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    pub fn render_png(&self) -> Result<Vec<u8>, InletError> {
+        let layout: Layout = self.layout()?;
+        let mut canvas: Canvas = Canvas::new(layout.width(), layout.height());
+
+        for series in &self.series {
+            let points: Vec<(f64, f64)> = series.x_coords.iter()
+                .zip(&series.y_coords)
+                .map(|(x, y)| layout.to_pixel(*x, *y))
+                .collect();
+            canvas.draw_polyline(&points, series.colour, series.style);
+        }
+
+        // frame, ticks and labels
+        let (left, top) = (MARGIN_LEFT, MARGIN_TOP);
+        let (right, bottom) = (left + layout.plot_width, top + layout.plot_height);
+        let frame: [(f64, f64); 5] = [(left, top), (right, top), (right, bottom), (left, bottom), (left, top)];
+        canvas.draw_polyline(&frame, FOREGROUND, LineStyle::Solid);
+        let glyph_height: f64 = (7 * FONT_SCALE) as f64;
+        for (tick, label) in ticks(layout.x_min, layout.x_max) {
+            let (px, _) = layout.to_pixel(tick, layout.y_min);
+            canvas.draw_polyline(&[(px, bottom), (px, bottom + TICK_LENGTH)], FOREGROUND, LineStyle::Solid);
+            let x: f64 = px - 0.5 * text_width(&label) as f64;
+            canvas.draw_text(&label, x, bottom + TICK_LENGTH + 6.0, FOREGROUND);
+        }
+        for (tick, label) in ticks(layout.y_min, layout.y_max) {
+            let (_, py) = layout.to_pixel(layout.x_min, tick);
+            canvas.draw_polyline(&[(left - TICK_LENGTH, py), (left, py)], FOREGROUND, LineStyle::Solid);
+            let x: f64 = left - TICK_LENGTH - 6.0 - text_width(&label) as f64;
+            canvas.draw_text(&label, x, py - 0.5 * glyph_height, FOREGROUND);
+        }
+        let x: f64 = 0.5 * (left + right) - 0.5 * text_width(&self.x_label) as f64;
+        canvas.draw_text(&self.x_label, x, bottom + MARGIN_BOTTOM - 2.0 * glyph_height, FOREGROUND);
+        canvas.draw_text(&self.y_label, 10.0, 0.5 * (top + bottom) - 0.5 * glyph_height, FOREGROUND);
+
+        Ok(canvas.encode_png())
+    }
+}
+
+fn svg_colour(colour: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn ticks(min: f64, max: f64) -> Vec<(f64, String)> {
+    // tick positions on a 1, 2, 5 progression with matching decimal places
+    let raw_step: f64 = (max - min) / TARGET_TICKS;
+    let magnitude: f64 = 10.0_f64.powf(raw_step.log10().floor());
+    let step: f64 = match raw_step / magnitude {
+        normalised if normalised < 1.5 => magnitude,
+        normalised if normalised < 3.5 => 2.0 * magnitude,
+        normalised if normalised < 7.5 => 5.0 * magnitude,
+        _ => 10.0 * magnitude,
+    };
+    let decimals: usize = (-step.log10().floor()).max(0.0) as usize;
+    let mut ticks: Vec<(f64, String)> = Vec::new();
+    let mut index: f64 = (min / step).ceil();
+    while index * step <= max {
+        let tick: f64 = index * step;
+        // avoid printing negative zero
+        let label: String = format!("{:.*}", decimals, if tick.abs() < 0.5 * step { 0.0 } else { tick });
+        ticks.push((tick, label));
+        index += 1.0;
+    }
+    ticks
+}
+
+struct Canvas {
+    // rgb raster with the origin at the top left
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: BACKGROUND.repeat(width * height),
+        }
+    }
+
+    fn set_pixel(&mut self, x: i64, y: i64, colour: [u8; 3]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let index: usize = 3 * (y as usize * self.width + x as usize);
+        self.pixels[index..index + 3].copy_from_slice(&colour);
+    }
+
+    fn draw_polyline(&mut self, points: &[(f64, f64)], colour: [u8; 3], style: LineStyle) {
+        // sample each segment at half pixel spacing, stamping a square pen while the
+        // distance along the line falls in an on section of the dash pattern
+        let pattern: &[f64] = style.dash_pattern();
+        let period: f64 = pattern.iter().sum();
+        let half_width: i64 = (LINE_WIDTH / 2.0) as i64;
+        let mut distance: f64 = 0.0;
+        for segment in points.windows(2) {
+            let (x1, y1) = segment[0];
+            let (x2, y2) = segment[1];
+            let length: f64 = (x2 - x1).hypot(y2 - y1);
+            let samples: usize = (2.0 * length).ceil().max(1.0) as usize;
+            for i in 0..=samples {
+                let s: f64 = i as f64 / samples as f64;
+                if !pattern.is_empty() {
+                    let mut phase: f64 = (distance + s * length) % period;
+                    let mut on: bool = true;
+                    for section in pattern {
+                        if phase < *section {
+                            break;
+                        }
+                        phase -= section;
+                        on = !on;
+                    }
+                    if !on {
+                        continue;
+                    }
+                }
+                let x: i64 = (x1 + s * (x2 - x1)).round() as i64;
+                let y: i64 = (y1 + s * (y2 - y1)).round() as i64;
+                for dx in -half_width..half_width.max(1) {
+                    for dy in -half_width..half_width.max(1) {
+                        self.set_pixel(x + dx, y + dy, colour);
+                    }
+                }
+            }
+            distance += length;
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, x: f64, y: f64, colour: [u8; 3]) {
+        // characters missing from the font are left blank
+        let (x, y) = (x.round() as i64, y.round() as i64);
+        for (position, character) in text.chars().enumerate() {
+            let rows: [u8; 7] = glyph(character);
+            let origin_x: i64 = x + (position * 6 * FONT_SCALE) as i64;
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0x10 >> column) == 0 {
+                        continue;
+                    }
+                    for sx in 0..FONT_SCALE {
+                        for sy in 0..FONT_SCALE {
+                            self.set_pixel(
+                                origin_x + (column * FONT_SCALE + sx) as i64,
+                                y + (row * FONT_SCALE + sy) as i64,
+                                colour,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        // 8 bit rgb image with every scanline unfiltered
+        let stride: usize = 3 * self.width + 1;
+        let mut raw: Vec<u8> = Vec::with_capacity(stride * self.height);
+        for row in self.pixels.chunks(3 * self.width) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut header: Vec<u8> = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png: Vec<u8> = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_compress(&raw, &[3, stride]));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start: usize = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc: u32 = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xffff_ffff;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b): (u32, u32) = (1, 0);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// deflate length and distance code tables
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter { bytes: Vec::new(), buffer: 0, count: 0 }
+    }
+
+    fn write_bits(&mut self, value: u32, bits: u32) {
+        // least significant bit first, as deflate packs its data
+        self.buffer |= value << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn write_code(&mut self, code: u32, bits: u32) {
+        // huffman codes are packed most significant bit first
+        let mut reversed: u32 = 0;
+        for i in 0..bits {
+            reversed |= ((code >> i) & 1) << (bits - 1 - i);
+        }
+        self.write_bits(reversed, bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+
+    fn write_literal_length(&mut self, symbol: u16) {
+        // fixed huffman code for the literal and length alphabet
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol as u32, 8),
+            144..=255 => self.write_code(0x190 + (symbol - 144) as u32, 9),
+            256..=279 => self.write_code((symbol - 256) as u32, 7),
+            _ => self.write_code(0xc0 + (symbol - 280) as u32, 8),
+        }
+    }
+
+    fn write_match(&mut self, length: usize, distance: usize) {
+        let length_code: usize = LENGTH_BASES.iter().rposition(|base| *base as usize <= length).unwrap_or(0);
+        self.write_literal_length(257 + length_code as u16);
+        self.write_bits((length - LENGTH_BASES[length_code] as usize) as u32, LENGTH_EXTRA_BITS[length_code] as u32);
+        let distance_code: usize = DISTANCE_BASES.iter().rposition(|base| *base as usize <= distance).unwrap_or(0);
+        self.write_code(distance_code as u32, 5);
+        self.write_bits((distance - DISTANCE_BASES[distance_code] as usize) as u32, DISTANCE_EXTRA_BITS[distance_code] as u32);
+    }
+}
+
+fn zlib_compress(data: &[u8], distances: &[usize]) -> Vec<u8> {
+    // a single fixed huffman deflate block, only looking for matches at the given
+    // distances, which for an image are the previous pixel and the row above
+    let mut writer: BitWriter = BitWriter::new();
+    writer.write_bits(1, 1); // final block
+    writer.write_bits(1, 2); // fixed huffman codes
+
+    let mut position: usize = 0;
+    while position < data.len() {
+        let mut best_length: usize = 0;
+        let mut best_distance: usize = 0;
+        for distance in distances.iter().filter(|distance| **distance <= position && **distance <= 32768) {
+            let limit: usize = MAX_MATCH.min(data.len() - position);
+            let length: usize = (0..limit)
+                .take_while(|i| data[position + i] == data[position + i - distance])
+                .count();
+            if length > best_length {
+                best_length = length;
+                best_distance = *distance;
+            }
+        }
+        if best_length >= MIN_MATCH {
+            writer.write_match(best_length, best_distance);
+            position += best_length;
+        } else {
+            writer.write_literal_length(data[position] as u16);
+            position += 1;
+        }
+    }
+    writer.write_literal_length(256); // end of block
+
+    let mut zlib: Vec<u8> = vec![0x78, 0x01];
+    zlib.extend(writer.finish());
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 6 * FONT_SCALE
+}
+
+fn glyph(character: char) -> [u8; 7] {
+    // 5 by 7 bitmap rows, most significant of the five bits on the left
+    match character {
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+        'x' => [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11],
+        'r' => [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10],
+        'y' => [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e],
+        _ => [0x00; 7],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Figure {
+        let mut figure = Figure::new("x", "r");
+        figure.add_series(&[0.0, 1.0, 1.0, 0.0, 0.0], &[0.0, 0.0, 1.0, 1.0, 0.0], FOREGROUND, LineStyle::Solid);
+        figure.add_series(&[0.0, 1.0], &[0.5, 0.5], [255, 0, 0], LineStyle::DashDot);
+        figure
+    }
+
+    #[test]
+    fn test_checksums() {
+        // standard check values for crc32 and adler32
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        // each series becomes a polyline with its own colour and dash pattern
+        let svg = square().render_svg().expect("valid svg");
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("stroke=\"#ff0000\" stroke-width=\"2\" stroke-dasharray=\"18 6 3 6\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_render_png() {
+        // a valid png signature and header for the equal aspect layout
+        let png = square().render_png().expect("valid png");
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']);
+        assert_eq!(&png[12..16], b"IHDR");
+        let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]) as f64;
+        let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]) as f64;
+        assert_eq!(width, (MARGIN_LEFT + PLOT_SIZE + MARGIN_RIGHT).ceil());
+        assert_eq!(height, (MARGIN_TOP + PLOT_SIZE + MARGIN_BOTTOM).ceil());
+        assert_eq!(crc32(&png[12..29]), u32::from_be_bytes([png[29], png[30], png[31], png[32]]));
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    #[test]
+    fn test_ticks() {
+        // ticks fall on a 1, 2, 5 progression inside the range
+        let ticks = ticks(-0.3, 2.7);
+        let labels: Vec<&str> = ticks.iter().map(|(_, label)| label.as_str()).collect();
+        assert_eq!(labels, vec!["0.0", "0.5", "1.0", "1.5", "2.0", "2.5"]);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(Figure::new("x", "r").render_svg().is_err());
+        let mut figure = Figure::new("x", "r");
+        figure.add_series(&[0.0, f64::NAN], &[0.0, 1.0], FOREGROUND, LineStyle::Solid);
+        assert!(figure.render_png().is_err());
+        assert!(square().save("contour.jpg").is_err());
+    }
+}
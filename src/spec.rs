@@ -0,0 +1,246 @@
+use std::path::Path;
+
+use crate::cli::{self, Design, DesignSpec, InletType};
+
+// a spec file holds one design per [name] section as key = value lines, using the
+// command line flag names, for example
+//
+//     # mach 6 busemann for the wind tunnel model
+//     [busemann-m6]
+//     inlet = busemann
+//     method = tm
+//     freestream-mach = 6
+//     exit-mach = 2.5
+//     gamma = 1.4
+//     scale = 0.05
+//     out = busemann-m6.csv
+//     out = busemann-m6.svg
+//
+// lines before the first section form a design named after the file, and a '#' starts a
+// comment only at the start of a line or after whitespace, so out = run#1.csv keeps its name
+
+struct Section {
+    name: String,
+    line_number: usize,
+    entries: Vec<(usize, String, String)>,
+}
+
+fn build_spec(section: &Section) -> Result<DesignSpec, String> {
+    // the inlet type decides the spec, every other key is applied in file order
+    let inlet_entry = section.entries.iter().find(|(_, key, _)| key == "inlet")
+        .ok_or(format!("line {}: design '{}' has no inlet type", section.line_number, section.name))?;
+    let inlet_type: InletType = InletType::parse(&inlet_entry.2)
+        .ok_or(format!("line {}: unknown inlet type '{}'", inlet_entry.0, inlet_entry.2))?;
+    let mut spec: DesignSpec = DesignSpec::new(inlet_type);
+    spec.name = section.name.clone();
+    for (line_number, key, value) in section.entries.iter().filter(|(_, key, _)| key != "inlet") {
+        spec.set(key, value).map_err(|err| format!("line {}: {}", line_number, err))?;
+    }
+    Ok(spec)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut previous: Option<char> = None;
+    for (index, character) in line.char_indices() {
+        if character == '#' && previous.is_none_or(char::is_whitespace) {
+            return &line[..index];
+        }
+        previous = Some(character);
+    }
+    line
+}
+
+fn push_section(sections: &mut Vec<Section>, section: Section, named: bool) -> Result<(), String> {
+    // only the unnamed lines before the first section may be left empty
+    if !section.entries.is_empty() {
+        sections.push(section);
+    } else if named {
+        return Err(format!("line {}: design '{}' has no keys", section.line_number, section.name));
+    }
+    Ok(())
+}
+
+pub fn parse_spec(text: &str, default_name: &str) -> Result<Vec<DesignSpec>, String> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current: Section = Section { name: default_name.to_string(), line_number: 1, entries: Vec::new() };
+    let mut named: bool = false;
+    for (index, line) in text.lines().enumerate() {
+        let line_number: usize = index + 1;
+        let line: &str = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            let name: &str = name.trim();
+            if name.is_empty() {
+                return Err(format!("line {}: empty design name", line_number));
+            }
+            push_section(&mut sections, current, named)?;
+            current = Section { name: name.to_string(), line_number, entries: Vec::new() };
+            named = true;
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or(format!("line {}: expected key = value", line_number))?;
+        let key: String = key.trim().replace('_', "-");
+        current.entries.push((line_number, key, value.trim().to_string()));
+    }
+    push_section(&mut sections, current, named)?;
+    if sections.is_empty() {
+        return Err("no designs found".to_string());
+    }
+    for (index, section) in sections.iter().enumerate() {
+        if sections[..index].iter().any(|other| other.name == section.name) {
+            return Err(format!("line {}: duplicate design name '{}'", section.line_number, section.name));
+        }
+    }
+    sections.iter().map(build_spec).collect()
+}
+
+pub fn load_spec_file(filename: &str) -> Result<Vec<DesignSpec>, String> {
+    // outputs are written relative to the spec file so runs are independent of the working directory
+    let path: &Path = Path::new(filename);
+    let text: String = std::fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", filename, err))?;
+    let default_name: &str = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("design");
+    let mut specs: Vec<DesignSpec> = parse_spec(&text, default_name).map_err(|err| format!("{}: {}", filename, err))?;
+    let directory: &Path = path.parent().unwrap_or(Path::new(""));
+    for spec in specs.iter_mut() {
+        for output in spec.outputs.iter_mut() {
+            *output = directory.join(&*output).to_string_lossy().into_owned();
+        }
+    }
+    Ok(specs)
+}
+
+fn format_row(columns: &[String], widths: &[usize]) -> String {
+    columns.iter().zip(widths).map(|(column, width)| format!("{:<width$}", column, width = width))
+        .collect::<Vec<String>>().join("  ").trim_end().to_string()
+}
+
+pub fn format_summary(rows: &[(DesignSpec, Result<Design, String>)]) -> String {
+    // one line per design with its performance, or the reason it failed
+    let header: Vec<String> = ["name", "inlet", "M1", "M3", "p0 ratio", "p ratio", "T ratio", "length", "status"]
+        .iter().map(|column| column.to_string()).collect();
+    let mut table: Vec<Vec<String>> = vec![header];
+    for (spec, result) in rows {
+        let mut columns: Vec<String> = vec![spec.name.clone(), spec.inlet_type.name().to_string()];
+        match result {
+            Ok(design) => {
+                let x_coords: &[f64] = design.inlet.contour().x_coords();
+                let length: f64 = x_coords.last().copied().unwrap_or(0.0) - x_coords.first().copied().unwrap_or(0.0);
+                columns.extend([
+                    format!("{:.4}", design.inlet.freestream_mach()),
+                    format!("{:.4}", design.inlet.exit_mach()),
+                    format!("{:.4}", design.performance.total_pressure_ratio),
+                    format!("{:.4}", design.performance.static_pressure_ratio),
+                    format!("{:.4}", design.performance.static_temperature_ratio),
+                    format!("{:.4}", length),
+                    "ok".to_string(),
+                ]);
+            }
+            Err(err) => {
+                columns.extend(std::iter::repeat_n("-".to_string(), 6));
+                columns.push(err.clone());
+            }
+        }
+        table.push(columns);
+    }
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|index| table.iter().map(|columns| columns[index].len()).max().unwrap_or(0))
+        .collect();
+    table.iter().map(|columns| format_row(columns, &widths)).collect::<Vec<String>>().join("\n")
+}
+
+pub fn run(filenames: &[String]) -> Result<(), String> {
+    // every design is attempted, failures are reported in the summary rather than stopping the run
+    if filenames.is_empty() {
+        return Err("missing spec file".to_string());
+    }
+    let mut specs: Vec<DesignSpec> = Vec::new();
+    for filename in filenames {
+        specs.extend(load_spec_file(filename)?);
+    }
+    let mut rows: Vec<(DesignSpec, Result<Design, String>)> = Vec::new();
+    for spec in specs {
+        let result: Result<Design, String> = cli::design(&spec)
+            .and_then(|design| cli::write_outputs(&design.inlet, &spec.outputs).map(|_| design));
+        rows.push((spec, result));
+    }
+    println!("{}", format_summary(&rows));
+    let failures: usize = rows.iter().filter(|(_, result)| result.is_err()).count();
+    if failures > 0 {
+        return Err(format!("{} of {} designs failed", failures, rows.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "
+        # two designs sharing a file
+        [busemann-m6]
+        inlet = busemann
+        freestream-mach = 6
+        exit-mach = 2.5   # design point
+        scale = 0.1
+        out = busemann-m6.csv
+
+        [truncated]
+        inlet = tb
+        freestream_mach = 6
+        exit_mach = 2.5
+        truncation-fraction = 0.3
+    ";
+
+    #[test]
+    fn test_parse_spec() {
+        // sections become named designs with comments stripped
+        let specs = parse_spec(SPEC, "file").expect("valid spec");
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].name, "busemann-m6");
+        assert_eq!(specs[0].inlet_type, InletType::Busemann);
+        assert_eq!(specs[0].exit_mach, Some(2.5));
+        assert_eq!(specs[0].scale, 0.1);
+        assert_eq!(specs[0].outputs, vec!["busemann-m6.csv".to_string()]);
+        assert_eq!(specs[1].inlet_type, InletType::TruncatedBusemann);
+        assert_eq!(specs[1].freestream_mach, Some(6.0));
+
+        // a file without sections is one design named after the file
+        let specs = parse_spec("inlet = icfa\nfreestream-mach = 6", "single").expect("valid spec");
+        assert_eq!(specs[0].name, "single");
+
+        // a '#' inside a value is kept, one after whitespace starts a comment
+        let specs = parse_spec("inlet = busemann\nout = out#1.csv # first run\nout = a#b#c.svg", "file").expect("valid spec");
+        assert_eq!(specs[0].outputs, vec!["out#1.csv".to_string(), "a#b#c.svg".to_string()]);
+    }
+
+    #[test]
+    fn test_format_summary() {
+        // designs are tabulated with failures kept in line
+        let specs = parse_spec(SPEC, "file").expect("valid spec");
+        let rows: Vec<(DesignSpec, Result<Design, String>)> = specs.into_iter()
+            .map(|spec| { let result = cli::design(&spec); (spec, result) })
+            .collect();
+        let summary = format_summary(&rows);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("name"));
+        assert!(lines[1].starts_with("busemann-m6") && lines[1].ends_with("ok"));
+        assert!(lines[2].contains("truncated-busemann"));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(parse_spec("", "file").is_err());
+        assert!(parse_spec("freestream-mach = 6", "file").is_err());
+        assert!(parse_spec("inlet = scramjet", "file").is_err());
+        assert!(parse_spec("inlet = busemann\nexit-mach 2.5", "file").is_err());
+        assert!(parse_spec("[a]\ninlet = busemann\n[a]\ninlet = icfa", "file").is_err());
+        assert!(parse_spec("[]\ninlet = busemann", "file").is_err());
+        assert!(parse_spec("[a]\n[b]\ninlet = busemann", "file").is_err_and(|err| err.contains("'a'")));
+        assert!(parse_spec("[a]\ninlet = busemann\n[b]", "file").is_err_and(|err| err.contains("'b'")));
+        assert!(run(&[]).is_err());
+    }
+}
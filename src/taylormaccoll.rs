@@ -1,5 +1,14 @@
 #![allow(dead_code)]
 
+use crate::{
+    error::InletError,
+    utils::{
+        equilibrium::Isentrope,
+        gas::GasModel,
+        numerics::{self, Event, EventAction, EventDirection, OdeSolution},
+    },
+};
+
 #[derive(Debug, Clone)]
 pub struct VelocityVector {
     pub radial_component: f64,      // u
@@ -22,6 +31,7 @@ pub struct VelocityVectorDerivative {
     tangential_derivative: f64, // dv / dθ
 }
 
+#[derive(Debug, Clone)]
 pub struct TaylorMaccollResult {
     // a struct to organise the results from integrating taylor maccoll equations
     pub velocity_vector: VelocityVector,
@@ -29,10 +39,23 @@ pub struct TaylorMaccollResult {
     pub theta: f64,
 }
 
+impl TaylorMaccollResult {
+    pub fn flow_angle(&self) -> f64 {
+        // angle of the velocity to the axis, negative when turning towards it
+        let axial_component: f64 = 
+            self.velocity_vector.radial_component * self.theta.cos() -
+            self.velocity_vector.tangential_component * self.theta.sin();
+        let normal_component: f64 = 
+            self.velocity_vector.radial_component * self.theta.sin() +
+            self.velocity_vector.tangential_component * self.theta.cos();
+        normal_component.atan2(axial_component)
+    }
+}
+
 pub fn streamline(
     velocity_vector: &VelocityVector,
     r: f64 // the radial distance 
-) -> Result<f64, &'static str> {
+) -> Result<f64, InletError> {
     let r_derivative: f64 = 
         r * velocity_vector.radial_component / velocity_vector.tangential_component;
 
@@ -43,7 +66,7 @@ pub fn taylor_maccoll(
     velocity_vector: &VelocityVector,
     theta: f64,
     gamma: f64,
-) -> Result<VelocityVectorDerivative, &'static str>{
+) -> Result<VelocityVectorDerivative, InletError>{
     // get radial and tangential velocity components
     let u: f64 = velocity_vector.radial_component;
     let v: f64 = velocity_vector.tangential_component;
@@ -63,6 +86,49 @@ pub fn taylor_maccoll(
     })
 }
 
+fn derivatives(theta: f64, state: &[f64; 3], gamma: f64) -> Result<[f64; 3], InletError> {
+    // taylor maccoll and streamline equations for the state (u, v, r)
+    let velocity_vector: VelocityVector = VelocityVector {
+        radial_component: state[0],
+        tangential_component: state[1],
+    };
+    let derivative: VelocityVectorDerivative = taylor_maccoll(&velocity_vector, theta, gamma)?;
+    Ok([
+        derivative.radial_derivative,
+        derivative.tangential_derivative,
+        streamline(&velocity_vector, state[2])?,
+    ])
+}
+
+fn cross_stream_mach(theta: f64, state: &[f64; 3]) -> f64 {
+    state[0] * theta.sin() + state[1] * theta.cos()
+}
+
+fn normal_mach_excess(_theta: f64, state: &[f64; 3]) -> f64 {
+    state[1].abs() - 1.0
+}
+
+// the integration ends exactly on the ray where the flow is parallel to the axis, or at the
+// last step before the normal mach number to the ray passes through one, which marks the
+// mach cone coinciding with the freestream ray or the singular ray behind an incident shock
+const EVENTS: [Event<'static, 3>; 2] = [
+    Event { function: &cross_stream_mach, direction: EventDirection::Rising, action: EventAction::Stop },
+    Event { function: &normal_mach_excess, direction: EventDirection::Either, action: EventAction::StopBefore },
+];
+
+fn to_results(solution: OdeSolution<3>) -> Vec<TaylorMaccollResult> {
+    solution.x.into_iter().zip(solution.y)
+        .map(|(theta, state)| TaylorMaccollResult {
+            velocity_vector: VelocityVector {
+                radial_component: state[0],
+                tangential_component: state[1],
+            },
+            radial_distance: state[2],
+            theta,
+        })
+        .collect()
+}
+
 pub fn solve_taylor_maccoll(
     initial_velocity_vector: VelocityVector,
     initial_theta: f64,
@@ -70,111 +136,261 @@ pub fn solve_taylor_maccoll(
     initial_r: f64,
     gamma: f64,
     steps: usize,
-) -> Result<Vec<TaylorMaccollResult>, &'static str> {
-    // 4th order runge kutta integration of taylor maccoll equations
-    // set step size
-    let h: f64 = (final_theta - initial_theta) / steps as f64;
-    
-    // vector to store results
-    let mut results: Vec<TaylorMaccollResult> = Vec::new();
-
-    // push initial values to results
-    results.push(TaylorMaccollResult{
-        velocity_vector: initial_velocity_vector.clone(),
-        radial_distance: initial_r,
-        theta: initial_theta,
-    });
-
-    // starting conditions for integration
-    let mut current_radial_velocity: f64 = initial_velocity_vector.radial_component;
-    let mut current_tangential_velocity: f64 = initial_velocity_vector.tangential_component;
-    let mut current_radial_distance: f64 = initial_r;
-    let mut current_theta: f64 = initial_theta;
-
-    for _ in 0..steps {
-        // first runge-kutta constant
-        let k1_velocity_vector: VelocityVector = 
-            VelocityVector {
-                radial_component: current_radial_velocity,
-                tangential_component: current_tangential_velocity,
-            };
-        let k1: VelocityVectorDerivative = taylor_maccoll(&k1_velocity_vector, current_theta, gamma)?;
-        let k1_radial: f64 = h * k1.radial_derivative;
-        let k1_tangential: f64 = h * k1.tangential_derivative;
-        let k1_contour: f64 = h * streamline(&k1_velocity_vector, current_radial_distance)?;
-
-        // second runge-kutta constant
-        let k2_velocity_vector: VelocityVector = 
-            VelocityVector {
-                radial_component: current_radial_velocity + (0.5 * k1_radial),
-                tangential_component: current_tangential_velocity + (0.5 * k1_tangential),
-            };
-        let k2: VelocityVectorDerivative = taylor_maccoll(&k2_velocity_vector, current_theta + (0.5 * h), gamma)?;
-        let k2_radial: f64 = h * k2.radial_derivative;
-        let k2_tangential: f64 = h * k2.tangential_derivative;
-        let k2_contour: f64 = h * streamline(&k2_velocity_vector, current_radial_distance + (0.5 * k1_contour))?;
-
-        // third runge-kutta constant
-        let k3_velocity_vector: VelocityVector = 
-            VelocityVector {
-                radial_component: current_radial_velocity + (0.5 * k2_radial),
-                tangential_component: current_tangential_velocity + (0.5 * k2_tangential),
-            };
-        let k3: VelocityVectorDerivative = taylor_maccoll(&k3_velocity_vector, current_theta + (0.5 * h), gamma)?;
-        let k3_radial: f64 = h * k3.radial_derivative;
-        let k3_tangential: f64 = h * k3.tangential_derivative;
-        let k3_conour: f64 = h * streamline(&k3_velocity_vector, current_radial_distance + (0.5 * k2_contour))?;
-
-        // fourth runge-kutta constant
-        let k4_velocity_vector: VelocityVector =
-            VelocityVector {
-                radial_component: current_radial_velocity + k3_radial,
-                tangential_component: current_tangential_velocity + k3_tangential,
-            };
-        let k4: VelocityVectorDerivative = taylor_maccoll(&k4_velocity_vector, current_theta + h, gamma)?;
-        let k4_radial: f64 = h * k4.radial_derivative;
-        let k4_tangential: f64 = h * k4.tangential_derivative;
-        let k4_contour: f64 = h * streamline(&k4_velocity_vector, current_radial_distance + k3_conour)?;
-
-        // calculate subsequent velocity components, radial distance and theta
-        let next_radial_velocity: f64 = 
-            current_radial_velocity + (1.0 / 6.0) *
-            (k1_radial + 2.0 * k2_radial + 2.0 * k3_radial + k4_radial);
-        let next_tangential_velocity: f64 = 
-            current_tangential_velocity + (1.0 / 6.0) *
-            (k1_tangential + 2.0 * k2_tangential + 2.0 * k3_tangential + k4_tangential);
-        let next_radial_distance: f64 = 
-            current_radial_distance + (1.0 / 6.0) *
-            (k1_contour + 2.0 * k2_contour + 2.0 * k3_conour + k4_contour);
-        let next_theta: f64 = current_theta + h;
-
-        // break clause
-        let cross_stream_mach: f64 = 
-            next_radial_velocity * next_theta.sin() +
-            next_tangential_velocity * next_theta.cos();
-            
-        if cross_stream_mach >= 0.0 {
-            break; // freestream condition reached
+) -> Result<Vec<TaylorMaccollResult>, InletError> {
+    // 4th order runge kutta integration of taylor maccoll equations with a fixed step
+    let solution: OdeSolution<3> = numerics::rk4(
+        &|theta: f64, state: &[f64; 3]| derivatives(theta, state, gamma),
+        initial_theta,
+        [initial_velocity_vector.radial_component, initial_velocity_vector.tangential_component, initial_r],
+        final_theta,
+        steps,
+        &EVENTS,
+    )?;
+    Ok(to_results(solution))
+}
+
+pub fn solve_taylor_maccoll_adaptive(
+    initial_velocity_vector: VelocityVector,
+    initial_theta: f64,
+    final_theta: f64,
+    initial_r: f64,
+    gamma: f64,
+    tolerance: f64,
+    max_step: f64,
+) -> Result<Vec<TaylorMaccollResult>, InletError> {
+    // dormand prince integration of taylor maccoll equations with local error control
+    let solution: OdeSolution<3> = numerics::rk45(
+        &|theta: f64, state: &[f64; 3]| derivatives(theta, state, gamma),
+        initial_theta,
+        [initial_velocity_vector.radial_component, initial_velocity_vector.tangential_component, initial_r],
+        final_theta,
+        tolerance,
+        max_step,
+        &EVENTS,
+    )?;
+    Ok(to_results(solution))
+}
+
+fn gas_derivatives(theta: f64, state: &[f64; 3], sound_speed: f64) -> [f64; 3] {
+    // taylor maccoll equations in velocity components over the limiting velocity, where the
+    // speed of sound comes from the gas model rather than the perfect gas energy equation
+    let (u, v, r) = (state[0], state[1], state[2]);
+    let sound_speed_squared: f64 = sound_speed.powi(2);
+    [
+        v,
+        (u * v.powi(2) - sound_speed_squared * (2.0 * u + v / theta.tan())) / (sound_speed_squared - v.powi(2)),
+        r * u / v,
+    ]
+}
+
+fn solve_taylor_maccoll_for_sound_speed(
+    initial: TaylorMaccollResult,
+    initial_sound_speed: f64,
+    final_theta: f64,
+    stagnation_enthalpy: f64,
+    sound_speed: &impl Fn(f64) -> Result<f64, InletError>,
+    tolerance: f64,
+    max_step: f64,
+) -> Result<Vec<TaylorMaccollResult>, InletError> {
+    // dormand prince integration given the mach number components on the initial ray and
+    // the speed of sound as a function of the static enthalpy, which the stagnation enthalpy
+    // fixes along the way
+    let initial_velocity_vector: &VelocityVector = &initial.velocity_vector;
+    let limiting_velocity: f64 = (2.0 * stagnation_enthalpy).sqrt();
+    let scaled_sound_speed = |state: &[f64; 3]| -> Result<f64, InletError> {
+        Ok(sound_speed(stagnation_enthalpy * (1.0 - state[0].powi(2) - state[1].powi(2)))? / limiting_velocity)
+    };
+    let normal_sound_speed_excess = |_theta: f64, state: &[f64; 3]| {
+        scaled_sound_speed(state).map_or(f64::NAN, |sound_speed| state[1].abs() - sound_speed)
+    };
+    let events: [Event<3>; 2] = [
+        Event { function: &cross_stream_mach, direction: EventDirection::Rising, action: EventAction::Stop },
+        Event { function: &normal_sound_speed_excess, direction: EventDirection::Either, action: EventAction::StopBefore },
+    ];
+
+    // trial states past the limiting velocity have no speed of sound, and are left non
+    // finite so the integrator rejects the step rather than failing
+    let scale: f64 = initial_sound_speed / limiting_velocity;
+    let solution: OdeSolution<3> = numerics::rk45(
+        &|theta: f64, state: &[f64; 3]| {
+            Ok(scaled_sound_speed(state).map_or([f64::NAN; 3], |sound_speed| gas_derivatives(theta, state, sound_speed)))
+        },
+        initial.theta,
+        [initial_velocity_vector.radial_component * scale, initial_velocity_vector.tangential_component * scale, initial.radial_distance],
+        final_theta,
+        tolerance,
+        max_step,
+        &events,
+    )?;
+
+    // convert back to mach number components with the local speed of sound
+    let mut results: Vec<TaylorMaccollResult> = Vec::with_capacity(solution.x.len());
+    for (theta, state) in solution.x.into_iter().zip(solution.y) {
+        let local_sound_speed: f64 = scaled_sound_speed(&state)?;
+        results.push(TaylorMaccollResult {
+            velocity_vector: VelocityVector {
+                radial_component: state[0] / local_sound_speed,
+                tangential_component: state[1] / local_sound_speed,
+            },
+            radial_distance: state[2],
+            theta,
+        });
+    }
+    Ok(results)
+}
+
+pub fn solve_taylor_maccoll_for_gas(
+    initial: TaylorMaccollResult,
+    final_theta: f64,
+    initial_temperature: f64,
+    gas: &impl GasModel,
+    tolerance: f64,
+    max_step: f64,
+) -> Result<Vec<TaylorMaccollResult>, InletError> {
+    // any gas model, given the static temperature on the initial ray
+    if initial_temperature.is_nan() || initial_temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "temperature", value: initial_temperature });
+    }
+    let initial_sound_speed: f64 = gas.speed_of_sound(initial_temperature);
+    let stagnation_enthalpy: f64 = gas.enthalpy(initial_temperature)
+        + (initial.velocity_vector.get_mach_number() * initial_sound_speed).powi(2) / 2.0;
+    solve_taylor_maccoll_for_sound_speed(
+        initial,
+        initial_sound_speed,
+        final_theta,
+        stagnation_enthalpy,
+        &|enthalpy: f64| Ok(gas.speed_of_sound(gas.temperature_from_enthalpy(enthalpy)?)),
+        tolerance,
+        max_step,
+    )
+}
+
+pub fn solve_taylor_maccoll_for_equilibrium_air(
+    initial: TaylorMaccollResult,
+    final_theta: f64,
+    isentrope: &Isentrope,
+    tolerance: f64,
+    max_step: f64,
+) -> Result<Vec<TaylorMaccollResult>, InletError> {
+    // equilibrium air, the conical flow keeping to the isentrope it starts on
+    let initial_enthalpy: f64 = isentrope.enthalpy_at_mach(initial.velocity_vector.get_mach_number())?;
+    solve_taylor_maccoll_for_sound_speed(
+        initial,
+        isentrope.sound_speed_at_enthalpy(initial_enthalpy)?,
+        final_theta,
+        isentrope.stagnation_enthalpy(),
+        &|enthalpy: f64| isentrope.sound_speed_at_enthalpy(enthalpy),
+        tolerance,
+        max_step,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use crate::{busemann, utils::{equilibrium::EquilibriumAir, gas::{CaloricallyPerfectGas, ThermallyPerfectGas}}};
+
+    fn terminal_shock_state(exit_mach: f64, conical_shock_angle: f64) -> VelocityVector {
+        let shock = busemann::calc_terminal_shock(exit_mach, conical_shock_angle, 1.4).expect("valid terminal shock");
+        VelocityVector {
+            radial_component: shock.upstream_mach * shock.shock_angle.cos(),
+            tangential_component: -shock.upstream_mach * shock.shock_angle.sin(),
         }
+    }
 
-        // append results to results vec
-        results.push(
-            TaylorMaccollResult {
-                velocity_vector: VelocityVector {
-                    radial_component: next_radial_velocity,
-                    tangential_component: next_tangential_velocity,
-                },
-                radial_distance: next_radial_distance,
-                theta: next_theta,
-            }
-        );
-
-        // update current values with their subsequent value and loop
-        current_radial_velocity = next_radial_velocity;
-        current_tangential_velocity = next_tangential_velocity;
-        current_radial_distance = next_radial_distance;
-        current_theta = next_theta;
+    fn initial_ray(conical_shock_angle: f64) -> TaylorMaccollResult {
+        TaylorMaccollResult {
+            velocity_vector: terminal_shock_state(2.5, conical_shock_angle),
+            radial_distance: 1.0,
+            theta: conical_shock_angle,
+        }
     }
 
-    Ok(results)
-}
\ No newline at end of file
+    #[test]
+    fn test_solve_taylor_maccoll_adaptive() {
+        // the adaptive solution ends with the flow parallel to the axis and agrees with fixed steps
+        let conical_shock_angle: f64 = 0.3;
+        let adaptive = solve_taylor_maccoll_adaptive(terminal_shock_state(2.5, conical_shock_angle), conical_shock_angle, PI, 1.0, 1.4, 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let fixed = solve_taylor_maccoll(terminal_shock_state(2.5, conical_shock_angle), conical_shock_angle, PI, 1.0, 1.4, 10000)
+            .expect("valid taylor maccoll solution");
+        let adaptive_end = adaptive.last().expect("leading edge");
+        let fixed_end = fixed.last().expect("leading edge");
+        assert!(adaptive_end.flow_angle().abs() < 1e-12);
+        assert!(adaptive.windows(2).all(|pair| pair[1].theta > pair[0].theta));
+        assert!(adaptive.windows(2).all(|pair| pair[1].theta - pair[0].theta <= 1e-3 + 1e-15));
+        let adaptive_mach: f64 = adaptive_end.velocity_vector.get_mach_number();
+        assert!((adaptive_mach - fixed_end.velocity_vector.get_mach_number()).abs() / adaptive_mach < 1e-4);
+    }
+
+    #[test]
+    fn test_adaptive_leading_edge_convergence() {
+        // the leading edge position settles as the tolerance tightens, unlike a fixed step count
+        let conical_shock_angle: f64 = 0.3;
+        let leading_edge = |tolerance: f64| {
+            let results = solve_taylor_maccoll_adaptive(terminal_shock_state(2.5, conical_shock_angle), conical_shock_angle, PI, 1.0, 1.4, tolerance, 1e-3)
+                .expect("valid taylor maccoll solution");
+            let last = results.last().expect("leading edge");
+            (last.radial_distance * last.theta.cos(), last.radial_distance * last.theta.sin())
+        };
+        let (coarse_x, coarse_r) = leading_edge(1e-8);
+        let (fine_x, fine_r) = leading_edge(1e-11);
+        assert!((coarse_x - fine_x).abs() / fine_x.abs() < 1e-6);
+        assert!((coarse_r - fine_r).abs() / fine_r < 1e-8);
+    }
+
+    #[test]
+    fn test_solve_taylor_maccoll_for_gas() {
+        // a calorically perfect gas follows the mach number form, and a thermally perfect
+        // gas at a cold stagnation temperature stays close to it
+        let conical_shock_angle: f64 = 0.3;
+        let mach_form = solve_taylor_maccoll_adaptive(terminal_shock_state(2.5, conical_shock_angle), conical_shock_angle, PI, 1.0, 1.4, 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let perfect = solve_taylor_maccoll_for_gas(initial_ray(conical_shock_angle), PI, 100.0, &CaloricallyPerfectGas::air(), 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let cold = solve_taylor_maccoll_for_gas(initial_ray(conical_shock_angle), PI, 100.0, &ThermallyPerfectGas::air(), 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let mach_form_end = mach_form.last().expect("leading edge");
+        for end in [perfect.last().expect("leading edge"), cold.last().expect("leading edge")] {
+            assert!(end.flow_angle().abs() < 1e-12);
+            assert!((end.theta - mach_form_end.theta).abs() < 1e-4);
+            assert!((end.velocity_vector.get_mach_number() / mach_form_end.velocity_vector.get_mach_number() - 1.0).abs() < 1e-4);
+        }
+        let perfect_end = perfect.last().expect("leading edge");
+        assert!((perfect_end.velocity_vector.get_mach_number() / mach_form_end.velocity_vector.get_mach_number() - 1.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_solve_taylor_maccoll_for_equilibrium_air() {
+        // cold equilibrium air neither vibrates nor dissociates, so follows the mach number form
+        let conical_shock_angle: f64 = 0.3;
+        let mach_form = solve_taylor_maccoll_adaptive(terminal_shock_state(2.5, conical_shock_angle), conical_shock_angle, PI, 1.0, 1.4, 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let air = EquilibriumAir::air();
+        let initial_state = air.state(1e5, 150.0).expect("valid state");
+        let initial = initial_ray(conical_shock_angle);
+        let velocity: f64 = initial.velocity_vector.get_mach_number() * air.speed_of_sound(&initial_state).expect("valid state");
+        let stagnation_enthalpy: f64 = initial_state.enthalpy + velocity.powi(2) / 2.0;
+
+        // the isentrope is tabulated from a low enough pressure to reach the leading edge
+        let base_state = air.state_from_entropy(1e4, initial_state.entropy).expect("valid state");
+        let isentrope = air.isentrope(&base_state, stagnation_enthalpy).expect("valid isentrope");
+        let equilibrium = solve_taylor_maccoll_for_equilibrium_air(initial, PI, &isentrope, 1e-10, 1e-3)
+            .expect("valid taylor maccoll solution");
+        let (mach_form_end, end) = (mach_form.last().expect("leading edge"), equilibrium.last().expect("leading edge"));
+        assert!(end.flow_angle().abs() < 1e-12);
+        assert!((end.theta - mach_form_end.theta).abs() < 1e-4);
+        assert!((end.velocity_vector.get_mach_number() / mach_form_end.velocity_vector.get_mach_number() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        let initial = terminal_shock_state(2.5, 0.3);
+        assert!(solve_taylor_maccoll_adaptive(initial.clone(), 0.3, PI, 1.0, 1.4, 0.0, 1e-3).is_err());
+        assert!(solve_taylor_maccoll_adaptive(initial.clone(), 0.3, PI, 1.0, 1.4, f64::NAN, 1e-3).is_err());
+        assert!(solve_taylor_maccoll_adaptive(initial, 0.3, PI, 1.0, 1.4, 1e-10, -1e-3).is_err());
+        assert!(solve_taylor_maccoll_for_gas(initial_ray(0.3), PI, 0.0, &CaloricallyPerfectGas::air(), 1e-10, 1e-3).is_err());
+    }
+}
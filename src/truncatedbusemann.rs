@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+
+use crate::{
+    busemann,
+    error::InletError,
+    inlet::Inlet,
+    utils::{self, gas::GasModel, normalshock::ShockJump},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Truncation {
+    WallAngle(f64),      // cut where the wall has turned this far from the freestream direction
+    LengthFraction(f64), // cut off this fraction of the full axial length
+}
+
+#[derive(Debug)]
+pub struct TruncatedBusemann {
+    // a struct to organise a truncated inlet and what truncation costs and saves
+    pub inlet: Inlet,
+    pub leading_edge_deflection_angle: f64, // wall angle at the new leading edge
+    pub leading_edge_shock_angle: f64,
+    pub capture_area_ratio: f64,            // truncated to full capture area
+    pub length_saving: f64,                 // fraction of the full axial length removed
+    pub leading_edge_total_pressure_ratio: f64,
+    pub total_pressure_ratio: f64,          // leading edge and terminal shocks together
+}
+
+fn find_start_index(inlet: &Inlet, truncation: Truncation) -> Result<usize, InletError> {
+    // first contour point at or beyond the requested cut
+    let x_coords: &[f64] = inlet.contour().x_coords();
+    if inlet.contour().len() < 2 || inlet.flowfield().len() != inlet.contour().len() {
+        return Err(InletError::InvalidInlet("inlet has no flowfield to truncate"));
+    }
+    let last_index: usize = x_coords.len() - 1;
+    let start_index: usize = match truncation {
+        Truncation::WallAngle(wall_angle) => {
+            if wall_angle < 0.0 {
+                return Err(InletError::InvalidParameter { name: "truncation angle", value: wall_angle });
+            }
+            inlet.flowfield()
+                .iter()
+                .position(|result| -result.flow_angle() >= wall_angle)
+                .ok_or(InletError::InvalidParameter { name: "truncation angle beyond the maximum wall angle", value: wall_angle })?
+        }
+        Truncation::LengthFraction(length_fraction) => {
+            if !(0.0..1.0).contains(&length_fraction) {
+                return Err(InletError::InvalidParameter { name: "truncation length fraction", value: length_fraction });
+            }
+            let cut_x: f64 = x_coords[0] + length_fraction * (x_coords[last_index] - x_coords[0]);
+            x_coords.iter().position(|x| *x >= cut_x).ok_or(InletError::NoSolution("math error"))?
+        }
+    };
+    if start_index >= last_index {
+        return Err(InletError::NoSolution("truncation removes the whole contour"));
+    }
+    Ok(start_index)
+}
+
+fn truncate_at(
+    inlet: &Inlet,
+    start_index: usize,
+    leading_edge_shock: impl Fn(f64) -> Result<(f64, f64), InletError>,
+    terminal_total_pressure_ratio: f64,
+) -> Result<TruncatedBusemann, InletError> {
+    // the freestream meets the wall at its local angle, so a leading edge shock, giving its
+    // angle and stagnation pressure ratio for the wall deflection, now does the turning the
+    // removed isentropic section used to do
+    let x_coords: &[f64] = inlet.contour().x_coords();
+    let y_coords: &[f64] = inlet.contour().y_coords();
+    let full_length: f64 = x_coords[x_coords.len() - 1] - x_coords[0];
+    let leading_edge_deflection_angle: f64 = -inlet.flowfield()[start_index].flow_angle();
+    let (leading_edge_shock_angle, leading_edge_total_pressure_ratio) = if leading_edge_deflection_angle > 0.0 {
+        leading_edge_shock(leading_edge_deflection_angle)?
+    } else {
+        (utils::isentropic::calc_mach_angle_from_mach(inlet.freestream_mach())?, 1.0)
+    };
+
+    Ok(TruncatedBusemann {
+        inlet: inlet.truncate(start_index, inlet.contour_total_pressure_ratio() * leading_edge_total_pressure_ratio),
+        leading_edge_deflection_angle,
+        leading_edge_shock_angle,
+        capture_area_ratio: (y_coords[start_index] / y_coords[0]).powi(2),
+        length_saving: (x_coords[start_index] - x_coords[0]) / full_length,
+        leading_edge_total_pressure_ratio,
+        total_pressure_ratio: leading_edge_total_pressure_ratio * terminal_total_pressure_ratio,
+    })
+}
+
+pub fn truncate(inlet: &Inlet, truncation: Truncation) -> Result<TruncatedBusemann, InletError> {
+    let start_index: usize = find_start_index(inlet, truncation)?;
+    let freestream_mach: f64 = inlet.freestream_mach();
+    let specific_heat_ratio: f64 = inlet.specific_heat_ratio();
+    let leading_edge_shock = |deflection_angle: f64| -> Result<(f64, f64), InletError> {
+        let shock_angle: f64 = utils::obliqueshock::calc_shock_angle(freestream_mach, deflection_angle, specific_heat_ratio)?;
+        let total_pressure_ratio: f64 = utils::obliqueshock::calc_stagnation_pressure_ratio(freestream_mach, shock_angle, specific_heat_ratio)?;
+        Ok((shock_angle, total_pressure_ratio))
+    };
+    let performance: busemann::BusemannPerformance = busemann::calc_performance_from_terminal_shock(
+        freestream_mach,
+        inlet.exit_mach(),
+        inlet.terminal_shock_angle(),
+        specific_heat_ratio,
+    )?;
+    truncate_at(inlet, start_index, leading_edge_shock, performance.total_pressure_ratio)
+}
+
+pub fn truncate_for_gas(inlet: &Inlet, truncation: Truncation, gas: &impl GasModel) -> Result<TruncatedBusemann, InletError> {
+    // as truncate for an inlet designed in the gas model, whose freestream temperature sets
+    // the leading edge shock
+    let freestream_temperature: f64 = inlet.freestream_temperature()
+        .ok_or(InletError::InvalidInlet("inlet was not designed with a gas model"))?;
+    let start_index: usize = find_start_index(inlet, truncation)?;
+    let freestream_mach: f64 = inlet.freestream_mach();
+    let leading_edge_shock = |deflection_angle: f64| -> Result<(f64, f64), InletError> {
+        let shock_angle: f64 = utils::obliqueshock::calc_shock_angle_for_gas(freestream_mach, deflection_angle, freestream_temperature, gas)?;
+        let jump: ShockJump = utils::obliqueshock::calc_shock_jump_for_gas(freestream_mach, shock_angle, freestream_temperature, gas)?;
+        Ok((shock_angle, jump.stagnation_pressure_ratio))
+    };
+    let performance: busemann::BusemannPerformance = busemann::calc_performance_for_gas(
+        freestream_mach,
+        freestream_temperature,
+        inlet.exit_mach(),
+        inlet.terminal_shock_angle(),
+        gas,
+    )?;
+    truncate_at(inlet, start_index, leading_edge_shock, performance.total_pressure_ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::gas::{CaloricallyPerfectGas, ThermallyPerfectGas};
+
+    #[test]
+    fn test_truncate_wall_angle() {
+        // truncating at a wall angle shortens the inlet at the cost of a leading edge shock
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let wall_angle = 5.0_f64.to_radians();
+        let truncated = truncate(&inlet, Truncation::WallAngle(wall_angle)).expect("valid truncation");
+        assert!(truncated.leading_edge_deflection_angle >= wall_angle);
+        assert!(truncated.leading_edge_deflection_angle < wall_angle + 1e-3);
+        let mach_angle = (1.0 / 6.0_f64).asin();
+        assert!(truncated.leading_edge_shock_angle > mach_angle);
+        assert!(truncated.capture_area_ratio < 1.0);
+        assert!(truncated.length_saving > 0.0 && truncated.length_saving < 1.0);
+        assert!(truncated.leading_edge_total_pressure_ratio < 1.0);
+        assert!(truncated.total_pressure_ratio < truncated.leading_edge_total_pressure_ratio);
+        assert!(truncated.inlet.contour().len() < inlet.contour().len());
+    }
+
+    #[test]
+    fn test_truncate_length_fraction() {
+        // the requested fraction of the axial length is removed
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let truncated = truncate(&inlet, Truncation::LengthFraction(0.4)).expect("valid truncation");
+        assert!((truncated.length_saving - 0.4).abs() < 1e-2);
+        let x_coords = truncated.inlet.contour().x_coords();
+        assert_eq!(x_coords[x_coords.len() - 1], inlet.contour().x_coords()[inlet.contour().len() - 1]);
+    }
+
+    #[test]
+    fn test_truncate_for_gas() {
+        // in a perfect gas the gas model truncation matches the closed form one, while the
+        // thermally perfect leading edge shock is weaker in stagnation pressure loss
+        let perfect = CaloricallyPerfectGas::air();
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        let gas_inlet = busemann::calc_contour_from_machs_for_gas(6.0, 220.0, 2.5, &perfect).expect("valid busemann inlet");
+        let wall_angle = 5.0_f64.to_radians();
+        let truncated = truncate(&inlet, Truncation::WallAngle(wall_angle)).expect("valid truncation");
+        let gas_truncated = truncate_for_gas(&gas_inlet, Truncation::WallAngle(wall_angle), &perfect).expect("valid truncation");
+        assert!((gas_truncated.leading_edge_shock_angle - truncated.leading_edge_shock_angle).abs() < 1e-4);
+        assert!((gas_truncated.total_pressure_ratio - truncated.total_pressure_ratio).abs() < 1e-4);
+        assert!(gas_truncated.inlet.wall_states().is_some_and(|states| states.len() == gas_truncated.inlet.contour().len()));
+        assert!((gas_truncated.inlet.freestream_temperature().expect("freestream temperature") - 220.0).abs() < 1e-3);
+
+        let air = ThermallyPerfectGas::air();
+        let real_inlet = busemann::calc_contour_from_machs_for_gas(8.0, 220.0, 3.0, &air).expect("valid busemann inlet");
+        let real_truncated = truncate_for_gas(&real_inlet, Truncation::LengthFraction(0.4), &air).expect("valid truncation");
+        assert!(real_truncated.leading_edge_total_pressure_ratio < 1.0);
+        assert!(real_truncated.total_pressure_ratio < real_truncated.leading_edge_total_pressure_ratio);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        let inlet = busemann::calc_contour_from_machs(6.0, 2.5, &CaloricallyPerfectGas::air()).expect("valid busemann inlet");
+        assert!(truncate(&inlet, Truncation::LengthFraction(1.0)).is_err());
+        assert!(truncate(&inlet, Truncation::WallAngle(-0.1)).is_err());
+        assert!(truncate(&inlet, Truncation::WallAngle(1.0)).is_err());
+        // a mach number design has no freestream temperature for the gas model
+        assert!(truncate_for_gas(&inlet, Truncation::LengthFraction(0.4), &CaloricallyPerfectGas::air()).is_err());
+    }
+}
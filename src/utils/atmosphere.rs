@@ -0,0 +1,210 @@
+use crate::error::InletError;
+
+// constants of the us standard atmosphere 1976, which takes its own values for the gas
+// constant and the molar mass of sea level air
+const UNIVERSAL_GAS_CONSTANT: f64 = 8.31432;     // J/(mol K)
+const SEA_LEVEL_MOLAR_MASS: f64 = 0.0289644;     // kg/mol
+const STANDARD_GRAVITY: f64 = 9.80665;           // m/s^2
+const EARTH_RADIUS: f64 = 6356766.0;             // m, for the geopotential altitude
+const SPECIFIC_HEAT_RATIO: f64 = 1.4;
+const SEA_LEVEL_PRESSURE: f64 = 101325.0;        // Pa
+const SUTHERLAND_CONSTANT: f64 = 1.458e-6;       // kg/(m s K^0.5)
+const SUTHERLAND_TEMPERATURE: f64 = 110.4;       // K
+
+// geometric altitude range of the lower atmosphere in metres, above which the composition
+// is no longer uniform
+const MAX_ALTITUDE: f64 = 86000.0;
+
+// base geopotential altitude in metres, base molecular scale temperature in kelvin and
+// lapse rate in K/m of each layer up to 86 km
+const LAYERS: [(f64, f64, f64); 7] = [
+    (0.0, 288.15, -0.0065),
+    (11000.0, 216.65, 0.0),
+    (20000.0, 216.65, 0.001),
+    (32000.0, 228.65, 0.0028),
+    (47000.0, 270.65, 0.0),
+    (51000.0, 270.65, -0.0028),
+    (71000.0, 214.65, -0.002),
+];
+
+// ratio of the molar mass to its sea level value against geometric altitude in metres,
+// which falls from one above 80 km as oxygen begins to dissociate
+const MOLAR_MASS_RATIOS: [(f64, f64); 13] = [
+    (80000.0, 1.000000),
+    (80500.0, 0.999996),
+    (81000.0, 0.999989),
+    (81500.0, 0.999971),
+    (82000.0, 0.999941),
+    (82500.0, 0.999909),
+    (83000.0, 0.999870),
+    (83500.0, 0.999829),
+    (84000.0, 0.999786),
+    (84500.0, 0.999741),
+    (85000.0, 0.999694),
+    (85500.0, 0.999641),
+    (86000.0, 0.999579),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphereState {
+    // static properties of the atmosphere at one altitude, in si units
+    pub pressure: f64,
+    pub temperature: f64,
+    pub density: f64,
+    pub speed_of_sound: f64,
+    pub viscosity: f64,
+}
+
+fn layer_pressure(base_pressure: f64, base_temperature: f64, lapse_rate: f64, height: f64) -> f64 {
+    // hydrostatic pressure a geopotential height above the base of a layer
+    let exponent: f64 = STANDARD_GRAVITY * SEA_LEVEL_MOLAR_MASS / UNIVERSAL_GAS_CONSTANT;
+    if lapse_rate == 0.0 {
+        base_pressure * (-exponent * height / base_temperature).exp()
+    } else {
+        base_pressure * (base_temperature / (base_temperature + lapse_rate * height)).powf(exponent / lapse_rate)
+    }
+}
+
+fn molar_mass_ratio(altitude: f64) -> f64 {
+    // linear between the tabulated altitudes
+    if altitude <= MOLAR_MASS_RATIOS[0].0 {
+        return 1.0;
+    }
+    let index: usize = MOLAR_MASS_RATIOS.iter().position(|(upper, _)| *upper >= altitude).unwrap_or(MOLAR_MASS_RATIOS.len() - 1);
+    let (lower_altitude, lower_ratio) = MOLAR_MASS_RATIOS[index - 1];
+    let (upper_altitude, upper_ratio) = MOLAR_MASS_RATIOS[index];
+    lower_ratio + (upper_ratio - lower_ratio) * (altitude - lower_altitude) / (upper_altitude - lower_altitude)
+}
+
+pub fn calc_standard_atmosphere(altitude: f64) -> Result<AtmosphereState, InletError> {
+    // us standard atmosphere 1976 at a geometric altitude in metres. The layers are linear
+    // in molecular scale temperature against geopotential altitude, with the pressure
+    // carried up through them hydrostatically, and the kinetic temperature only departing
+    // from the molecular scale temperature above 80 km
+    if altitude.is_nan() || !(0.0..=MAX_ALTITUDE).contains(&altitude) {
+        return Err(InletError::InvalidParameter { name: "altitude", value: altitude });
+    }
+    let geopotential_altitude: f64 = EARTH_RADIUS * altitude / (EARTH_RADIUS + altitude);
+    let mut base_pressure: f64 = SEA_LEVEL_PRESSURE;
+    let mut layer: usize = 0;
+    while layer + 1 < LAYERS.len() && LAYERS[layer + 1].0 <= geopotential_altitude {
+        let (base_altitude, base_temperature, lapse_rate) = LAYERS[layer];
+        base_pressure = layer_pressure(base_pressure, base_temperature, lapse_rate, LAYERS[layer + 1].0 - base_altitude);
+        layer += 1;
+    }
+    let (base_altitude, base_temperature, lapse_rate) = LAYERS[layer];
+    let height: f64 = geopotential_altitude - base_altitude;
+    let molecular_scale_temperature: f64 = base_temperature + lapse_rate * height;
+    let pressure: f64 = layer_pressure(base_pressure, base_temperature, lapse_rate, height);
+
+    // the density and speed of sound follow from the molecular scale temperature, the
+    // viscosity from the kinetic temperature
+    let gas_constant: f64 = UNIVERSAL_GAS_CONSTANT / SEA_LEVEL_MOLAR_MASS;
+    let temperature: f64 = molecular_scale_temperature * molar_mass_ratio(altitude);
+    Ok(AtmosphereState {
+        pressure,
+        temperature,
+        density: pressure / (gas_constant * molecular_scale_temperature),
+        speed_of_sound: (SPECIFIC_HEAT_RATIO * gas_constant * molecular_scale_temperature).sqrt(),
+        viscosity: SUTHERLAND_CONSTANT * temperature.powf(1.5) / (temperature + SUTHERLAND_TEMPERATURE),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FreestreamConditions {
+    // a flight condition in the standard atmosphere, fixing the dimensional freestream
+    // that the mach number alone leaves open
+    pub altitude: f64, // geometric, in metres
+    pub mach_number: f64,
+    pub atmosphere: AtmosphereState,
+}
+
+impl FreestreamConditions {
+    pub fn from_altitude_and_mach(altitude: f64, mach_number: f64) -> Result<Self, InletError> {
+        if mach_number.is_nan() || mach_number <= 0.0 {
+            return Err(InletError::InvalidMachNumber { mach_number });
+        }
+        let atmosphere: AtmosphereState = calc_standard_atmosphere(altitude)?;
+        Ok(FreestreamConditions { altitude, mach_number, atmosphere })
+    }
+
+    pub fn from_altitude_and_dynamic_pressure(altitude: f64, dynamic_pressure: f64) -> Result<Self, InletError> {
+        // the dynamic pressure is half the specific heat ratio times the static pressure
+        // times the square of the mach number
+        if dynamic_pressure.is_nan() || dynamic_pressure <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "dynamic pressure", value: dynamic_pressure });
+        }
+        let atmosphere: AtmosphereState = calc_standard_atmosphere(altitude)?;
+        let mach_number: f64 = (2.0 * dynamic_pressure / (SPECIFIC_HEAT_RATIO * atmosphere.pressure)).sqrt();
+        Ok(FreestreamConditions { altitude, mach_number, atmosphere })
+    }
+
+    pub fn velocity(&self) -> f64 {
+        self.mach_number * self.atmosphere.speed_of_sound
+    }
+
+    pub fn dynamic_pressure(&self) -> f64 {
+        0.5 * self.atmosphere.density * self.velocity().powi(2)
+    }
+
+    pub fn unit_reynolds_number(&self) -> f64 {
+        // per metre of length
+        self.atmosphere.density * self.velocity() / self.atmosphere.viscosity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_atmosphere() {
+        // values from the tables of the us standard atmosphere 1976
+        let sea_level = calc_standard_atmosphere(0.0).expect("valid altitude");
+        assert!((sea_level.pressure - 101325.0).abs() < 1e-9);
+        assert!((sea_level.temperature - 288.15).abs() < 1e-12);
+        assert!((sea_level.density - 1.2250).abs() < 1e-4);
+        assert!((sea_level.speed_of_sound - 340.294).abs() < 1e-3);
+        assert!((sea_level.viscosity - 1.7894e-5).abs() < 1e-9);
+
+        let tables: [(f64, f64, f64, f64); 5] = [
+            // altitude, temperature, pressure, density
+            (11000.0, 216.774, 2.2700e4, 3.6480e-1),
+            (20000.0, 216.650, 5.5293e3, 8.8910e-2),
+            (50000.0, 270.650, 7.9779e1, 1.0269e-3),
+            (80000.0, 198.639, 1.0524, 1.8458e-5),
+            (86000.0, 186.867, 3.7338e-1, 6.958e-6),
+        ];
+        for (altitude, temperature, pressure, density) in tables {
+            let state = calc_standard_atmosphere(altitude).expect("valid altitude");
+            assert!((state.temperature - temperature).abs() < 2e-3);
+            assert!((state.pressure / pressure - 1.0).abs() < 2e-4);
+            assert!((state.density / density - 1.0).abs() < 5e-4);
+        }
+    }
+
+    #[test]
+    fn test_freestream_conditions() {
+        // a mach 6 flight at 25 km, and the same condition given by its dynamic pressure
+        let freestream = FreestreamConditions::from_altitude_and_mach(25000.0, 6.0).expect("valid freestream");
+        assert!((freestream.atmosphere.temperature - 221.552).abs() < 2e-3);
+        assert!((freestream.velocity() - 6.0 * 298.389).abs() < 1e-1);
+        let dynamic_pressure: f64 = 0.7 * freestream.atmosphere.pressure * 36.0;
+        assert!((freestream.dynamic_pressure() / dynamic_pressure - 1.0).abs() < 1e-12);
+        let recovered = FreestreamConditions::from_altitude_and_dynamic_pressure(25000.0, freestream.dynamic_pressure())
+            .expect("valid freestream");
+        assert!((recovered.mach_number - 6.0).abs() < 1e-12);
+        assert!((freestream.unit_reynolds_number() / 4.956e6 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(calc_standard_atmosphere(-1.0).is_err());
+        assert!(calc_standard_atmosphere(86001.0).is_err());
+        assert!(calc_standard_atmosphere(f64::NAN).is_err());
+        assert!(FreestreamConditions::from_altitude_and_mach(10000.0, 0.0).is_err());
+        assert!(FreestreamConditions::from_altitude_and_dynamic_pressure(10000.0, -1.0).is_err());
+        assert!(FreestreamConditions::from_altitude_and_dynamic_pressure(90000.0, 5e4).is_err());
+    }
+}
@@ -0,0 +1,566 @@
+#![allow(dead_code)]
+
+use super::numerics::brent;
+use crate::error::InletError;
+
+// physical constants in si units
+const UNIVERSAL_GAS_CONSTANT: f64 = 8.314462618;
+const BOLTZMANN_CONSTANT: f64 = 1.380649e-23;
+const PLANCK_CONSTANT: f64 = 6.62607015e-34;
+const AVOGADRO_CONSTANT: f64 = 6.02214076e23;
+
+// pressure of the standard state the equilibrium constants refer to, in pascals
+const STANDARD_PRESSURE: f64 = 1e5;
+
+// temperatures the five species model covers, ionisation taking over above the upper limit
+const MIN_TEMPERATURE: f64 = 50.0;
+const MAX_TEMPERATURE: f64 = 20000.0;
+
+// mole fraction of oxygen in undissociated air, with the argon counted as nitrogen
+const AIR_OXYGEN_FRACTION: f64 = 0.21;
+
+// step in the logarithm of pressure between tabulated points on an isentrope, which starts
+// at this fraction of the pressure of the state it is built from
+const ISENTROPE_STEP: f64 = 0.01;
+const ISENTROPE_LOWER_PRESSURE: f64 = 0.25;
+
+// relative step of the central differences behind the equilibrium speed of sound
+const DIFFERENCE_STEP: f64 = 1e-5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Species {
+    Nitrogen,
+    Oxygen,
+    NitricOxide,
+    AtomicNitrogen,
+    AtomicOxygen,
+}
+
+struct Diatomic {
+    rotational_temperature: f64,
+    symmetry_number: f64,
+    vibrational_temperature: f64,
+}
+
+struct SpeciesData {
+    // a rigid rotor and harmonic oscillator for the molecules plus the low lying electronic
+    // levels as degeneracy and energy over the boltzmann constant
+    molar_mass: f64,           // kg/mol
+    formation_enthalpy: f64,   // J/mol at absolute zero
+    diatomic: Option<Diatomic>,
+    electronic_levels: &'static [(f64, f64)],
+}
+
+const NITROGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0280134,
+    formation_enthalpy: 0.0,
+    diatomic: Some(Diatomic { rotational_temperature: 2.88, symmetry_number: 2.0, vibrational_temperature: 3395.0 }),
+    electronic_levels: &[(1.0, 0.0)],
+};
+
+const OXYGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0319988,
+    formation_enthalpy: 0.0,
+    diatomic: Some(Diatomic { rotational_temperature: 2.08, symmetry_number: 2.0, vibrational_temperature: 2239.0 }),
+    electronic_levels: &[(3.0, 0.0), (2.0, 11393.0), (1.0, 18985.0)],
+};
+
+const NITRIC_OXIDE: SpeciesData = SpeciesData {
+    molar_mass: 0.0300061,
+    formation_enthalpy: 89775.0,
+    diatomic: Some(Diatomic { rotational_temperature: 2.45, symmetry_number: 1.0, vibrational_temperature: 2817.0 }),
+    electronic_levels: &[(2.0, 0.0), (2.0, 174.0)],
+};
+
+const ATOMIC_NITROGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0140067,
+    formation_enthalpy: 470820.0,
+    diatomic: None,
+    electronic_levels: &[(4.0, 0.0), (10.0, 27658.0), (6.0, 41495.0)],
+};
+
+const ATOMIC_OXYGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0159994,
+    formation_enthalpy: 246790.0,
+    diatomic: None,
+    electronic_levels: &[(5.0, 0.0), (3.0, 228.0), (1.0, 326.0), (5.0, 22830.0), (1.0, 48621.0)],
+};
+
+impl Species {
+    pub const ALL: [Species; 5] = [
+        Species::Nitrogen,
+        Species::Oxygen,
+        Species::NitricOxide,
+        Species::AtomicNitrogen,
+        Species::AtomicOxygen,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Species::Nitrogen => "N2",
+            Species::Oxygen => "O2",
+            Species::NitricOxide => "NO",
+            Species::AtomicNitrogen => "N",
+            Species::AtomicOxygen => "O",
+        }
+    }
+
+    fn data(&self) -> &'static SpeciesData {
+        match self {
+            Species::Nitrogen => &NITROGEN,
+            Species::Oxygen => &OXYGEN,
+            Species::NitricOxide => &NITRIC_OXIDE,
+            Species::AtomicNitrogen => &ATOMIC_NITROGEN,
+            Species::AtomicOxygen => &ATOMIC_OXYGEN,
+        }
+    }
+
+    pub fn molar_mass(&self) -> f64 {
+        self.data().molar_mass
+    }
+
+    fn electronic_energy(&self, temperature: f64) -> (f64, f64) {
+        // partition function and mean energy over the gas constant of the electronic levels
+        let levels: &[(f64, f64)] = self.data().electronic_levels;
+        let partition_function: f64 = levels.iter().map(|(degeneracy, energy)| degeneracy * (-energy / temperature).exp()).sum();
+        let energy: f64 = levels.iter().map(|(degeneracy, energy)| degeneracy * energy * (-energy / temperature).exp()).sum::<f64>()
+            / partition_function;
+        (partition_function, energy)
+    }
+
+    pub fn enthalpy(&self, temperature: f64) -> f64 {
+        // molar enthalpy in J/mol, including the heat of formation
+        let data: &SpeciesData = self.data();
+        let (_, electronic_energy) = self.electronic_energy(temperature);
+        let mut enthalpy: f64 = data.formation_enthalpy + UNIVERSAL_GAS_CONSTANT * (2.5 * temperature + electronic_energy);
+        if let Some(diatomic) = &data.diatomic {
+            let decay: f64 = (-diatomic.vibrational_temperature / temperature).exp();
+            enthalpy += UNIVERSAL_GAS_CONSTANT * (temperature + diatomic.vibrational_temperature * decay / (1.0 - decay));
+        }
+        enthalpy
+    }
+
+    pub fn standard_entropy(&self, temperature: f64) -> f64 {
+        // molar entropy in J/(mol K) at the standard pressure, from the sackur tetrode
+        // translational entropy and the internal partition functions
+        let data: &SpeciesData = self.data();
+        let mass: f64 = data.molar_mass / AVOGADRO_CONSTANT;
+        let thermal: f64 = BOLTZMANN_CONSTANT * temperature;
+        let mut entropy: f64 = 1.5 * (2.0 * std::f64::consts::PI * mass * thermal / PLANCK_CONSTANT.powi(2)).ln()
+            + (thermal / STANDARD_PRESSURE).ln() + 2.5;
+        let (partition_function, electronic_energy) = self.electronic_energy(temperature);
+        entropy += partition_function.ln() + electronic_energy / temperature;
+        if let Some(diatomic) = &data.diatomic {
+            let x: f64 = diatomic.vibrational_temperature / temperature;
+            let decay: f64 = (-x).exp();
+            entropy += (temperature / (diatomic.symmetry_number * diatomic.rotational_temperature)).ln() + 1.0;
+            entropy += x * decay / (1.0 - decay) - (1.0 - decay).ln();
+        }
+        UNIVERSAL_GAS_CONSTANT * entropy
+    }
+
+    fn gibbs_energy(&self, temperature: f64) -> f64 {
+        // standard molar gibbs energy over the universal gas constant and temperature
+        (self.enthalpy(temperature) - temperature * self.standard_entropy(temperature)) / (UNIVERSAL_GAS_CONSTANT * temperature)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Composition {
+    // mole fractions in the order of Species::ALL
+    mole_fractions: [f64; 5],
+}
+
+impl Composition {
+    pub fn mole_fraction(&self, species: Species) -> f64 {
+        self.mole_fractions[species as usize]
+    }
+
+    pub fn molar_mass(&self) -> f64 {
+        Species::ALL.iter().map(|species| self.mole_fraction(*species) * species.molar_mass()).sum()
+    }
+
+    pub fn mass_fraction(&self, species: Species) -> f64 {
+        self.mole_fraction(species) * species.molar_mass() / self.molar_mass()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquilibriumState {
+    // a gas state with the composition it relaxes to at its pressure and temperature
+    pub pressure: f64,
+    pub temperature: f64,
+    pub density: f64,
+    pub enthalpy: f64, // per unit mass, zero for undissociated air at absolute zero
+    pub entropy: f64,  // per unit mass
+    pub composition: Composition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquilibriumAir {
+    // air in chemical equilibrium between N2, O2, NO, N and O, which unlike a GasModel
+    // depends on the pressure as well as the temperature, since dissociation adds moles
+    oxygen_fraction: f64,
+}
+
+impl EquilibriumAir {
+    pub fn new(oxygen_fraction: f64) -> Result<Self, InletError> {
+        // oxygen mole fraction of the undissociated mixture of nitrogen and oxygen
+        if oxygen_fraction.is_nan() || oxygen_fraction <= 0.0 || oxygen_fraction >= 1.0 {
+            return Err(InletError::InvalidParameter { name: "oxygen fraction", value: oxygen_fraction });
+        }
+        Ok(EquilibriumAir { oxygen_fraction })
+    }
+
+    pub fn air() -> Self {
+        EquilibriumAir { oxygen_fraction: AIR_OXYGEN_FRACTION }
+    }
+
+    pub fn gas_constant(&self) -> f64 {
+        // specific gas constant of the undissociated mixture
+        let molar_mass: f64 = self.oxygen_fraction * Species::Oxygen.molar_mass()
+            + (1.0 - self.oxygen_fraction) * Species::Nitrogen.molar_mass();
+        UNIVERSAL_GAS_CONSTANT / molar_mass
+    }
+
+    fn composition(&self, pressure: f64, temperature: f64) -> Result<Composition, InletError> {
+        // with a and b the square roots of the N2 and O2 partial pressures over the standard
+        // pressure, the dissociation and nitric oxide equilibria make every other partial
+        // pressure a multiple of a, b or ab, and the element balance is a quadratic in a, so
+        // only b is searched for, on a log scale as oxygen can be almost fully dissociated
+        let gibbs: [f64; 5] = Species::ALL.map(|species| species.gibbs_energy(temperature));
+        let [nitrogen, oxygen, nitric_oxide, atomic_nitrogen, atomic_oxygen] = gibbs;
+        let nitrogen_coefficient: f64 = (-(2.0 * atomic_nitrogen - nitrogen) / 2.0).exp();
+        let oxygen_coefficient: f64 = (-(2.0 * atomic_oxygen - oxygen) / 2.0).exp();
+        let nitric_oxide_coefficient: f64 = (-(2.0 * nitric_oxide - nitrogen - oxygen) / 2.0).exp();
+        let element_ratio: f64 = (1.0 - self.oxygen_fraction) / self.oxygen_fraction;
+        let total_pressure: f64 = pressure / STANDARD_PRESSURE;
+
+        let partial_pressures = |log_b: f64| {
+            let b: f64 = log_b.exp();
+            let linear: f64 = nitrogen_coefficient + (1.0 - element_ratio) * nitric_oxide_coefficient * b;
+            let constant: f64 = element_ratio * (2.0 * b.powi(2) + oxygen_coefficient * b);
+            let discriminant: f64 = (linear.powi(2) + 8.0 * constant).sqrt();
+            let a: f64 = if linear > 0.0 { 2.0 * constant / (linear + discriminant) } else { (discriminant - linear) / 4.0 };
+            [a.powi(2), b.powi(2), nitric_oxide_coefficient * a * b, nitrogen_coefficient * a, oxygen_coefficient * b]
+        };
+        let f = |log_b: f64| partial_pressures(log_b).iter().sum::<f64>() / total_pressure - 1.0;
+        let upper_bound: f64 = 0.5 * total_pressure.ln();
+        let log_b: f64 = brent(&f, upper_bound - 690.0, upper_bound, Some(1e-14), None)?.root;
+
+        let partial_pressures: [f64; 5] = partial_pressures(log_b);
+        let sum: f64 = partial_pressures.iter().sum();
+        Ok(Composition { mole_fractions: partial_pressures.map(|partial_pressure| partial_pressure / sum) })
+    }
+
+    pub fn state(&self, pressure: f64, temperature: f64) -> Result<EquilibriumState, InletError> {
+        if pressure.is_nan() || pressure <= 0.0 || pressure.is_infinite() {
+            return Err(InletError::InvalidParameter { name: "pressure", value: pressure });
+        }
+        if temperature.is_nan() || !(MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&temperature) {
+            return Err(InletError::InvalidParameter { name: "temperature", value: temperature });
+        }
+        let composition: Composition = self.composition(pressure, temperature)?;
+        let molar_mass: f64 = composition.molar_mass();
+        let mut enthalpy: f64 = 0.0;
+        let mut entropy: f64 = 0.0;
+        for species in Species::ALL {
+            let mole_fraction: f64 = composition.mole_fraction(species);
+            if mole_fraction > 0.0 {
+                enthalpy += mole_fraction * species.enthalpy(temperature);
+                entropy += mole_fraction * (species.standard_entropy(temperature)
+                    - UNIVERSAL_GAS_CONSTANT * (mole_fraction * pressure / STANDARD_PRESSURE).ln());
+            }
+        }
+        Ok(EquilibriumState {
+            pressure,
+            temperature,
+            density: pressure * molar_mass / (UNIVERSAL_GAS_CONSTANT * temperature),
+            enthalpy: enthalpy / molar_mass,
+            entropy: entropy / molar_mass,
+            composition,
+        })
+    }
+
+    fn solve_temperature(
+        &self,
+        pressure: f64,
+        residual: &impl Fn(&EquilibriumState) -> f64,
+        lower_bound: f64,
+        upper_bound: f64,
+    ) -> Result<EquilibriumState, InletError> {
+        // the state at a pressure where a property rising with temperature takes a value
+        let f = |temperature: f64| self.state(pressure, temperature).map_or(f64::NAN, |state| residual(&state));
+        let temperature: f64 = brent(&f, lower_bound.max(MIN_TEMPERATURE), upper_bound.min(MAX_TEMPERATURE), Some(1e-13), None)?.root;
+        self.state(pressure, temperature)
+    }
+
+    pub fn state_from_enthalpy(&self, pressure: f64, enthalpy: f64) -> Result<EquilibriumState, InletError> {
+        if enthalpy.is_nan() || enthalpy <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "enthalpy", value: enthalpy });
+        }
+        self.solve_temperature(pressure, &|state: &EquilibriumState| state.enthalpy / enthalpy - 1.0, MIN_TEMPERATURE, MAX_TEMPERATURE)
+    }
+
+    pub fn state_from_entropy(&self, pressure: f64, entropy: f64) -> Result<EquilibriumState, InletError> {
+        if entropy.is_nan() {
+            return Err(InletError::InvalidParameter { name: "entropy", value: entropy });
+        }
+        let gas_constant: f64 = self.gas_constant();
+        self.solve_temperature(pressure, &|state: &EquilibriumState| (state.entropy - entropy) / gas_constant, MIN_TEMPERATURE, MAX_TEMPERATURE)
+    }
+
+    pub fn speed_of_sound(&self, state: &EquilibriumState) -> Result<f64, InletError> {
+        // with the composition shifting as the gas is compressed, the isentropic derivative
+        // dp/drho follows from dh = dp / rho and the partial derivatives of enthalpy and
+        // density in temperature and pressure, taken by central differences
+        let (pressure, temperature) = (state.pressure, state.temperature);
+        let (temperature_step, pressure_step) = (DIFFERENCE_STEP * temperature, DIFFERENCE_STEP * pressure);
+        let (hotter, colder) = (self.state(pressure, temperature + temperature_step)?, self.state(pressure, temperature - temperature_step)?);
+        let (higher, lower) = (self.state(pressure + pressure_step, temperature)?, self.state(pressure - pressure_step, temperature)?);
+        let enthalpy_temperature: f64 = (hotter.enthalpy - colder.enthalpy) / (2.0 * temperature_step);
+        let density_temperature: f64 = (hotter.density - colder.density) / (2.0 * temperature_step);
+        let enthalpy_pressure: f64 = (higher.enthalpy - lower.enthalpy) / (2.0 * pressure_step);
+        let density_pressure: f64 = (higher.density - lower.density) / (2.0 * pressure_step);
+        let isentropic_derivative: f64 = density_temperature * (1.0 / state.density - enthalpy_pressure) / enthalpy_temperature + density_pressure;
+        if isentropic_derivative.is_nan() || isentropic_derivative <= 0.0 {
+            return Err(InletError::NoSolution("math error"));
+        }
+        Ok(1.0 / isentropic_derivative.sqrt())
+    }
+
+    pub fn state_from_mach(&self, pressure: f64, stagnation_enthalpy: f64, mach_number: f64) -> Result<EquilibriumState, InletError> {
+        // the state at a pressure whose enthalpy falls short of the stagnation enthalpy by the
+        // kinetic energy at a mach number, the static temperature lying between the
+        // stagnation temperature at this pressure and that over one plus the mach number squared
+        if mach_number.is_nan() || mach_number < 0.0 {
+            return Err(InletError::InvalidMachNumber { mach_number });
+        }
+        let stagnation: EquilibriumState = self.state_from_enthalpy(pressure, stagnation_enthalpy)?;
+        if mach_number == 0.0 {
+            return Ok(stagnation);
+        }
+        let f = |temperature: f64| {
+            self.state(pressure, temperature)
+                .and_then(|state| Ok(state.enthalpy + (mach_number * self.speed_of_sound(&state)?).powi(2) / 2.0))
+                .map_or(f64::NAN, |enthalpy| enthalpy / stagnation_enthalpy - 1.0)
+        };
+        let lower_bound: f64 = (stagnation.temperature / (1.0 + mach_number.powi(2))).max(MIN_TEMPERATURE);
+        let temperature: f64 = brent(&f, lower_bound, stagnation.temperature, Some(1e-13), None)?.root;
+        self.state(pressure, temperature)
+    }
+
+    pub fn isentropic_exponent(&self, state: &EquilibriumState) -> Result<f64, InletError> {
+        // the ratio a^2 rho / p standing in for the specific heat ratio in perfect gas estimates
+        Ok(self.speed_of_sound(state)?.powi(2) * state.density / state.pressure)
+    }
+
+    pub fn isentrope(&self, state: &EquilibriumState, stagnation_enthalpy: f64) -> Result<Isentrope, InletError> {
+        // states at the entropy of a given state, from below its pressure up past the given
+        // stagnation enthalpy, each found from the temperature of the last
+        if stagnation_enthalpy.is_nan() || stagnation_enthalpy < state.enthalpy {
+            return Err(InletError::InvalidParameter { name: "stagnation enthalpy", value: stagnation_enthalpy });
+        }
+        let mut states: Vec<EquilibriumState> = vec![self.state_from_entropy(ISENTROPE_LOWER_PRESSURE * state.pressure, state.entropy)?];
+        let gas_constant: f64 = self.gas_constant();
+        while states[states.len() - 1].enthalpy < stagnation_enthalpy {
+            let last: &EquilibriumState = &states[states.len() - 1];
+            let next: EquilibriumState = self.solve_temperature(
+                last.pressure * ISENTROPE_STEP.exp(),
+                &|next: &EquilibriumState| (next.entropy - state.entropy) / gas_constant,
+                last.temperature,
+                last.temperature * (1.0 + ISENTROPE_STEP),
+            )?;
+            states.push(next);
+        }
+        let sound_speeds: Vec<f64> = states.iter().map(|state| self.speed_of_sound(state)).collect::<Result<Vec<f64>, InletError>>()?;
+        Ok(Isentrope { states, sound_speeds, stagnation_enthalpy })
+    }
+
+    pub fn stagnation_state(&self, state: &EquilibriumState, velocity: f64) -> Result<EquilibriumState, InletError> {
+        // the state brought to rest isentropically, searching up in pressure from the static state
+        if velocity.is_nan() {
+            return Err(InletError::InvalidParameter { name: "velocity", value: velocity });
+        }
+        let stagnation_enthalpy: f64 = state.enthalpy + velocity.powi(2) / 2.0;
+        if velocity == 0.0 {
+            return Ok(*state);
+        }
+        let f = |log_pressure: f64| {
+            self.state_from_entropy(log_pressure.exp(), state.entropy)
+                .map_or(f64::NAN, |stagnation| stagnation.enthalpy / stagnation_enthalpy - 1.0)
+        };
+        let mut upper_bound: f64 = state.pressure.ln() + 1.0;
+        while f(upper_bound) < 0.0 {
+            upper_bound += 1.0;
+        }
+        let log_pressure: f64 = brent(&f, state.pressure.ln(), upper_bound, Some(1e-13), None)?.root;
+        self.state_from_entropy(log_pressure.exp(), state.entropy)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Isentrope {
+    // equilibrium states along one isentrope with rising pressure, interpolated in enthalpy,
+    // with the speed of sound at each and the stagnation enthalpy of the flow on it
+    states: Vec<EquilibriumState>,
+    sound_speeds: Vec<f64>,
+    stagnation_enthalpy: f64,
+}
+
+impl Isentrope {
+    pub fn entropy(&self) -> f64 {
+        self.states[0].entropy
+    }
+
+    pub fn stagnation_enthalpy(&self) -> f64 {
+        self.stagnation_enthalpy
+    }
+
+    pub fn min_enthalpy(&self) -> f64 {
+        self.states[0].enthalpy
+    }
+
+    fn interval(&self, enthalpy: f64) -> Result<(usize, f64), InletError> {
+        // index of the tabulated interval holding an enthalpy and the fraction across it
+        let last: &EquilibriumState = &self.states[self.states.len() - 1];
+        if enthalpy.is_nan() || enthalpy < self.states[0].enthalpy || enthalpy > last.enthalpy {
+            return Err(InletError::InvalidParameter { name: "enthalpy", value: enthalpy });
+        }
+        let index: usize = self.states.partition_point(|state| state.enthalpy <= enthalpy).clamp(1, self.states.len() - 1) - 1;
+        let (lower, upper) = (&self.states[index], &self.states[index + 1]);
+        Ok((index, (enthalpy - lower.enthalpy) / (upper.enthalpy - lower.enthalpy)))
+    }
+
+    pub fn state_at_enthalpy(&self, enthalpy: f64) -> Result<EquilibriumState, InletError> {
+        // cubic hermite in the logarithm of pressure, whose slope in enthalpy is rho / p, and
+        // linear in the rest
+        let (index, fraction) = self.interval(enthalpy)?;
+        let (lower, upper) = (&self.states[index], &self.states[index + 1]);
+        let step: f64 = upper.enthalpy - lower.enthalpy;
+        let (s, t) = (fraction, 1.0 - fraction);
+        let log_pressure: f64 = lower.pressure.ln() * t.powi(2) * (1.0 + 2.0 * s) + upper.pressure.ln() * s.powi(2) * (1.0 + 2.0 * t)
+            + step * s * t * (lower.density / lower.pressure * t - upper.density / upper.pressure * s);
+        let linear = |lower: f64, upper: f64| lower + fraction * (upper - lower);
+        let mut mole_fractions: [f64; 5] = [0.0; 5];
+        for (index, species) in Species::ALL.iter().enumerate() {
+            mole_fractions[index] = linear(lower.composition.mole_fraction(*species), upper.composition.mole_fraction(*species));
+        }
+        Ok(EquilibriumState {
+            pressure: log_pressure.exp(),
+            temperature: linear(lower.temperature.ln(), upper.temperature.ln()).exp(),
+            density: linear(lower.density.ln(), upper.density.ln()).exp(),
+            enthalpy,
+            entropy: lower.entropy,
+            composition: Composition { mole_fractions },
+        })
+    }
+
+    pub fn sound_speed_at_enthalpy(&self, enthalpy: f64) -> Result<f64, InletError> {
+        let (index, fraction) = self.interval(enthalpy)?;
+        Ok(self.sound_speeds[index] + fraction * (self.sound_speeds[index + 1] - self.sound_speeds[index]))
+    }
+
+    pub fn enthalpy_at_mach(&self, mach_number: f64) -> Result<f64, InletError> {
+        // static enthalpy whose deficit from stagnation is the kinetic energy
+        if mach_number.is_nan() || mach_number < 0.0 {
+            return Err(InletError::InvalidMachNumber { mach_number });
+        }
+        let f = |enthalpy: f64| {
+            self.sound_speed_at_enthalpy(enthalpy).map_or(f64::NAN, |sound_speed| {
+                (self.stagnation_enthalpy - enthalpy - (mach_number * sound_speed).powi(2) / 2.0) / self.stagnation_enthalpy
+            })
+        };
+        Ok(brent(&f, self.min_enthalpy(), self.stagnation_enthalpy, Some(1e-15), None)?.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_species_properties() {
+        // standard entropies at 298.15 K agree with the janaf tables to within 0.2 J/(mol K)
+        let expected: [(Species, f64); 5] = [
+            (Species::Nitrogen, 191.61),
+            (Species::Oxygen, 205.15),
+            (Species::NitricOxide, 210.76),
+            (Species::AtomicNitrogen, 153.30),
+            (Species::AtomicOxygen, 161.06),
+        ];
+        for (species, entropy) in expected {
+            assert!((species.standard_entropy(298.15) - entropy).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_equilibrium_air() {
+        // cold air is undissociated, slightly lighter than standard air without its argon,
+        // with sound at 341 m/s
+        let air = EquilibriumAir::air();
+        let cold = air.state(101325.0, 288.15).expect("valid state");
+        assert!((cold.composition.mole_fraction(Species::Oxygen) - 0.21).abs() < 1e-9);
+        assert!((cold.density - 1.2201).abs() < 1e-4);
+        assert!((air.speed_of_sound(&cold).expect("valid state") - 340.85).abs() < 0.01);
+
+        // oxygen is about half dissociated at 3500 K and one atmosphere, less so at higher
+        // pressure, while nitrogen holds together until much hotter
+        let hot = air.state(101325.0, 3500.0).expect("valid state");
+        let dissociated = hot.composition.mole_fraction(Species::AtomicOxygen) /
+            (hot.composition.mole_fraction(Species::AtomicOxygen) + 2.0 * hot.composition.mole_fraction(Species::Oxygen));
+        assert!(dissociated > 0.3 && dissociated < 0.7);
+        assert!(hot.composition.mole_fraction(Species::NitricOxide) > 0.01);
+        assert!(hot.composition.mole_fraction(Species::AtomicNitrogen) < 1e-3);
+        let compressed = air.state(100.0 * 101325.0, 3500.0).expect("valid state");
+        assert!(compressed.composition.mole_fraction(Species::AtomicOxygen) < hot.composition.mole_fraction(Species::AtomicOxygen));
+        let sum: f64 = Species::ALL.iter().map(|species| hot.composition.mass_fraction(*species)).sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+
+        // the inverse lookups recover the state
+        let recovered = air.state_from_enthalpy(101325.0, hot.enthalpy).expect("valid state");
+        assert!((recovered.temperature - 3500.0).abs() < 1e-8);
+        let recovered = air.state_from_entropy(101325.0, hot.entropy).expect("valid state");
+        assert!((recovered.temperature - 3500.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_isentrope() {
+        // tabulated states agree with direct solutions and the stagnation state closes the energy
+        let air = EquilibriumAir::air();
+        let freestream = air.state(1000.0, 250.0).expect("valid state");
+        let velocity: f64 = 4000.0;
+        let stagnation_enthalpy: f64 = freestream.enthalpy + velocity.powi(2) / 2.0;
+        let isentrope = air.isentrope(&freestream, stagnation_enthalpy).expect("valid isentrope");
+        let stagnation = air.stagnation_state(&freestream, velocity).expect("valid state");
+        assert!((stagnation.enthalpy / stagnation_enthalpy - 1.0).abs() < 1e-10);
+        assert!(stagnation.temperature > 3000.0);
+
+        let enthalpy: f64 = 0.7 * stagnation_enthalpy;
+        let interpolated = isentrope.state_at_enthalpy(enthalpy).expect("valid enthalpy");
+        let direct = air.state_from_entropy(interpolated.pressure, freestream.entropy).expect("valid state");
+        assert!((direct.enthalpy / enthalpy - 1.0).abs() < 1e-6);
+        assert!((interpolated.temperature / direct.temperature - 1.0).abs() < 1e-5);
+        let sound_speed = isentrope.sound_speed_at_enthalpy(enthalpy).expect("valid enthalpy");
+        assert!((sound_speed / air.speed_of_sound(&direct).expect("valid state") - 1.0).abs() < 1e-5);
+
+        // the freestream mach number is found again
+        let mach_number: f64 = velocity / air.speed_of_sound(&freestream).expect("valid state");
+        let enthalpy = isentrope.enthalpy_at_mach(mach_number).expect("valid mach");
+        assert!((enthalpy / freestream.enthalpy - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        let air = EquilibriumAir::air();
+        assert!(EquilibriumAir::new(0.0).is_err());
+        assert!(EquilibriumAir::new(1.0).is_err());
+        assert!(air.state(0.0, 300.0).is_err());
+        assert!(air.state(101325.0, 10.0).is_err());
+        assert!(air.state(101325.0, 1e5).is_err());
+        assert!(air.state_from_enthalpy(101325.0, -1.0).is_err());
+        let freestream = air.state(1000.0, 250.0).expect("valid state");
+        assert!(air.isentrope(&freestream, 0.5 * freestream.enthalpy).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+#![allow(dead_code)]
+
+use super::isentropic::valid_specific_heat_ratio;
+use super::numerics::{expand_bracket, safeguarded_newton};
+use crate::error::InletError;
+
+// sea level temperature of the standard atmosphere in kelvin, where the search for the
+// temperature at a given enthalpy starts
+pub const STANDARD_TEMPERATURE: f64 = 288.15;
+
+// specific gas constant of dry air in J/(kg K)
+pub const AIR_GAS_CONSTANT: f64 = 287.05;
+
+// characteristic vibrational temperature of air in kelvin, from naca report 1135
+pub const AIR_VIBRATIONAL_TEMPERATURE: f64 = 3055.556;
+
+pub trait GasModel {
+    // thermodynamic properties of a gas as functions of its static temperature in kelvin,
+    // with the nondimensional relations taking the specific heat ratio at a given state
+    fn specific_heat_ratio(&self, temperature: f64) -> f64;
+
+    fn gas_constant(&self) -> f64;
+
+    fn specific_heat_cp(&self, temperature: f64) -> f64 {
+        let specific_heat_ratio: f64 = self.specific_heat_ratio(temperature);
+        specific_heat_ratio * self.gas_constant() / (specific_heat_ratio - 1.0)
+    }
+
+    fn speed_of_sound(&self, temperature: f64) -> f64 {
+        (self.specific_heat_ratio(temperature) * self.gas_constant() * temperature).sqrt()
+    }
+
+    // the defaults integrate a constant cp from absolute zero, so models whose specific
+    // heats vary with temperature must provide both of these
+    fn enthalpy(&self, temperature: f64) -> f64 {
+        self.specific_heat_cp(temperature) * temperature
+    }
+
+    fn entropy_function(&self, temperature: f64) -> f64 {
+        // integral of cp / T, the temperature dependent part of the entropy
+        self.specific_heat_cp(temperature) * temperature.ln()
+    }
+
+    fn temperature_from_enthalpy(&self, enthalpy: f64) -> Result<f64, InletError> {
+        // the enthalpy rises monotonically with temperature, so the bracketed root is unique
+        if enthalpy.is_nan() || enthalpy <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "enthalpy", value: enthalpy });
+        }
+        let f = |temperature: f64| self.enthalpy(temperature) / enthalpy - 1.0;
+        let df = |temperature: f64| self.specific_heat_cp(temperature) / enthalpy;
+        let estimate: f64 = enthalpy / self.specific_heat_cp(STANDARD_TEMPERATURE);
+        let (lower_bound, upper_bound) = expand_bracket(&f, 0.5 * estimate, 2.0 * estimate, None)?;
+        Ok(safeguarded_newton(&f, &df, estimate, lower_bound.max(0.0), upper_bound, Some(1e-14), None)?.root)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaloricallyPerfectGas {
+    // constant specific heats, the model behind every closed form relation in this crate
+    specific_heat_ratio: f64,
+    gas_constant: f64,
+}
+
+impl CaloricallyPerfectGas {
+    pub fn new(specific_heat_ratio: f64, gas_constant: f64) -> Result<Self, InletError> {
+        if !valid_specific_heat_ratio(specific_heat_ratio) {
+            return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+        }
+        if gas_constant.is_nan() || gas_constant <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "gas constant", value: gas_constant });
+        }
+        Ok(CaloricallyPerfectGas { specific_heat_ratio, gas_constant })
+    }
+
+    pub fn air() -> Self {
+        CaloricallyPerfectGas { specific_heat_ratio: 1.4, gas_constant: AIR_GAS_CONSTANT }
+    }
+
+    pub fn constant_specific_heat_ratio(&self) -> f64 {
+        // the same at every temperature, for the closed form relations that take it directly
+        self.specific_heat_ratio
+    }
+}
+
+impl GasModel for CaloricallyPerfectGas {
+    fn specific_heat_ratio(&self, _temperature: f64) -> f64 {
+        self.specific_heat_ratio
+    }
+
+    fn gas_constant(&self) -> f64 {
+        self.gas_constant
+    }
+
+    fn temperature_from_enthalpy(&self, enthalpy: f64) -> Result<f64, InletError> {
+        if enthalpy.is_nan() || enthalpy <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "enthalpy", value: enthalpy });
+        }
+        Ok(enthalpy / self.specific_heat_cp(STANDARD_TEMPERATURE))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermallyPerfectGas {
+    // a gas whose translational and rotational modes are fully excited, giving the frozen
+    // specific heat ratio, plus a harmonic oscillator vibrational mode that raises cp at
+    // high temperature, valid below the onset of dissociation at around 2500 K in air
+    frozen_specific_heat_ratio: f64,
+    gas_constant: f64,
+    vibrational_temperature: f64,
+}
+
+impl ThermallyPerfectGas {
+    pub fn new(frozen_specific_heat_ratio: f64, gas_constant: f64, vibrational_temperature: f64) -> Result<Self, InletError> {
+        if !valid_specific_heat_ratio(frozen_specific_heat_ratio) {
+            return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio: frozen_specific_heat_ratio });
+        }
+        if gas_constant.is_nan() || gas_constant <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "gas constant", value: gas_constant });
+        }
+        if vibrational_temperature.is_nan() || vibrational_temperature <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "vibrational temperature", value: vibrational_temperature });
+        }
+        Ok(ThermallyPerfectGas { frozen_specific_heat_ratio, gas_constant, vibrational_temperature })
+    }
+
+    pub fn air() -> Self {
+        ThermallyPerfectGas {
+            frozen_specific_heat_ratio: 1.4,
+            gas_constant: AIR_GAS_CONSTANT,
+            vibrational_temperature: AIR_VIBRATIONAL_TEMPERATURE,
+        }
+    }
+
+    fn frozen_specific_heat_cp(&self) -> f64 {
+        self.frozen_specific_heat_ratio * self.gas_constant / (self.frozen_specific_heat_ratio - 1.0)
+    }
+}
+
+impl GasModel for ThermallyPerfectGas {
+    fn specific_heat_ratio(&self, temperature: f64) -> f64 {
+        let specific_heat_cp: f64 = self.specific_heat_cp(temperature);
+        specific_heat_cp / (specific_heat_cp - self.gas_constant)
+    }
+
+    fn gas_constant(&self) -> f64 {
+        self.gas_constant
+    }
+
+    fn specific_heat_cp(&self, temperature: f64) -> f64 {
+        // the exponential is written in its decaying form so low temperatures cannot overflow
+        let x: f64 = self.vibrational_temperature / temperature;
+        let decay: f64 = (-x).exp();
+        self.frozen_specific_heat_cp() + self.gas_constant * x.powi(2) * decay / (1.0 - decay).powi(2)
+    }
+
+    fn enthalpy(&self, temperature: f64) -> f64 {
+        let decay: f64 = (-self.vibrational_temperature / temperature).exp();
+        self.frozen_specific_heat_cp() * temperature +
+            self.gas_constant * self.vibrational_temperature * decay / (1.0 - decay)
+    }
+
+    fn entropy_function(&self, temperature: f64) -> f64 {
+        let x: f64 = self.vibrational_temperature / temperature;
+        let decay: f64 = (-x).exp();
+        self.frozen_specific_heat_cp() * temperature.ln() +
+            self.gas_constant * (x * decay / (1.0 - decay) - (1.0 - decay).ln())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calorically_perfect_air() {
+        // air at sea level has cp of 1005 J/(kg K) and a speed of sound of 340.3 m/s
+        let air = CaloricallyPerfectGas::air();
+        assert_eq!(air.specific_heat_ratio(STANDARD_TEMPERATURE), air.specific_heat_ratio(2000.0));
+        assert!((air.specific_heat_cp(STANDARD_TEMPERATURE) - 1004.675).abs() < 1e-9);
+        assert!((air.speed_of_sound(STANDARD_TEMPERATURE) - 340.29).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_thermally_perfect_air() {
+        // vibration is frozen at room temperature and lowers gamma to about 1.30 at 2000 K
+        let air = ThermallyPerfectGas::air();
+        let perfect = CaloricallyPerfectGas::air();
+        assert!((air.specific_heat_ratio(STANDARD_TEMPERATURE) - 1.4).abs() < 1e-3);
+        assert!((air.specific_heat_ratio(2000.0) - 1.3006).abs() < 1e-4);
+        assert!((air.specific_heat_ratio(50.0) - 1.4).abs() < 1e-12);
+
+        // the enthalpy and entropy function are consistent with cp, and the enthalpy inverts
+        for temperature in [100.0, 300.0, 1500.0, 3000.0] {
+            let step: f64 = 1e-3 * temperature;
+            let enthalpy_slope = (air.enthalpy(temperature + step) - air.enthalpy(temperature - step)) / (2.0 * step);
+            let entropy_slope = (air.entropy_function(temperature + step) - air.entropy_function(temperature - step)) / (2.0 * step);
+            assert!((enthalpy_slope / air.specific_heat_cp(temperature) - 1.0).abs() < 1e-6);
+            assert!((entropy_slope * temperature / air.specific_heat_cp(temperature) - 1.0).abs() < 1e-6);
+            let recovered = air.temperature_from_enthalpy(air.enthalpy(temperature)).expect("valid enthalpy");
+            assert!((recovered - temperature).abs() < 1e-9 * temperature);
+            let recovered = perfect.temperature_from_enthalpy(perfect.enthalpy(temperature)).expect("valid enthalpy");
+            assert!((recovered - temperature).abs() < 1e-9 * temperature);
+        }
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(CaloricallyPerfectGas::new(1.0, AIR_GAS_CONSTANT).is_err());
+        assert!(CaloricallyPerfectGas::new(1.4, 0.0).is_err());
+        assert!(CaloricallyPerfectGas::new(1.4, f64::NAN).is_err());
+        assert!(ThermallyPerfectGas::new(1.4, AIR_GAS_CONSTANT, 0.0).is_err());
+        assert!(ThermallyPerfectGas::air().temperature_from_enthalpy(-1.0).is_err());
+    }
+}
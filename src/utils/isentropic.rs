@@ -1,50 +1,52 @@
 #![allow(dead_code)]
 
 use std::f64::consts::PI;
+use super::gas::GasModel;
 use super::numerics::*;
+use crate::error::InletError;
 
-pub fn calc_mach_angle_from_mach(mach_number: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_angle_from_mach(mach_number: f64) -> Result<f64, InletError> {
     if mach_number < 0.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number });
     }
     let mach_angle = (1.0 / mach_number).asin();
     Ok(mach_angle)
 }
 
-pub fn calc_pressure_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_pressure_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     let pressure_ratio: f64 = (1.0 + (specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2)).powf(-specific_heat_ratio / (specific_heat_ratio - 1.0));
     Ok(pressure_ratio)
 }
 
-pub fn calc_temperature_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_temperature_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     let temperature_ratio: f64 = (1.0 + (specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2)).powi(-1);
     Ok(temperature_ratio)
 }
 
-pub fn calc_density_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_density_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     let density_ratio: f64 = (1.0 + (specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2)).powf(-1.0 / (specific_heat_ratio - 1.0));
     Ok(density_ratio)
 }
 
-pub fn calc_mach_from_speed_of_sound(velocity: f64, speed_of_sound: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_from_speed_of_sound(velocity: f64, speed_of_sound: f64) -> Result<f64, InletError> {
     Ok(velocity / speed_of_sound)
 }
 
-pub fn prandtl_meyer_function(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn prandtl_meyer_function(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     if mach_number <= 1.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number });
     }
     let gamma_ratio = (specific_heat_ratio - 1.0) / (specific_heat_ratio + 1.0);
     let sqrt_gamma_ratio = gamma_ratio.sqrt();
@@ -55,55 +57,55 @@ pub fn prandtl_meyer_function(mach_number: f64, specific_heat_ratio: f64) -> Res
     Ok(prandtl_meyer_angle)
 }
 
-pub fn calc_mach_from_mach_angle(mach_angle: f64) -> Result<f64, &'static str> {
-    if mach_angle < 0.0 || mach_angle > PI / 2.0 {
+pub fn calc_mach_from_mach_angle(mach_angle: f64) -> Result<f64, InletError> {
+    if !(0.0..=PI / 2.0).contains(&mach_angle) {
         // check valid mach angle in radians
-        return Err("invalid mach angle")
+        return Err(InletError::InvalidParameter { name: "mach angle", value: mach_angle })
     }
     let mach_number: f64 = 1.0 / mach_angle.sin();
     Ok(mach_number)
 }
 
-pub fn calc_mach_from_temperature_ratio(temperature_ratio: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_from_temperature_ratio(temperature_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     if temperature_ratio <= 0.0 || temperature_ratio > 1.0 {
         // check valid temperature ratio
-        return Err("invalid temperature ratio");
+        return Err(InletError::InvalidParameter { name: "temperature ratio", value: temperature_ratio });
     }
     let mach_number: f64 = (2.0 * ((1.0 / temperature_ratio) - 1.0) / (specific_heat_ratio - 1.0)).sqrt();
     Ok(mach_number)
 }
 
-pub fn calc_mach_from_pressure_ratio(pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_from_pressure_ratio(pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     if pressure_ratio <= 0.0 || pressure_ratio > 1.0 {
         // check valid pressure ratio
-        return Err("invalid pressure ratio");
+        return Err(InletError::InvalidParameter { name: "pressure ratio", value: pressure_ratio });
     }
     let mach_number: f64 = (2.0 * ((1.0 / pressure_ratio.powf((specific_heat_ratio - 1.0) / specific_heat_ratio)) - 1.0) / (specific_heat_ratio - 1.0)).sqrt();
     Ok(mach_number)
 }
 
-pub fn calc_mach_from_density_ratio(density_ratio: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_from_density_ratio(density_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     if density_ratio <= 0.0 || density_ratio > 1.0 {
         // check valid density ratio
-        return Err("invalid density ratio");
+        return Err(InletError::InvalidParameter { name: "density ratio", value: density_ratio });
     }
     let mach_number: f64 = ((2.0 * ((1.0 / density_ratio.powf(specific_heat_ratio - 1.0)) - 1.0)) / (specific_heat_ratio - 1.0)).sqrt();
     Ok(mach_number)
 }
 
-pub fn calc_mach_from_prandtl_meyer_angle(prandtl_meyer_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_mach_from_prandtl_meyer_angle(prandtl_meyer_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     // eta is the value of (m^2 - 1).sqrt()
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     let alpha = ((specific_heat_ratio + 1.0) / (specific_heat_ratio - 1.0)).sqrt(); // just a constant to make things easier
     let f = |eta: f64| {
@@ -114,11 +116,199 @@ pub fn calc_mach_from_prandtl_meyer_angle(prandtl_meyer_angle: f64, specific_hea
         1.0 / ((eta / alpha).powi(2) + 1.0)
         - 1.0 / (eta.powi(2) + 1.0)
     };
-    let eta: f64 = newton_raphson(&f, &df, 1.5, None, None);
+
+    // the angle is bounded by the expansion to infinite mach number, below which the
+    // function rises monotonically with eta so the bracketed root is the only one
+    let max_prandtl_meyer_angle: f64 = (alpha - 1.0) * PI / 2.0;
+    if !(0.0..max_prandtl_meyer_angle).contains(&prandtl_meyer_angle) {
+        return Err(InletError::InvalidParameter { name: "prandtl meyer angle", value: prandtl_meyer_angle });
+    }
+    let (lower_bound, upper_bound) = expand_bracket(&f, 0.0, 1.5, None)?;
+    let eta: f64 = safeguarded_newton(&f, &df, 1.5, lower_bound, upper_bound, None, None)?.root;
     let mach_number: f64 = (eta.powi(2) + 1.0).sqrt();
     Ok(mach_number)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowRegime {
+    // the two mach numbers sharing an area ratio or mass flow parameter either side of the throat
+    Subsonic,
+    Supersonic,
+}
+
+pub fn calc_area_ratio_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // area to sonic throat area for isentropic flow
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if mach_number.is_nan() || mach_number <= 0.0 {
+        return Err(InletError::InvalidMachNumber { mach_number });
+    }
+    let area_ratio: f64 = (
+        2.0 / (specific_heat_ratio + 1.0) * (1.0 + (specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2))
+    ).powf((specific_heat_ratio + 1.0) / (2.0 * (specific_heat_ratio - 1.0))) / mach_number;
+    Ok(area_ratio)
+}
+
+pub fn calc_mach_from_area_ratio(area_ratio: f64, regime: FlowRegime, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if area_ratio.is_nan() || area_ratio < 1.0 {
+        return Err(InletError::InvalidParameter { name: "area ratio", value: area_ratio });
+    }
+    if area_ratio == 1.0 {
+        return Ok(1.0);
+    }
+    // the logarithm keeps the residual well scaled for the large area ratios of hypersonic flow
+    let f = |mach_number: f64| {
+        calc_area_ratio_from_mach(mach_number, specific_heat_ratio).map_or(f64::NAN, |ratio| (ratio / area_ratio).ln())
+    };
+    let (lower_bound, upper_bound) = match regime {
+        FlowRegime::Subsonic => {
+            // the area ratio is at least the leading 1 / m term, so half its root lies above the target
+            let lower_bound: f64 = 0.5 * (2.0 / (specific_heat_ratio + 1.0))
+                .powf((specific_heat_ratio + 1.0) / (2.0 * (specific_heat_ratio - 1.0))) / area_ratio;
+            (lower_bound, 1.0)
+        }
+        FlowRegime::Supersonic => {
+            let mut upper_bound: f64 = 2.0;
+            while f(upper_bound) < 0.0 {
+                upper_bound *= 2.0;
+                if upper_bound > 1e6 {
+                    return Err(InletError::NoSolution("no supersonic mach number gives the area ratio"));
+                }
+            }
+            (1.0, upper_bound)
+        }
+    };
+    Ok(brent(&f, lower_bound, upper_bound, Some(1e-14), None)?.root)
+}
+
+pub fn calc_mass_flow_parameter_from_mach(mach_number: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // mass flow rate * sqrt(gas constant * stagnation temperature) / (area * stagnation pressure)
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if mach_number.is_nan() || mach_number < 0.0 {
+        return Err(InletError::InvalidMachNumber { mach_number });
+    }
+    let mass_flow_parameter: f64 = specific_heat_ratio.sqrt() * mach_number *
+        (1.0 + (specific_heat_ratio - 1.0) / 2.0 * mach_number.powi(2))
+            .powf(-(specific_heat_ratio + 1.0) / (2.0 * (specific_heat_ratio - 1.0)));
+    Ok(mass_flow_parameter)
+}
+
+pub fn calc_choked_mass_flow_parameter(specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // largest mass flow parameter, reached at a sonic throat
+    calc_mass_flow_parameter_from_mach(1.0, specific_heat_ratio)
+}
+
+pub fn calc_mach_from_mass_flow_parameter(mass_flow_parameter: f64, regime: FlowRegime, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the mass flow parameter is inversely proportional to the area ratio
+    let choked_mass_flow_parameter: f64 = calc_choked_mass_flow_parameter(specific_heat_ratio)?;
+    if mass_flow_parameter.is_nan() || mass_flow_parameter <= 0.0 || mass_flow_parameter > choked_mass_flow_parameter {
+        return Err(InletError::InvalidParameter { name: "mass flow parameter", value: mass_flow_parameter });
+    }
+    calc_mach_from_area_ratio(choked_mass_flow_parameter / mass_flow_parameter, regime, specific_heat_ratio)
+}
+
+pub fn calc_mass_flow_rate(
+    mach_number: f64,
+    area: f64,
+    stagnation_pressure: f64,
+    stagnation_temperature: f64,
+    gas_constant: f64,
+    specific_heat_ratio: f64,
+) -> Result<f64, InletError> {
+    // mass flow through an area in consistent units, e.g. kg/s from m^2, Pa, K and J/(kg K)
+    if stagnation_temperature.is_nan() || stagnation_temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "stagnation temperature", value: stagnation_temperature });
+    }
+    if gas_constant.is_nan() || gas_constant <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "gas constant", value: gas_constant });
+    }
+    let mass_flow_parameter: f64 = calc_mass_flow_parameter_from_mach(mach_number, specific_heat_ratio)?;
+    Ok(mass_flow_parameter * area * stagnation_pressure / (gas_constant * stagnation_temperature).sqrt())
+}
+
+fn valid_temperature(temperature: f64) -> Result<(), InletError> {
+    if temperature.is_nan() || temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "temperature", value: temperature });
+    }
+    Ok(())
+}
+
+pub fn calc_speed_of_sound(temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    valid_temperature(temperature)?;
+    Ok(gas.speed_of_sound(temperature))
+}
+
+pub fn calc_mach_from_velocity(velocity: f64, temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    calc_mach_from_speed_of_sound(velocity, calc_speed_of_sound(temperature, gas)?)
+}
+
+pub fn calc_stagnation_temperature(mach_number: f64, temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    // the stagnation enthalpy adds the kinetic energy to the static enthalpy
+    valid_temperature(temperature)?;
+    if mach_number.is_nan() || mach_number < 0.0 {
+        return Err(InletError::InvalidMachNumber { mach_number });
+    }
+    let velocity: f64 = mach_number * gas.speed_of_sound(temperature);
+    gas.temperature_from_enthalpy(gas.enthalpy(temperature) + velocity.powi(2) / 2.0)
+}
+
+pub fn calc_stagnation_pressure(mach_number: f64, pressure: f64, temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    let stagnation_temperature: f64 = calc_stagnation_temperature(mach_number, temperature, gas)?;
+    Ok(pressure * isentropic_pressure_ratio(stagnation_temperature, temperature, gas))
+}
+
+fn isentropic_pressure_ratio(temperature: f64, reference_temperature: f64, gas: &impl GasModel) -> f64 {
+    // pressure ratio between two temperatures at constant entropy
+    ((gas.entropy_function(temperature) - gas.entropy_function(reference_temperature)) / gas.gas_constant()).exp()
+}
+
+// the relations below hold for any gas model given the stagnation temperature, and reduce
+// to the closed forms above for a calorically perfect gas
+
+pub fn calc_temperature_ratio_from_mach_for_gas(mach_number: f64, stagnation_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    // static temperature whose enthalpy deficit from stagnation is the kinetic energy
+    valid_temperature(stagnation_temperature)?;
+    if mach_number.is_nan() || mach_number < 0.0 {
+        return Err(InletError::InvalidMachNumber { mach_number });
+    }
+    let stagnation_enthalpy: f64 = gas.enthalpy(stagnation_temperature);
+    let f = |temperature: f64| {
+        (stagnation_enthalpy - gas.enthalpy(temperature) - (mach_number * gas.speed_of_sound(temperature)).powi(2) / 2.0) / stagnation_enthalpy
+    };
+    if f(stagnation_temperature) == 0.0 {
+        return Ok(1.0);
+    }
+    let temperature: f64 = brent(&f, 1e-4 * stagnation_temperature, stagnation_temperature, Some(1e-15), None)?.root;
+    Ok(temperature / stagnation_temperature)
+}
+
+pub fn calc_pressure_ratio_from_mach_for_gas(mach_number: f64, stagnation_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    let temperature_ratio: f64 = calc_temperature_ratio_from_mach_for_gas(mach_number, stagnation_temperature, gas)?;
+    Ok(isentropic_pressure_ratio(temperature_ratio * stagnation_temperature, stagnation_temperature, gas))
+}
+
+pub fn calc_density_ratio_from_mach_for_gas(mach_number: f64, stagnation_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    let temperature_ratio: f64 = calc_temperature_ratio_from_mach_for_gas(mach_number, stagnation_temperature, gas)?;
+    let pressure_ratio: f64 = isentropic_pressure_ratio(temperature_ratio * stagnation_temperature, stagnation_temperature, gas);
+    Ok(pressure_ratio / temperature_ratio)
+}
+
+pub fn calc_mach_from_temperature_ratio_for_gas(temperature_ratio: f64, stagnation_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    valid_temperature(stagnation_temperature)?;
+    if temperature_ratio.is_nan() || temperature_ratio <= 0.0 || temperature_ratio > 1.0 {
+        return Err(InletError::InvalidParameter { name: "temperature ratio", value: temperature_ratio });
+    }
+    let temperature: f64 = temperature_ratio * stagnation_temperature;
+    let velocity: f64 = (2.0 * (gas.enthalpy(stagnation_temperature) - gas.enthalpy(temperature))).max(0.0).sqrt();
+    Ok(velocity / gas.speed_of_sound(temperature))
+}
+
 pub fn valid_specific_heat_ratio(specific_heat_ratio: f64) -> bool {
     // specific heat ratio must be greater than 1
     specific_heat_ratio > 1.0
@@ -127,6 +317,7 @@ pub fn valid_specific_heat_ratio(specific_heat_ratio: f64) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::gas::{CaloricallyPerfectGas, ThermallyPerfectGas};
 
     #[test]
     fn test_valid_specific_heat_ratio() {
@@ -269,6 +460,85 @@ mod tests {
             .expect("valid mach from prandtl meyer angle calculation");
         let expected = 2.0;
         assert!((result - expected).abs() < 1e-5);
+
+        // angles close to the limit of infinite expansion still find the right root
+        for mach_number in [1.01, 10.0, 40.0] {
+            let prandtl_angle = prandtl_meyer_function(mach_number, specific_heat_ratio)
+                .expect("valid prandtl meyer function");
+            let result = calc_mach_from_prandtl_meyer_angle(prandtl_angle, specific_heat_ratio)
+                .expect("valid mach from prandtl meyer angle calculation");
+            assert!((result - mach_number).abs() / mach_number < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_area_ratio() {
+        // mach 2 and mach 0.3722 share an area ratio of 1.6875 in air
+        let area_ratio = calc_area_ratio_from_mach(2.0, 1.4).expect("valid mach number");
+        assert!((area_ratio - 1.6875).abs() < 1e-12);
+        let subsonic = calc_mach_from_area_ratio(area_ratio, FlowRegime::Subsonic, 1.4).expect("valid area ratio");
+        let supersonic = calc_mach_from_area_ratio(area_ratio, FlowRegime::Supersonic, 1.4).expect("valid area ratio");
+        assert!((subsonic - 0.37224).abs() < 1e-5);
+        assert!((supersonic - 2.0).abs() < 1e-10);
+        assert_eq!(calc_mach_from_area_ratio(1.0, FlowRegime::Supersonic, 1.4).expect("valid area ratio"), 1.0);
+
+        // round trips across both branches, including hypersonic contraction ratios
+        for mach_number in [0.01, 0.5, 0.99, 1.01, 6.0, 25.0] {
+            let regime = if mach_number < 1.0 { FlowRegime::Subsonic } else { FlowRegime::Supersonic };
+            let area_ratio = calc_area_ratio_from_mach(mach_number, 1.3).expect("valid mach number");
+            let result = calc_mach_from_area_ratio(area_ratio, regime, 1.3).expect("valid area ratio");
+            assert!((result - mach_number).abs() / mach_number < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_mass_flow_parameter() {
+        // choked flow of air passes 0.6847 in the nondimensional mass flow parameter
+        let choked = calc_choked_mass_flow_parameter(1.4).expect("valid specific heat ratio");
+        assert!((choked - 0.68473).abs() < 1e-5);
+        let mass_flow_parameter = calc_mass_flow_parameter_from_mach(2.0, 1.4).expect("valid mach number");
+        assert!((choked / mass_flow_parameter - 1.6875).abs() < 1e-12);
+        let result = calc_mach_from_mass_flow_parameter(mass_flow_parameter, FlowRegime::Supersonic, 1.4).expect("valid mass flow parameter");
+        assert!((result - 2.0).abs() < 1e-10);
+
+        // a 1 cm^2 sonic throat at sea level stagnation conditions passes about 24 g/s
+        let mass_flow_rate = calc_mass_flow_rate(1.0, 1e-4, 101325.0, 288.15, 287.05, 1.4).expect("valid throat");
+        assert!((mass_flow_rate - 0.02406).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_stagnation_conditions() {
+        // mach 2 air at 216.65 K and 5529 Pa stagnates at 389.97 K and 43.26 kPa
+        let air = CaloricallyPerfectGas::air();
+        let stagnation_temperature = calc_stagnation_temperature(2.0, 216.65, &air).expect("valid state");
+        assert!((stagnation_temperature - 389.97).abs() < 1e-9);
+        let stagnation_pressure = calc_stagnation_pressure(2.0, 5529.0, 216.65, &air).expect("valid state");
+        assert!((stagnation_pressure / 5529.0 - 7.8244).abs() < 1e-4);
+        let velocity = 2.0 * calc_speed_of_sound(216.65, &air).expect("valid state");
+        assert!((calc_mach_from_velocity(velocity, 216.65, &air).expect("valid state") - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_thermally_perfect_relations() {
+        // a cold flow matches the closed forms, while at mach 8 and 220 K the vibrational
+        // energy leaves the stagnation temperature well below the perfect gas value
+        let air = ThermallyPerfectGas::air();
+        let perfect = CaloricallyPerfectGas::air();
+        let temperature_ratio = calc_temperature_ratio_from_mach_for_gas(0.5, 300.0, &air).expect("valid state");
+        assert!((temperature_ratio - calc_temperature_ratio_from_mach(0.5, 1.4).expect("valid state")).abs() < 1e-4);
+        let pressure_ratio = calc_pressure_ratio_from_mach_for_gas(0.5, 300.0, &perfect).expect("valid state");
+        assert!((pressure_ratio - calc_pressure_ratio_from_mach(0.5, 1.4).expect("valid state")).abs() < 1e-12);
+
+        let stagnation_temperature = calc_stagnation_temperature(8.0, 220.0, &air).expect("valid state");
+        let perfect_stagnation_temperature = calc_stagnation_temperature(8.0, 220.0, &perfect).expect("valid state");
+        assert!(perfect_stagnation_temperature - stagnation_temperature > 300.0);
+        let temperature_ratio = calc_temperature_ratio_from_mach_for_gas(8.0, stagnation_temperature, &air).expect("valid state");
+        assert!((temperature_ratio * stagnation_temperature - 220.0).abs() < 1e-9);
+        let mach_number = calc_mach_from_temperature_ratio_for_gas(temperature_ratio, stagnation_temperature, &air).expect("valid state");
+        assert!((mach_number - 8.0).abs() < 1e-9);
+        let density_ratio = calc_density_ratio_from_mach_for_gas(8.0, stagnation_temperature, &air).expect("valid state");
+        let pressure_ratio = calc_pressure_ratio_from_mach_for_gas(8.0, stagnation_temperature, &air).expect("valid state");
+        assert!((density_ratio - pressure_ratio / temperature_ratio).abs() < 1e-15);
     }
 
     #[test]
@@ -282,5 +552,18 @@ mod tests {
         assert!(prandtl_meyer_function(1.0, 1.4).is_err());
         // invalid mach angle for calc_mach_from_mach_angle (out of range)
         assert!(calc_mach_from_mach_angle(PI).is_err());
+        // prandtl meyer angle beyond the limit of infinite expansion
+        assert!(calc_mach_from_prandtl_meyer_angle(2.3, 1.4).is_err());
+        assert!(calc_mach_from_prandtl_meyer_angle(-0.1, 1.4).is_err());
+        // area ratios below the throat and mass flows beyond choking
+        assert!(calc_area_ratio_from_mach(0.0, 1.4).is_err());
+        assert!(calc_mach_from_area_ratio(0.9, FlowRegime::Subsonic, 1.4).is_err());
+        assert!(calc_mach_from_mass_flow_parameter(0.7, FlowRegime::Supersonic, 1.4).is_err());
+        assert!(calc_mass_flow_rate(1.0, 1e-4, 101325.0, 0.0, 287.05, 1.4).is_err());
+        // non-positive static temperatures for the dimensional relations
+        assert!(calc_speed_of_sound(0.0, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_stagnation_temperature(2.0, -1.0, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_temperature_ratio_from_mach_for_gas(-1.0, 300.0, &ThermallyPerfectGas::air()).is_err());
+        assert!(calc_mach_from_temperature_ratio_for_gas(1.2, 300.0, &ThermallyPerfectGas::air()).is_err());
     }
 }
\ No newline at end of file
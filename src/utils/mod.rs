@@ -1,3 +1,8 @@
+pub mod atmosphere;
+pub mod equilibrium;
+pub mod gas;
 pub mod isentropic;
+pub mod normalshock;
 pub mod numerics;
-pub mod obliqueshock;
\ No newline at end of file
+pub mod obliqueshock;
+pub mod species;
\ No newline at end of file
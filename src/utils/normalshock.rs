@@ -0,0 +1,402 @@
+#![allow(dead_code)]
+
+use super::equilibrium::{EquilibriumAir, EquilibriumState};
+use super::gas::GasModel;
+use super::isentropic::valid_specific_heat_ratio;
+use super::numerics::brent;
+use crate::error::InletError;
+
+// largest upstream mach number searched when inverting the stagnation and pitot pressure ratios
+const MAX_UPSTREAM_MACH: f64 = 1e4;
+
+// range of inverse density ratio searched for a shock in equilibrium air, shocks closer to
+// sonic than the upper limit being taken as vanishing
+const MIN_INVERSE_DENSITY_RATIO: f64 = 1e-2;
+const MAX_INVERSE_DENSITY_RATIO: f64 = 1.0 - 1e-9;
+
+fn validate(upstream_mach: f64, specific_heat_ratio: f64) -> Result<(), InletError> {
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if upstream_mach.is_nan() || upstream_mach < 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
+    }
+    Ok(())
+}
+
+pub fn calc_downstream_mach(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    validate(upstream_mach, specific_heat_ratio)?;
+    let downstream_mach: f64 = (
+        (1.0 + (specific_heat_ratio - 1.0) / 2.0 * upstream_mach.powi(2))
+        / (specific_heat_ratio * upstream_mach.powi(2) - (specific_heat_ratio - 1.0) / 2.0)
+    ).sqrt();
+    Ok(downstream_mach)
+}
+
+pub fn calc_pressure_ratio(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    validate(upstream_mach, specific_heat_ratio)?;
+    let pressure_ratio: f64 =
+        (2.0 * specific_heat_ratio * upstream_mach.powi(2) - (specific_heat_ratio - 1.0)) /
+        (specific_heat_ratio + 1.0);
+    Ok(pressure_ratio)
+}
+
+pub fn calc_density_ratio(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    validate(upstream_mach, specific_heat_ratio)?;
+    let density_ratio: f64 =
+        (specific_heat_ratio + 1.0) * upstream_mach.powi(2) /
+        ((specific_heat_ratio - 1.0) * upstream_mach.powi(2) + 2.0);
+    Ok(density_ratio)
+}
+
+pub fn calc_temperature_ratio(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    let pressure_ratio: f64 = calc_pressure_ratio(upstream_mach, specific_heat_ratio)?;
+    let density_ratio: f64 = calc_density_ratio(upstream_mach, specific_heat_ratio)?;
+    Ok(pressure_ratio / density_ratio)
+}
+
+pub fn calc_stagnation_pressure_ratio(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // downstream to upstream stagnation pressure, the stagnation temperature being unchanged
+    let stagnation_pressure_ratio: f64 =
+        calc_density_ratio(upstream_mach, specific_heat_ratio)?.powf(specific_heat_ratio / (specific_heat_ratio - 1.0)) *
+        (1.0 / calc_pressure_ratio(upstream_mach, specific_heat_ratio)?).powf(1.0 / (specific_heat_ratio - 1.0));
+    Ok(stagnation_pressure_ratio)
+}
+
+pub fn calc_pitot_pressure_ratio(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // rayleigh pitot formula, the stagnation pressure behind the shock to the upstream static pressure
+    validate(upstream_mach, specific_heat_ratio)?;
+    let pitot_pressure_ratio: f64 =
+        ((specific_heat_ratio + 1.0).powi(2) * upstream_mach.powi(2) /
+            (4.0 * specific_heat_ratio * upstream_mach.powi(2) - 2.0 * (specific_heat_ratio - 1.0)))
+            .powf(specific_heat_ratio / (specific_heat_ratio - 1.0)) *
+        (1.0 - specific_heat_ratio + 2.0 * specific_heat_ratio * upstream_mach.powi(2)) /
+        (specific_heat_ratio + 1.0);
+    Ok(pitot_pressure_ratio)
+}
+
+pub fn calc_upstream_mach_from_downstream_mach(downstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the relation is symmetric in the upstream and downstream mach numbers
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    let min_downstream_mach: f64 = ((specific_heat_ratio - 1.0) / (2.0 * specific_heat_ratio)).sqrt();
+    if downstream_mach.is_nan() || downstream_mach <= min_downstream_mach || downstream_mach > 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: downstream_mach });
+    }
+    let upstream_mach: f64 = (
+        (1.0 + (specific_heat_ratio - 1.0) / 2.0 * downstream_mach.powi(2))
+        / (specific_heat_ratio * downstream_mach.powi(2) - (specific_heat_ratio - 1.0) / 2.0)
+    ).sqrt();
+    Ok(upstream_mach)
+}
+
+pub fn calc_upstream_mach_from_pressure_ratio(pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if pressure_ratio.is_nan() || pressure_ratio < 1.0 {
+        return Err(InletError::InvalidParameter { name: "pressure ratio", value: pressure_ratio });
+    }
+    let upstream_mach: f64 = (
+        ((specific_heat_ratio + 1.0) * pressure_ratio + (specific_heat_ratio - 1.0))
+        / (2.0 * specific_heat_ratio)
+    ).sqrt();
+    Ok(upstream_mach)
+}
+
+pub fn calc_upstream_mach_from_density_ratio(density_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the density ratio tends to (gamma + 1) / (gamma - 1) for an infinitely strong shock
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    let max_density_ratio: f64 = (specific_heat_ratio + 1.0) / (specific_heat_ratio - 1.0);
+    if density_ratio.is_nan() || density_ratio < 1.0 || density_ratio >= max_density_ratio {
+        return Err(InletError::InvalidParameter { name: "density ratio", value: density_ratio });
+    }
+    let upstream_mach: f64 = (
+        2.0 * density_ratio / ((specific_heat_ratio + 1.0) - (specific_heat_ratio - 1.0) * density_ratio)
+    ).sqrt();
+    Ok(upstream_mach)
+}
+
+pub fn calc_upstream_mach_from_temperature_ratio(temperature_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // positive root of the quadratic in the square of the upstream mach number
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if temperature_ratio.is_nan() || temperature_ratio < 1.0 {
+        return Err(InletError::InvalidParameter { name: "temperature ratio", value: temperature_ratio });
+    }
+    let a: f64 = 2.0 * specific_heat_ratio * (specific_heat_ratio - 1.0);
+    let b: f64 = 4.0 * specific_heat_ratio - (specific_heat_ratio - 1.0).powi(2)
+        - temperature_ratio * (specific_heat_ratio + 1.0).powi(2);
+    let c: f64 = -2.0 * (specific_heat_ratio - 1.0);
+    let upstream_mach_squared: f64 = (-b + (b.powi(2) - 4.0 * a * c).sqrt()) / (2.0 * a);
+    Ok(upstream_mach_squared.sqrt())
+}
+
+fn solve_upstream_mach(f: &impl Fn(f64) -> f64) -> Result<f64, InletError> {
+    // the pressure ratios are monotonic in mach number, so double the upper bound from
+    // mach 2 until the target is passed
+    let mut upper_bound: f64 = 2.0;
+    while f(1.0) * f(upper_bound) > 0.0 {
+        upper_bound *= 2.0;
+        if upper_bound > MAX_UPSTREAM_MACH {
+            return Err(InletError::NoSolution("no upstream mach number gives the pressure ratio"));
+        }
+    }
+    Ok(brent(f, 1.0, upper_bound, Some(1e-12), None)?.root)
+}
+
+pub fn calc_upstream_mach_from_stagnation_pressure_ratio(stagnation_pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if stagnation_pressure_ratio.is_nan() || stagnation_pressure_ratio <= 0.0 || stagnation_pressure_ratio > 1.0 {
+        return Err(InletError::InvalidParameter { name: "stagnation pressure ratio", value: stagnation_pressure_ratio });
+    }
+    let f = |upstream_mach: f64| {
+        calc_stagnation_pressure_ratio(upstream_mach, specific_heat_ratio).map_or(f64::NAN, |ratio| ratio - stagnation_pressure_ratio)
+    };
+    solve_upstream_mach(&f)
+}
+
+pub fn calc_upstream_mach_from_pitot_pressure_ratio(pitot_pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // below the sonic value the pitot probe sees no shock and the flow is subsonic
+    let sonic_pitot_pressure_ratio: f64 = calc_pitot_pressure_ratio(1.0, specific_heat_ratio)?;
+    if pitot_pressure_ratio.is_nan() || pitot_pressure_ratio < sonic_pitot_pressure_ratio {
+        return Err(InletError::InvalidParameter { name: "pitot pressure ratio", value: pitot_pressure_ratio });
+    }
+    let f = |upstream_mach: f64| {
+        calc_pitot_pressure_ratio(upstream_mach, specific_heat_ratio).map_or(f64::NAN, |ratio| ratio - pitot_pressure_ratio)
+    };
+    solve_upstream_mach(&f)
+}
+
+#[derive(Debug, Clone)]
+pub struct ShockJump {
+    // downstream to upstream ratios across a shock in a general gas model
+    pub downstream_mach: f64,
+    pub pressure_ratio: f64,
+    pub density_ratio: f64,
+    pub temperature_ratio: f64,
+    pub stagnation_pressure_ratio: f64,
+    pub deflection_angle: f64, // zero for a normal shock
+}
+
+pub fn calc_shock_jump_for_gas(upstream_mach: f64, upstream_temperature: f64, gas: &impl GasModel) -> Result<ShockJump, InletError> {
+    // the rankine hugoniot relations reduce to one equation in the inverse density ratio,
+    // here divided through by the trivial root at one so that the only root in (0, 1) is
+    // the shock, with the temperature rise over the enthalpy rise being the mean of 1 / cp
+    if upstream_temperature.is_nan() || upstream_temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "temperature", value: upstream_temperature });
+    }
+    let specific_heat_ratio: f64 = gas.specific_heat_ratio(upstream_temperature);
+    validate(upstream_mach, specific_heat_ratio)?;
+    let velocity: f64 = upstream_mach * gas.speed_of_sound(upstream_temperature);
+    let upstream_enthalpy: f64 = gas.enthalpy(upstream_temperature);
+
+    let downstream_temperature = |inverse_density_ratio: f64| {
+        let enthalpy_rise: f64 = velocity.powi(2) * (1.0 - inverse_density_ratio.powi(2)) / 2.0;
+        gas.temperature_from_enthalpy(upstream_enthalpy + enthalpy_rise).map(|temperature| (temperature, enthalpy_rise))
+    };
+    let f = |inverse_density_ratio: f64| {
+        downstream_temperature(inverse_density_ratio).map_or(f64::NAN, |(temperature, enthalpy_rise)| {
+            let mean_inverse_cp: f64 = if enthalpy_rise > 1e-6 * upstream_enthalpy {
+                (temperature - upstream_temperature) / enthalpy_rise
+            } else {
+                1.0 / gas.specific_heat_cp(upstream_temperature)
+            };
+            inverse_density_ratio * specific_heat_ratio * upstream_mach.powi(2) - 1.0
+                - velocity.powi(2) * (1.0 + inverse_density_ratio) / (2.0 * upstream_temperature) * mean_inverse_cp
+        })
+    };
+    // at sonic speed the residual vanishes at one, up to rounding, and there is no shock
+    let inverse_density_ratio: f64 = if f(1.0) <= 0.0 { 1.0 } else { brent(&f, 0.0, 1.0, Some(1e-15), None)?.root };
+    let pressure_ratio: f64 = 1.0 + specific_heat_ratio * upstream_mach.powi(2) * (1.0 - inverse_density_ratio);
+    let (downstream_temperature, _) = downstream_temperature(inverse_density_ratio)?;
+
+    // the entropy rise sets the stagnation pressure loss
+    let entropy_rise: f64 = gas.entropy_function(downstream_temperature) - gas.entropy_function(upstream_temperature)
+        - gas.gas_constant() * pressure_ratio.ln();
+    Ok(ShockJump {
+        downstream_mach: inverse_density_ratio * velocity / gas.speed_of_sound(downstream_temperature),
+        pressure_ratio,
+        density_ratio: 1.0 / inverse_density_ratio,
+        temperature_ratio: downstream_temperature / upstream_temperature,
+        stagnation_pressure_ratio: (-entropy_rise / gas.gas_constant()).exp(),
+        deflection_angle: 0.0,
+    })
+}
+
+pub fn calc_shock_jump_for_equilibrium_air(
+    upstream_mach: f64,
+    upstream: &EquilibriumState,
+    air: &EquilibriumAir,
+) -> Result<(ShockJump, EquilibriumState), InletError> {
+    // the downstream state relaxes to equilibrium at the pressure and enthalpy set by the
+    // momentum and energy balances, and mass conservation is solved for the inverse density
+    // ratio with the trivial root at one divided out
+    if upstream_mach.is_nan() || upstream_mach < 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
+    }
+    let velocity: f64 = upstream_mach * air.speed_of_sound(upstream)?;
+    let mass_flux: f64 = upstream.density * velocity;
+    let downstream = |inverse_density_ratio: f64| {
+        air.state_from_enthalpy(
+            upstream.pressure + mass_flux * velocity * (1.0 - inverse_density_ratio),
+            upstream.enthalpy + velocity.powi(2) * (1.0 - inverse_density_ratio.powi(2)) / 2.0,
+        )
+    };
+    let f = |inverse_density_ratio: f64| {
+        downstream(inverse_density_ratio).map_or(f64::NAN, |state| {
+            (upstream.density / state.density - inverse_density_ratio) / (1.0 - inverse_density_ratio)
+        })
+    };
+    let inverse_density_ratio: f64 = if f(MAX_INVERSE_DENSITY_RATIO) >= 0.0 {
+        1.0
+    } else {
+        brent(&f, MIN_INVERSE_DENSITY_RATIO, MAX_INVERSE_DENSITY_RATIO, Some(1e-13), None)?.root
+    };
+    let downstream: EquilibriumState = if inverse_density_ratio == 1.0 { *upstream } else { downstream(inverse_density_ratio)? };
+
+    // without a fixed gas constant the stagnation pressures are found directly
+    let downstream_velocity: f64 = inverse_density_ratio * velocity;
+    let stagnation_pressure_ratio: f64 = air.stagnation_state(&downstream, downstream_velocity)?.pressure
+        / air.stagnation_state(upstream, velocity)?.pressure;
+    Ok((
+        ShockJump {
+            downstream_mach: downstream_velocity / air.speed_of_sound(&downstream)?,
+            pressure_ratio: downstream.pressure / upstream.pressure,
+            density_ratio: downstream.density / upstream.density,
+            temperature_ratio: downstream.temperature / upstream.temperature,
+            stagnation_pressure_ratio,
+            deflection_angle: 0.0,
+        },
+        downstream,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{equilibrium::Species, gas::{CaloricallyPerfectGas, ThermallyPerfectGas}};
+
+    #[test]
+    fn test_forward_relations() {
+        // tabulated values for a mach 2 normal shock in air
+        let specific_heat_ratio = 1.4;
+        let downstream_mach = calc_downstream_mach(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((downstream_mach - 0.57735).abs() < 1e-5);
+        let pressure_ratio = calc_pressure_ratio(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((pressure_ratio - 4.5).abs() < 1e-12);
+        let density_ratio = calc_density_ratio(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((density_ratio - 2.66667).abs() < 1e-5);
+        let temperature_ratio = calc_temperature_ratio(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((temperature_ratio - 1.6875).abs() < 1e-12);
+        let stagnation_pressure_ratio = calc_stagnation_pressure_ratio(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((stagnation_pressure_ratio - 0.72087).abs() < 1e-5);
+        let pitot_pressure_ratio = calc_pitot_pressure_ratio(2.0, specific_heat_ratio).expect("valid normal shock");
+        assert!((pitot_pressure_ratio - 5.6405).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sonic_limit() {
+        // a mach 1 normal shock is a mach wave and changes nothing
+        let specific_heat_ratio = 1.4;
+        assert!((calc_downstream_mach(1.0, specific_heat_ratio).expect("valid normal shock") - 1.0).abs() < 1e-12);
+        assert!((calc_pressure_ratio(1.0, specific_heat_ratio).expect("valid normal shock") - 1.0).abs() < 1e-12);
+        assert!((calc_stagnation_pressure_ratio(1.0, specific_heat_ratio).expect("valid normal shock") - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inverse_relations() {
+        // each inverse recovers the upstream mach number
+        let specific_heat_ratio = 1.3;
+        for upstream_mach in [1.2, 2.0, 6.0] {
+            let downstream_mach = calc_downstream_mach(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let pressure_ratio = calc_pressure_ratio(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let density_ratio = calc_density_ratio(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let temperature_ratio = calc_temperature_ratio(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let stagnation_pressure_ratio = calc_stagnation_pressure_ratio(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let pitot_pressure_ratio = calc_pitot_pressure_ratio(upstream_mach, specific_heat_ratio).expect("valid normal shock");
+            let recovered = [
+                calc_upstream_mach_from_downstream_mach(downstream_mach, specific_heat_ratio),
+                calc_upstream_mach_from_pressure_ratio(pressure_ratio, specific_heat_ratio),
+                calc_upstream_mach_from_density_ratio(density_ratio, specific_heat_ratio),
+                calc_upstream_mach_from_temperature_ratio(temperature_ratio, specific_heat_ratio),
+                calc_upstream_mach_from_stagnation_pressure_ratio(stagnation_pressure_ratio, specific_heat_ratio),
+                calc_upstream_mach_from_pitot_pressure_ratio(pitot_pressure_ratio, specific_heat_ratio),
+            ];
+            for result in recovered {
+                assert!((result.expect("valid inverse") - upstream_mach).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shock_jump_for_gas() {
+        // a calorically perfect gas reproduces the closed forms, while vibration absorbs
+        // energy behind a mach 8 shock, leaving it cooler and denser
+        let perfect = CaloricallyPerfectGas::air();
+        let jump = calc_shock_jump_for_gas(2.0, 300.0, &perfect).expect("valid normal shock");
+        assert!((jump.downstream_mach - calc_downstream_mach(2.0, 1.4).expect("valid normal shock")).abs() < 1e-12);
+        assert!((jump.pressure_ratio - 4.5).abs() < 1e-12);
+        assert!((jump.temperature_ratio - 1.6875).abs() < 1e-12);
+        assert!((jump.stagnation_pressure_ratio - calc_stagnation_pressure_ratio(2.0, 1.4).expect("valid normal shock")).abs() < 1e-12);
+
+        let air = ThermallyPerfectGas::air();
+        let real = calc_shock_jump_for_gas(8.0, 220.0, &air).expect("valid normal shock");
+        let ideal = calc_shock_jump_for_gas(8.0, 220.0, &perfect).expect("valid normal shock");
+        assert!(real.temperature_ratio < ideal.temperature_ratio);
+        assert!(real.density_ratio > ideal.density_ratio);
+        assert!((real.pressure_ratio / ideal.pressure_ratio - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_shock_jump_for_equilibrium_air() {
+        // a weak shock in cold air is within a percent of the perfect gas, while a mach 20
+        // shock dissociates the oxygen and leaves the gas far cooler and denser
+        let air = EquilibriumAir::air();
+        let cold = air.state(101325.0, 288.15).expect("valid state");
+        let (jump, _) = calc_shock_jump_for_equilibrium_air(2.0, &cold, &air).expect("valid normal shock");
+        assert!((jump.pressure_ratio / 4.5 - 1.0).abs() < 1e-2);
+        assert!((jump.stagnation_pressure_ratio / calc_stagnation_pressure_ratio(2.0, 1.4).expect("valid normal shock") - 1.0).abs() < 1e-2);
+
+        let upper_atmosphere = air.state(100.0, 250.0).expect("valid state");
+        let (jump, downstream) = calc_shock_jump_for_equilibrium_air(20.0, &upper_atmosphere, &air).expect("valid normal shock");
+        assert!(jump.density_ratio > 1.5 * calc_density_ratio(20.0, 1.4).expect("valid normal shock"));
+        assert!(jump.temperature_ratio < 0.5 * calc_temperature_ratio(20.0, 1.4).expect("valid normal shock"));
+        assert!(downstream.composition.mole_fraction(Species::AtomicOxygen) > 0.2);
+
+        // momentum is conserved across it
+        let velocity: f64 = 20.0 * air.speed_of_sound(&upper_atmosphere).expect("valid state");
+        let upstream_momentum: f64 = upper_atmosphere.pressure + upper_atmosphere.density * velocity.powi(2);
+        let downstream_momentum: f64 = downstream.pressure + downstream.density * (velocity / jump.density_ratio).powi(2);
+        assert!((downstream_momentum / upstream_momentum - 1.0).abs() < 1e-10);
+
+        // a sonic flow passes unchanged
+        let (jump, _) = calc_shock_jump_for_equilibrium_air(1.0, &cold, &air).expect("valid normal shock");
+        assert_eq!(jump.density_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(calc_downstream_mach(0.8, 1.4).is_err());
+        assert!(calc_pressure_ratio(2.0, 1.0).is_err());
+        assert!(calc_upstream_mach_from_downstream_mach(1.2, 1.4).is_err());
+        assert!(calc_upstream_mach_from_downstream_mach(0.3, 1.4).is_err());
+        assert!(calc_upstream_mach_from_pressure_ratio(0.5, 1.4).is_err());
+        assert!(calc_upstream_mach_from_density_ratio(6.5, 1.4).is_err());
+        assert!(calc_upstream_mach_from_temperature_ratio(0.9, 1.4).is_err());
+        assert!(calc_upstream_mach_from_stagnation_pressure_ratio(1.1, 1.4).is_err());
+        assert!(calc_upstream_mach_from_stagnation_pressure_ratio(0.0, 1.4).is_err());
+        assert!(calc_upstream_mach_from_pitot_pressure_ratio(1.5, 1.4).is_err());
+        assert!(calc_shock_jump_for_gas(0.5, 300.0, &CaloricallyPerfectGas::air()).is_err());
+        assert!(calc_shock_jump_for_gas(2.0, 0.0, &CaloricallyPerfectGas::air()).is_err());
+        let air = EquilibriumAir::air();
+        assert!(calc_shock_jump_for_equilibrium_air(0.5, &air.state(101325.0, 288.15).expect("valid state"), &air).is_err());
+    }
+}
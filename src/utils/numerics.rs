@@ -1,37 +1,116 @@
 #![allow(dead_code)]
 
+use crate::error::InletError;
+
 pub fn bisection (
     f: &impl Fn(f64) -> f64,
     x1: f64, // 1st solution bound
     x2: f64, // 2nd solution bound
     tolerance: Option<f64>,
     max_iters: Option<u16>,
-) -> f64 {
+) -> Result<f64, InletError> {
     // default tolerance 1e-9 unless otherwise given
     let tolerance = tolerance.unwrap_or(1e-9);
     let max_iters = max_iters.unwrap_or(200);
 
-    // initialise lower and upper bound according to given bounds
+    // initialise lower and upper bound according to given bounds, evaluating each once
     let (mut lowerbound, mut upperbound) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+    let f_lower = f(lowerbound);
+    let f_upper = f(upperbound);
+    if f_lower == 0.0 {
+        return Ok(lowerbound);
+    }
+    if f_upper == 0.0 {
+        return Ok(upperbound);
+    }
+    if f_lower * f_upper > 0.0 || f_lower.is_nan() || f_upper.is_nan() {
+        return Err(InletError::NotBracketed { lower_bound: x1, upper_bound: x2 });
+    }
+    let lower_sign = f_lower.signum();
+    let mut residual = f64::INFINITY;
 
     // iterate
     for _ in 0..max_iters {
         let midpoint = (upperbound + lowerbound) / 2.0;
-        
+        let f_midpoint = f(midpoint);
+        residual = f_midpoint.abs();
+
         // check convergence
-        if f(midpoint).abs() < tolerance || (upperbound - lowerbound) / 2.0 < tolerance {
-            return midpoint;
+        if residual < tolerance || (upperbound - lowerbound) / 2.0 < tolerance {
+            return Ok(midpoint);
         }
 
         // update bounds
-        if (f(midpoint) * f(lowerbound)) > 0.0 {
+        if f_midpoint * lower_sign > 0.0 {
             lowerbound = midpoint;
         } else {
             upperbound = midpoint;
         }
     }
 
-    panic!("solution not converged");
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual })
+}
+
+pub fn false_position(
+    f: &impl Fn(f64) -> f64,
+    x1: f64, // 1st solution bound
+    x2: f64, // 2nd solution bound
+    tolerance: Option<f64>,
+    max_iters: Option<u16>,
+) -> Result<f64, InletError> {
+    // illinois variant of regula falsi, for functions too costly to bisect
+    let tolerance = tolerance.unwrap_or(1e-9);
+    let max_iters = max_iters.unwrap_or(200);
+
+    // evaluate each bound once and keep the function values alongside
+    let (mut a, mut b) = (x1, x2);
+    let (mut f_a, mut f_b) = (f(a), f(b));
+    if f_a == 0.0 {
+        return Ok(a);
+    }
+    if f_b == 0.0 {
+        return Ok(b);
+    }
+    if f_a * f_b > 0.0 || f_a.is_nan() || f_b.is_nan() {
+        return Err(InletError::NotBracketed { lower_bound: x1, upper_bound: x2 });
+    }
+
+    // track which bound was retained last so its value can be halved
+    let mut side: i8 = 0;
+    let mut residual: f64 = f64::INFINITY;
+
+    for _ in 0..max_iters {
+        let c = (a * f_b - b * f_a) / (f_b - f_a);
+        let f_c = f(c);
+        if f_c.is_nan() {
+            return Err(InletError::NoSolution("function undefined inside the bracket"));
+        }
+        residual = f_c.abs();
+
+        // check convergence
+        if f_c.abs() < tolerance || (b - a).abs() / 2.0 < tolerance {
+            return Ok(c);
+        }
+
+        // update bounds
+        if f_c * f_b > 0.0 {
+            b = c;
+            f_b = f_c;
+            if side == -1 {
+                f_a /= 2.0;
+            }
+            side = -1;
+        } else {
+            a = c;
+            f_a = f_c;
+            if side == 1 {
+                f_b /= 2.0;
+            }
+            side = 1;
+        }
+    }
+
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual })
 }
 
 pub fn newton_raphson(
@@ -40,7 +119,7 @@ pub fn newton_raphson(
     x_init: f64,
     tolerance: Option<f64>,
     max_iters: Option<u16>,
-) -> f64 {
+) -> Result<f64, InletError> {
     // default tolerance 1e-9 unless otherwise given
     let tolerance = tolerance.unwrap_or(1e-9);
     let max_iters = max_iters.unwrap_or(200);
@@ -58,7 +137,7 @@ pub fn newton_raphson(
     // iterative solution
     for _ in 0..max_iters {
         if df_curr.abs() < 1e-12 {
-            panic!("derivative is too small, newton-raphson may fail")
+            return Err(InletError::ZeroDerivative { x: x_current });
         }
         x_next = x_current - (f_current / df_curr);
 
@@ -69,7 +148,7 @@ pub fn newton_raphson(
         // solver termination on convergence criteria
         if (x_next - x_current).abs() <= tolerance {
             x_current = x_next;
-            return x_current;
+            return Ok(x_current);
         }
 
         // store updated values for next iteration
@@ -78,5 +157,815 @@ pub fn newton_raphson(
         df_curr = df_next;
     }
 
-    panic!("solution not converged");
-}
\ No newline at end of file
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual: f_current.abs() })
+}
+
+// growth of the bracket each time it is expanded
+const BRACKET_GROWTH_FACTOR: f64 = 1.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RootStatus {
+    ExactRoot, // the function vanished
+    Residual,  // the function fell below the tolerance
+    StepSize,  // the bracket or step shrank below the tolerance
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RootSolution {
+    // a root with the diagnostics of how it was found
+    pub root: f64,
+    pub iterations: usize,
+    pub residual: f64,
+    pub status: RootStatus,
+}
+
+pub fn expand_bracket(
+    f: &impl Fn(f64) -> f64,
+    x1: f64, // 1st initial bound
+    x2: f64, // 2nd initial bound
+    max_iters: Option<u16>,
+) -> Result<(f64, f64), InletError> {
+    // grow the interval geometrically on the side closer to a root until the function
+    // changes sign across it
+    let max_iters = max_iters.unwrap_or(50);
+    if x1 == x2 || !x1.is_finite() || !x2.is_finite() {
+        return Err(InletError::NotBracketed { lower_bound: x1, upper_bound: x2 });
+    }
+    let (mut a, mut b) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+    let (mut f_a, mut f_b) = (f(a), f(b));
+    for _ in 0..max_iters {
+        if f_a * f_b <= 0.0 {
+            return Ok((a, b));
+        }
+        if f_a.abs() < f_b.abs() {
+            a += BRACKET_GROWTH_FACTOR * (a - b);
+            f_a = f(a);
+        } else {
+            b += BRACKET_GROWTH_FACTOR * (b - a);
+            f_b = f(b);
+        }
+    }
+    Err(InletError::NotBracketed { lower_bound: a, upper_bound: b })
+}
+
+pub fn brent(
+    f: &impl Fn(f64) -> f64,
+    x1: f64, // 1st solution bound
+    x2: f64, // 2nd solution bound
+    tolerance: Option<f64>,
+    max_iters: Option<u16>,
+) -> Result<RootSolution, InletError> {
+    // brent's method, taking inverse quadratic or secant steps while they stay inside the
+    // bracket and shrink it quickly enough, and bisecting otherwise
+    let tolerance = tolerance.unwrap_or(1e-9);
+    let max_iters = max_iters.unwrap_or(200);
+
+    let (mut a, mut b) = (x1, x2);
+    let (mut f_a, mut f_b) = (f(a), f(b));
+    if f_a == 0.0 {
+        return Ok(RootSolution { root: a, iterations: 0, residual: 0.0, status: RootStatus::ExactRoot });
+    }
+    if f_b == 0.0 {
+        return Ok(RootSolution { root: b, iterations: 0, residual: 0.0, status: RootStatus::ExactRoot });
+    }
+    if f_a * f_b > 0.0 || f_a.is_nan() || f_b.is_nan() {
+        return Err(InletError::NotBracketed { lower_bound: x1, upper_bound: x2 });
+    }
+
+    // b is the best estimate, a the previous one and c the other end of the bracket
+    let (mut c, mut f_c) = (b, f_b);
+    let (mut d, mut e) = (b - a, b - a);
+    for iteration in 1..=max_iters as usize {
+        if f_b * f_c > 0.0 {
+            c = a;
+            f_c = f_a;
+            d = b - a;
+            e = d;
+        }
+        if f_c.abs() < f_b.abs() {
+            a = b;
+            b = c;
+            c = a;
+            f_a = f_b;
+            f_b = f_c;
+            f_c = f_a;
+        }
+
+        // check convergence
+        let step_tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let midpoint_step = 0.5 * (c - b);
+        if f_b == 0.0 {
+            return Ok(RootSolution { root: b, iterations: iteration, residual: 0.0, status: RootStatus::ExactRoot });
+        }
+        if f_b.abs() < tolerance {
+            return Ok(RootSolution { root: b, iterations: iteration, residual: f_b.abs(), status: RootStatus::Residual });
+        }
+        if midpoint_step.abs() <= step_tolerance {
+            return Ok(RootSolution { root: b, iterations: iteration, residual: f_b.abs(), status: RootStatus::StepSize });
+        }
+
+        // interpolate when the last steps have been converging, non finite function values
+        // failing the comparisons and falling back to bisection
+        if e.abs() >= step_tolerance && f_a.abs() > f_b.abs() {
+            let s = f_b / f_a;
+            let (mut p, mut q) = if a == c {
+                (2.0 * midpoint_step * s, 1.0 - s)
+            } else {
+                let q = f_a / f_c;
+                let r = f_b / f_c;
+                (
+                    s * (2.0 * midpoint_step * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min_step = (3.0 * midpoint_step * q - (step_tolerance * q).abs()).min((e * q).abs());
+            if 2.0 * p < min_step {
+                e = d;
+                d = p / q;
+            } else {
+                d = midpoint_step;
+                e = d;
+            }
+        } else {
+            d = midpoint_step;
+            e = d;
+        }
+
+        a = b;
+        f_a = f_b;
+        b += if d.abs() > step_tolerance { d } else { step_tolerance.copysign(midpoint_step) };
+        f_b = f(b);
+        if f_b.is_nan() {
+            return Err(InletError::NoSolution("function undefined inside the bracket"));
+        }
+    }
+
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual: f_b.abs() })
+}
+
+pub fn safeguarded_newton(
+    f: &impl Fn(f64) -> f64,
+    df: &impl Fn(f64) -> f64,
+    x_init: f64,
+    x1: f64, // 1st solution bound
+    x2: f64, // 2nd solution bound
+    tolerance: Option<f64>,
+    max_iters: Option<u16>,
+) -> Result<RootSolution, InletError> {
+    // newton raphson kept inside a bracket, bisecting whenever a step would leave it, would
+    // not halve the previous step, or the derivative vanishes
+    let tolerance = tolerance.unwrap_or(1e-9);
+    let max_iters = max_iters.unwrap_or(200);
+
+    let (f_1, f_2) = (f(x1), f(x2));
+    if f_1 == 0.0 {
+        return Ok(RootSolution { root: x1, iterations: 0, residual: 0.0, status: RootStatus::ExactRoot });
+    }
+    if f_2 == 0.0 {
+        return Ok(RootSolution { root: x2, iterations: 0, residual: 0.0, status: RootStatus::ExactRoot });
+    }
+    if f_1 * f_2 > 0.0 || f_1.is_nan() || f_2.is_nan() {
+        return Err(InletError::NotBracketed { lower_bound: x1, upper_bound: x2 });
+    }
+
+    // orient the bracket so the function is negative at the low end
+    let (mut x_low, mut x_high) = if f_1 < 0.0 { (x1, x2) } else { (x2, x1) };
+    let mut x = if (x_init - x1) * (x_init - x2) < 0.0 { x_init } else { 0.5 * (x1 + x2) };
+    let mut previous_step = (x2 - x1).abs();
+    let mut step = previous_step;
+    let mut f_x = f(x);
+    let mut df_x = df(x);
+
+    for iteration in 1..=max_iters as usize {
+        let newton_leaves_bracket = ((x - x_high) * df_x - f_x) * ((x - x_low) * df_x - f_x) > 0.0;
+        let newton_too_slow = (2.0 * f_x).abs() > (previous_step * df_x).abs();
+        previous_step = step;
+        if df_x == 0.0 || !df_x.is_finite() || newton_leaves_bracket || newton_too_slow {
+            step = 0.5 * (x_high - x_low);
+            x = x_low + step;
+        } else {
+            step = f_x / df_x;
+            x -= step;
+        }
+
+        f_x = f(x);
+        df_x = df(x);
+        if f_x.is_nan() {
+            return Err(InletError::NoSolution("function undefined inside the bracket"));
+        }
+
+        // check convergence
+        if f_x == 0.0 {
+            return Ok(RootSolution { root: x, iterations: iteration, residual: 0.0, status: RootStatus::ExactRoot });
+        }
+        if f_x.abs() < tolerance {
+            return Ok(RootSolution { root: x, iterations: iteration, residual: f_x.abs(), status: RootStatus::Residual });
+        }
+        if step.abs() < tolerance {
+            return Ok(RootSolution { root: x, iterations: iteration, residual: f_x.abs(), status: RootStatus::StepSize });
+        }
+
+        // update bounds
+        if f_x < 0.0 {
+            x_low = x;
+        } else {
+            x_high = x;
+        }
+    }
+
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual: f_x.abs() })
+}
+
+// fraction of the interval kept at each golden section step, one over the golden ratio
+const GOLDEN_SECTION_RATIO: f64 = 0.618_033_988_749_894_9;
+
+pub fn golden_section_maximum(
+    f: &impl Fn(f64) -> f64,
+    x1: f64, // 1st interval bound
+    x2: f64, // 2nd interval bound
+    tolerance: Option<f64>,
+    max_iters: Option<u16>,
+) -> Result<f64, InletError> {
+    // maximum of a function with a single peak inside the interval, each step dropping the
+    // part beyond the lower of two interior points and reusing the other point in the next
+    let tolerance = tolerance.unwrap_or(1e-9);
+    let max_iters = max_iters.unwrap_or(200);
+    if x1 == x2 || !x1.is_finite() || !x2.is_finite() {
+        return Err(InletError::InvalidParameter { name: "search interval width", value: x2 - x1 });
+    }
+
+    let (mut a, mut b) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+    let mut c = b - GOLDEN_SECTION_RATIO * (b - a);
+    let mut d = a + GOLDEN_SECTION_RATIO * (b - a);
+    let (mut f_c, mut f_d) = (f(c), f(d));
+    for _ in 0..max_iters {
+        if f_c.is_nan() || f_d.is_nan() {
+            return Err(InletError::NoSolution("function undefined inside the interval"));
+        }
+
+        // check convergence
+        if b - a < tolerance {
+            return Ok(if f_c > f_d { c } else { d });
+        }
+
+        // update bounds
+        if f_c > f_d {
+            b = d;
+            (d, f_d) = (c, f_c);
+            c = b - GOLDEN_SECTION_RATIO * (b - a);
+            f_c = f(c);
+        } else {
+            a = c;
+            (c, f_c) = (d, f_d);
+            d = a + GOLDEN_SECTION_RATIO * (b - a);
+            f_d = f(d);
+        }
+    }
+
+    Err(InletError::NotConverged { iterations: max_iters as usize, residual: b - a })
+}
+
+// dormand prince 5(4) coefficients, the fifth order solution being propagated
+const DORMAND_PRINCE_C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DORMAND_PRINCE_A: [[f64; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+const DORMAND_PRINCE_B: [f64; 7] = [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0];
+// difference between the fifth and fourth order weights
+const DORMAND_PRINCE_E: [f64; 7] = [
+    71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0,
+];
+// dense output weights for the continuous extension, as polynomials in the step fraction
+const DORMAND_PRINCE_DENSE: [[f64; 4]; 7] = [
+    [1.0, -8048581381.0 / 2820520608.0, 8663915743.0 / 2820520608.0, -12715105075.0 / 11282082432.0],
+    [0.0, 0.0, 0.0, 0.0],
+    [0.0, 131558114200.0 / 32700410799.0, -68118460800.0 / 10900136933.0, 87487479700.0 / 32700410799.0],
+    [0.0, -1754552775.0 / 470086768.0, 14199869525.0 / 1410260304.0, -10690763975.0 / 1880347072.0],
+    [0.0, 127303824393.0 / 49829197408.0, -318862633887.0 / 49829197408.0, 701980252875.0 / 199316789632.0],
+    [0.0, -282668133.0 / 205662961.0, 2019193451.0 / 616988883.0, -1453857185.0 / 822651844.0],
+    [0.0, 40617522.0 / 29380423.0, -110615467.0 / 29380423.0, 69997945.0 / 29380423.0],
+];
+
+// limits on the adaptive integration, the step size being allowed to shrink towards a
+// singular surface of the equations until it is this fraction of the integration span
+const ADAPTIVE_MAX_STEPS: usize = 1000000;
+const ADAPTIVE_MIN_STEP_FRACTION: f64 = 1e-12;
+const ADAPTIVE_SAFETY_FACTOR: f64 = 0.9;
+const ADAPTIVE_MIN_SCALE: f64 = 0.2;
+const ADAPTIVE_MAX_SCALE: f64 = 5.0;
+
+// tolerance on the step fraction when locating events
+const EVENT_TOLERANCE: f64 = 1e-15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventDirection {
+    Rising,  // event function passing from negative to zero or positive
+    Falling, // event function passing from positive to zero or negative
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventAction {
+    Record,     // locate the event and carry on integrating
+    Stop,       // locate the event and end the solution there
+    StopBefore, // end at the last step before the event, for singular surfaces that cannot be stepped onto
+}
+
+pub struct Event<'a, const N: usize> {
+    // a function of the independent variable and state whose sign change marks the event
+    pub function: &'a dyn Fn(f64, &[f64; N]) -> f64,
+    pub direction: EventDirection,
+    pub action: EventAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Termination {
+    Completed,     // the end of the integration range was reached
+    Event(usize),  // the stopping event with this index in the event list
+    Singular,      // the solution could not be continued, by step underflow or a non finite state
+}
+
+#[derive(Debug, Clone)]
+pub struct EventPoint<const N: usize> {
+    pub event: usize,
+    pub x: f64,
+    pub y: [f64; N],
+}
+
+#[derive(Debug, Clone)]
+struct DenseStep<const N: usize> {
+    // polynomial in the step fraction s with y = y0 + sum of s^(k+1) coefficients[k]
+    h: f64,
+    coefficients: [[f64; N]; 4],
+}
+
+#[derive(Debug, Clone)]
+pub struct OdeSolution<const N: usize> {
+    // a struct to organise the accepted steps of an integration with its continuous extension
+    pub x: Vec<f64>,
+    pub y: Vec<[f64; N]>,
+    pub events: Vec<EventPoint<N>>,
+    pub termination: Termination,
+    dense: Vec<DenseStep<N>>,
+}
+
+impl<const N: usize> OdeSolution<N> {
+    pub fn interpolate(&self, x: f64) -> Option<[f64; N]> {
+        // dense output anywhere between the first and last points
+        let (first, last) = (*self.x.first()?, *self.x.last()?);
+        if (x - first) * (x - last) > 0.0 {
+            return None;
+        }
+        let direction: f64 = (last - first).signum();
+        let index: usize = self.x.partition_point(|value| (x - value) * direction > 0.0).saturating_sub(1);
+        match self.dense.get(index) {
+            Some(step) => Some(evaluate_dense(&self.y[index], step, (x - self.x[index]) / step.h)),
+            None => Some(self.y[index]),
+        }
+    }
+}
+
+fn evaluate_dense<const N: usize>(y: &[f64; N], step: &DenseStep<N>, fraction: f64) -> [f64; N] {
+    let mut interpolated: [f64; N] = *y;
+    for (component, value) in interpolated.iter_mut().enumerate() {
+        let [c1, c2, c3, c4] = [0, 1, 2, 3].map(|k| step.coefficients[k][component]);
+        *value += fraction * (c1 + fraction * (c2 + fraction * (c3 + fraction * c4)));
+    }
+    interpolated
+}
+
+fn event_triggered(direction: EventDirection, g_start: f64, g_end: f64) -> bool {
+    let rising: bool = g_start < 0.0 && g_end >= 0.0;
+    let falling: bool = g_start > 0.0 && g_end <= 0.0;
+    match direction {
+        EventDirection::Rising => rising,
+        EventDirection::Falling => falling,
+        EventDirection::Either => rising || falling,
+    }
+}
+
+struct Integration<'a, 'b, const N: usize> {
+    // bookkeeping shared by the fixed and adaptive integrators
+    events: &'a [Event<'b, N>],
+    event_values: Vec<f64>,
+    solution: OdeSolution<N>,
+}
+
+impl<'a, 'b, const N: usize> Integration<'a, 'b, N> {
+    fn new(events: &'a [Event<'b, N>], x0: f64, y0: [f64; N]) -> Self {
+        Integration {
+            events,
+            event_values: events.iter().map(|event| (event.function)(x0, &y0)).collect(),
+            solution: OdeSolution {
+                x: vec![x0],
+                y: vec![y0],
+                events: Vec::new(),
+                termination: Termination::Completed,
+                dense: Vec::new(),
+            },
+        }
+    }
+
+    fn accept(&mut self, x1: f64, y1: [f64; N], step: DenseStep<N>) -> Result<bool, InletError> {
+        // add a step, handling any events inside it, and report whether integration goes on
+        let x0: f64 = self.solution.x[self.solution.x.len() - 1];
+        let y0: [f64; N] = self.solution.y[self.solution.y.len() - 1];
+        let end_values: Vec<f64> = self.events.iter().map(|event| (event.function)(x1, &y1)).collect();
+
+        // the first terminal event in the list to trigger ends the solution
+        let mut stop: Option<(usize, f64)> = None;
+        for (index, event) in self.events.iter().enumerate() {
+            if event.action == EventAction::Record || !event_triggered(event.direction, self.event_values[index], end_values[index]) {
+                continue;
+            }
+            let fraction: f64 = match event.action {
+                EventAction::StopBefore => 0.0,
+                _ => locate_event(event, x0, &y0, &step)?,
+            };
+            stop = Some((index, fraction));
+            break;
+        }
+        let end_fraction: f64 = stop.map_or(1.0, |(_, fraction)| fraction);
+
+        // recorded events up to the end of the accepted part of the step
+        for (index, event) in self.events.iter().enumerate() {
+            if event.action != EventAction::Record || !event_triggered(event.direction, self.event_values[index], end_values[index]) {
+                continue;
+            }
+            let fraction: f64 = locate_event(event, x0, &y0, &step)?;
+            if fraction <= end_fraction {
+                self.solution.events.push(EventPoint { event: index, x: x0 + fraction * step.h, y: evaluate_dense(&y0, &step, fraction) });
+            }
+        }
+
+        match stop {
+            Some((index, fraction)) => {
+                if self.events[index].action == EventAction::Stop {
+                    let (x, y) = if fraction < 1.0 { (x0 + fraction * step.h, evaluate_dense(&y0, &step, fraction)) } else { (x1, y1) };
+                    self.solution.events.push(EventPoint { event: index, x, y });
+                    self.solution.x.push(x);
+                    self.solution.y.push(y);
+                    self.solution.dense.push(step);
+                }
+                self.solution.termination = Termination::Event(index);
+                Ok(false)
+            }
+            None => {
+                self.solution.x.push(x1);
+                self.solution.y.push(y1);
+                self.solution.dense.push(step);
+                self.event_values = end_values;
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn locate_event<const N: usize>(event: &Event<N>, x0: f64, y0: &[f64; N], step: &DenseStep<N>) -> Result<f64, InletError> {
+    // root of the event function along the continuous extension of the step
+    let g = |fraction: f64| (event.function)(x0 + fraction * step.h, &evaluate_dense(y0, step, fraction));
+    false_position(&g, 0.0, 1.0, Some(EVENT_TOLERANCE), None)
+}
+
+pub fn rk4<const N: usize>(
+    f: &impl Fn(f64, &[f64; N]) -> Result<[f64; N], InletError>,
+    x0: f64,
+    y0: [f64; N],
+    x_end: f64,
+    steps: usize,
+    events: &[Event<N>],
+) -> Result<OdeSolution<N>, InletError> {
+    // classical 4th order runge kutta with a fixed step, interpolated by cubic hermite
+    // polynomials between steps
+    if steps == 0 {
+        return Err(InletError::InvalidParameter { name: "steps", value: 0.0 });
+    }
+    let h: f64 = (x_end - x0) / steps as f64;
+    let mut integration: Integration<N> = Integration::new(events, x0, y0);
+    let mut x: f64 = x0;
+    let mut y: [f64; N] = y0;
+    let mut derivative: [f64; N] = f(x, &y)?;
+
+    for step in 0..steps {
+        let k1: [f64; N] = derivative;
+        let k2: [f64; N] = f(x + 0.5 * h, &add_scaled(&y, 0.5 * h, &k1))?;
+        let k3: [f64; N] = f(x + 0.5 * h, &add_scaled(&y, 0.5 * h, &k2))?;
+        let k4: [f64; N] = f(x + h, &add_scaled(&y, h, &k3))?;
+        let mut next_y: [f64; N] = y;
+        for component in 0..N {
+            next_y[component] += h / 6.0 * (k1[component] + 2.0 * k2[component] + 2.0 * k3[component] + k4[component]);
+        }
+        let next_x: f64 = if step + 1 == steps { x_end } else { x0 + (step + 1) as f64 * h };
+        let next_derivative: [f64; N] = f(next_x, &next_y)?;
+        if next_y.iter().chain(&next_derivative).any(|value| !value.is_finite()) {
+            integration.solution.termination = Termination::Singular;
+            break;
+        }
+
+        let mut coefficients: [[f64; N]; 4] = [[0.0; N]; 4];
+        for component in 0..N {
+            let change: f64 = next_y[component] - y[component];
+            coefficients[0][component] = h * k1[component];
+            coefficients[1][component] = 3.0 * change - h * (2.0 * k1[component] + next_derivative[component]);
+            coefficients[2][component] = h * (k1[component] + next_derivative[component]) - 2.0 * change;
+        }
+        if !integration.accept(next_x, next_y, DenseStep { h, coefficients })? {
+            break;
+        }
+        x = next_x;
+        y = next_y;
+        derivative = next_derivative;
+    }
+
+    Ok(integration.solution)
+}
+
+pub fn rk45<const N: usize>(
+    f: &impl Fn(f64, &[f64; N]) -> Result<[f64; N], InletError>,
+    x0: f64,
+    y0: [f64; N],
+    x_end: f64,
+    tolerance: f64,
+    max_step: f64,
+    events: &[Event<N>],
+) -> Result<OdeSolution<N>, InletError> {
+    // dormand prince embedded runge kutta with local error control, where each component's
+    // error is held below the tolerance times one plus its magnitude
+    if tolerance.is_nan() || tolerance <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "tolerance", value: tolerance });
+    }
+    if max_step.is_nan() || max_step <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "max step", value: max_step });
+    }
+    let span: f64 = x_end - x0;
+    let direction: f64 = span.signum();
+    let min_step: f64 = ADAPTIVE_MIN_STEP_FRACTION * span.abs();
+
+    let mut integration: Integration<N> = Integration::new(events, x0, y0);
+    let mut x: f64 = x0;
+    let mut y: [f64; N] = y0;
+    let mut derivative: [f64; N] = f(x, &y)?;
+    let mut h: f64 = direction * max_step.min(span.abs());
+
+    for _ in 0..ADAPTIVE_MAX_STEPS {
+        if (x_end - x) * direction <= 0.0 {
+            return Ok(integration.solution);
+        }
+        if (x + h - x_end) * direction > 0.0 {
+            h = x_end - x;
+        }
+
+        // stages, the last of which is the derivative at the new point
+        let mut stages: [[f64; N]; 7] = [[0.0; N]; 7];
+        stages[0] = derivative;
+        for stage in 1..7 {
+            let mut stage_y: [f64; N] = y;
+            for j in 0..stage {
+                for component in 0..N {
+                    stage_y[component] += h * DORMAND_PRINCE_A[stage][j] * stages[j][component];
+                }
+            }
+            stages[stage] = f(x + DORMAND_PRINCE_C[stage] * h, &stage_y)?;
+        }
+        let mut next_y: [f64; N] = y;
+        let mut error: [f64; N] = [0.0; N];
+        for j in 0..7 {
+            for component in 0..N {
+                next_y[component] += h * DORMAND_PRINCE_B[j] * stages[j][component];
+                error[component] += h * DORMAND_PRINCE_E[j] * stages[j][component];
+            }
+        }
+
+        // non finite trial states near a singular surface are treated as failed steps
+        let mut error_norm: f64 = 0.0;
+        for component in 0..N {
+            let scale: f64 = tolerance * (1.0 + next_y[component].abs().max(y[component].abs()));
+            error_norm = error_norm.max(error[component].abs() / scale);
+        }
+        let finite: bool = error_norm.is_finite() && next_y.iter().chain(&stages[6]).all(|value| value.is_finite());
+
+        if !finite || error_norm > 1.0 {
+            h *= if finite {
+                (ADAPTIVE_SAFETY_FACTOR * error_norm.powf(-0.2)).max(ADAPTIVE_MIN_SCALE)
+            } else {
+                ADAPTIVE_MIN_SCALE
+            };
+            if h.abs() < min_step {
+                integration.solution.termination = Termination::Singular;
+                return Ok(integration.solution);
+            }
+            continue;
+        }
+
+        let mut coefficients: [[f64; N]; 4] = [[0.0; N]; 4];
+        for (k, coefficient) in coefficients.iter_mut().enumerate() {
+            for j in 0..7 {
+                for component in 0..N {
+                    coefficient[component] += h * DORMAND_PRINCE_DENSE[j][k] * stages[j][component];
+                }
+            }
+        }
+        if !integration.accept(x + h, next_y, DenseStep { h, coefficients })? {
+            return Ok(integration.solution);
+        }
+        x += h;
+        y = next_y;
+        derivative = stages[6];
+
+        let scale: f64 = if error_norm > 0.0 {
+            (ADAPTIVE_SAFETY_FACTOR * error_norm.powf(-0.2)).clamp(ADAPTIVE_MIN_SCALE, ADAPTIVE_MAX_SCALE)
+        } else {
+            ADAPTIVE_MAX_SCALE
+        };
+        h = direction * (h.abs() * scale).min(max_step);
+    }
+
+    Err(InletError::NotConverged { iterations: ADAPTIVE_MAX_STEPS, residual: (x_end - x).abs() })
+}
+
+fn add_scaled<const N: usize>(y: &[f64; N], scale: f64, derivative: &[f64; N]) -> [f64; N] {
+    let mut result: [f64; N] = *y;
+    for component in 0..N {
+        result[component] += scale * derivative[component];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisection() {
+        // test bisection on a cubic with a single real root at 2
+        let f = |x: f64| x.powi(3) - 8.0;
+        let result = bisection(&f, 0.0, 5.0, None, None).expect("converged root");
+        assert!((result - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_false_position() {
+        // test false position on the same cubic, which converges from one side
+        let f = |x: f64| x.powi(3) - 8.0;
+        let result = false_position(&f, 0.0, 5.0, None, None).expect("converged root");
+        assert!((result - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_newton_raphson() {
+        // test newton raphson on the same cubic
+        let f = |x: f64| x.powi(3) - 8.0;
+        let df = |x: f64| 3.0 * x.powi(2);
+        let result = newton_raphson(&f, &df, 3.0, None, None).expect("converged root");
+        assert!((result - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_brent() {
+        // brent converges in far fewer evaluations than bisection and reports how
+        let f = |x: f64| x.powi(3) - 8.0;
+        let solution = brent(&f, 0.0, 5.0, Some(1e-12), None).expect("converged root");
+        assert!((solution.root - 2.0).abs() < 1e-9);
+        assert!(solution.iterations < 20);
+        assert!(solution.residual < 1e-9);
+        assert!(brent(&f, 2.0, 5.0, None, None).expect("converged root").status == RootStatus::ExactRoot);
+
+        // infinite values at the bracket ends fall back to bisection
+        let g = |x: f64| if x < 0.5 { f64::NEG_INFINITY } else { x - 1.0 };
+        let solution = brent(&g, 0.0, 3.0, None, None).expect("converged root");
+        assert!((solution.root - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_safeguarded_newton() {
+        // newton from a poor start, where plain newton raphson hits a stationary point
+        let f = |x: f64| x.powi(3) - 2.0 * x + 2.0;
+        let df = |x: f64| 3.0 * x.powi(2) - 2.0;
+        assert!(newton_raphson(&f, &df, 0.0, None, Some(50)).is_err());
+        let solution = safeguarded_newton(&f, &df, 0.0, -3.0, 0.0, None, None).expect("converged root");
+        assert!(f(solution.root).abs() < 1e-9);
+        assert!(solution.root > -3.0 && solution.root < 0.0);
+    }
+
+    #[test]
+    fn test_golden_section_maximum() {
+        // the peak of x exp(-x) lies at one, whichever way round the interval is given
+        let f = |x: f64| x * (-x).exp();
+        let peak = golden_section_maximum(&f, 0.0, 5.0, Some(1e-10), None).expect("converged maximum");
+        assert!((peak - 1.0).abs() < 1e-8);
+        let peak = golden_section_maximum(&f, 5.0, 0.0, Some(1e-10), None).expect("converged maximum");
+        assert!((peak - 1.0).abs() < 1e-8);
+        assert!(golden_section_maximum(&f, 1.0, 1.0, None, None).is_err());
+        assert!(matches!(golden_section_maximum(&f, 0.0, 5.0, Some(1e-10), Some(5)), Err(InletError::NotConverged { .. })));
+    }
+
+    #[test]
+    fn test_expand_bracket() {
+        // a root far outside the initial interval is enclosed
+        let f = |x: f64| x - 100.0;
+        let (lower, upper) = expand_bracket(&f, 0.0, 1.0, None).expect("bracketed root");
+        assert!(f(lower) * f(upper) <= 0.0);
+        let g = |x: f64| x.powi(2) + 1.0;
+        assert!(matches!(expand_bracket(&g, 0.0, 1.0, None), Err(InletError::NotBracketed { .. })));
+    }
+
+    #[test]
+    fn test_rk4() {
+        // exponential growth, where halving the step cuts the error sixteen fold
+        let f = |_x: f64, y: &[f64; 1]| Ok([y[0]]);
+        let coarse = rk4(&f, 0.0, [1.0], 1.0, 10, &[]).expect("valid solution");
+        let fine = rk4(&f, 0.0, [1.0], 1.0, 20, &[]).expect("valid solution");
+        let exact = 1.0_f64.exp();
+        let coarse_error = (coarse.y[10][0] - exact).abs();
+        let fine_error = (fine.y[20][0] - exact).abs();
+        assert!(coarse_error < 1e-5);
+        assert!((coarse_error / fine_error - 16.0).abs() < 1.0);
+        assert_eq!(coarse.termination, Termination::Completed);
+    }
+
+    #[test]
+    fn test_rk45_dense_output() {
+        // a harmonic oscillator is followed to tolerance, both at steps and between them
+        let f = |_x: f64, y: &[f64; 2]| Ok([y[1], -y[0]]);
+        let solution = rk45(&f, 0.0, [0.0, 1.0], 10.0, 1e-10, 1.0, &[]).expect("valid solution");
+        let last = solution.y.len() - 1;
+        assert!((solution.x[last] - 10.0).abs() < 1e-12);
+        assert!((solution.y[last][0] - 10.0_f64.sin()).abs() < 1e-8);
+        for x in [0.05, 1.234, 5.5, 9.99] {
+            let y = solution.interpolate(x).expect("inside the solution");
+            assert!((y[0] - x.sin()).abs() < 1e-8);
+            assert!((y[1] - x.cos()).abs() < 1e-8);
+        }
+        assert!(solution.interpolate(10.5).is_none());
+
+        // integrating backwards works the same way
+        let backwards = rk45(&f, 10.0, solution.y[last], 0.0, 1e-10, 1.0, &[]).expect("valid solution");
+        assert!(backwards.y[backwards.y.len() - 1][0].abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_events() {
+        // zero crossings of a sine are recorded and a falling value stops the integration
+        let f = |_x: f64, y: &[f64; 2]| Ok([y[1], -y[0]]);
+        let position = |_x: f64, y: &[f64; 2]| y[0];
+        let threshold = |_x: f64, y: &[f64; 2]| y[0] + 0.5;
+        let events = [
+            Event { function: &position, direction: EventDirection::Either, action: EventAction::Record },
+            Event { function: &threshold, direction: EventDirection::Falling, action: EventAction::Stop },
+        ];
+        let solution = rk45(&f, 0.0, [0.0, 1.0], 20.0, 1e-10, 0.5, &events).expect("valid solution");
+        assert_eq!(solution.termination, Termination::Event(1));
+        let stop_x = 7.0 * std::f64::consts::PI / 6.0;
+        assert!((solution.x[solution.x.len() - 1] - stop_x).abs() < 1e-9);
+        let crossings: Vec<f64> = solution.events.iter().filter(|event| event.event == 0).map(|event| event.x).collect();
+        assert_eq!(crossings.len(), 1);
+        assert!((crossings[0] - std::f64::consts::PI).abs() < 1e-9);
+
+        // stopping before an event leaves the solution short of it
+        let events = [Event { function: &threshold, direction: EventDirection::Falling, action: EventAction::StopBefore }];
+        let solution = rk4(&f, 0.0, [0.0, 1.0], 20.0, 200, &events).expect("valid solution");
+        let last = solution.x[solution.x.len() - 1];
+        assert!(last < stop_x && last > stop_x - 0.1 - 1e-12);
+        assert!(solution.events.is_empty());
+    }
+
+    #[test]
+    fn test_singular() {
+        // a solution blowing up in finite time ends at the singularity rather than failing
+        let f = |_x: f64, y: &[f64; 1]| Ok([y[0].powi(2)]);
+        let solution = rk45(&f, 0.0, [1.0], 2.0, 1e-8, 0.1, &[]).expect("valid solution");
+        assert_eq!(solution.termination, Termination::Singular);
+        let last = solution.x.len() - 1;
+        assert!((solution.x[last] - 1.0).abs() < 1e-6);
+        assert!(solution.y[last][0] > 1e6);
+    }
+
+    #[test]
+    fn test_failures() {
+        // failures are reported as errors rather than panics
+        let f = |x: f64| x.powi(2) + 1.0;
+        let df = |x: f64| 2.0 * x;
+        assert!(matches!(false_position(&f, -1.0, 2.0, None, None), Err(InletError::NotBracketed { .. })));
+        assert!(matches!(bisection(&f, -1.0, 2.0, None, None), Err(InletError::NotBracketed { .. })));
+        assert!(matches!(brent(&f, -1.0, 2.0, None, None), Err(InletError::NotBracketed { .. })));
+        assert!(matches!(safeguarded_newton(&f, &df, 0.0, -1.0, 2.0, None, None), Err(InletError::NotBracketed { .. })));
+        assert!(matches!(brent(&|x: f64| x.cbrt(), -1.0, 2.0, Some(1e-300), Some(3)), Err(InletError::NotConverged { iterations: 3, .. })));
+        assert!(matches!(newton_raphson(&f, &df, 0.0, None, None), Err(InletError::ZeroDerivative { .. })));
+        assert!(matches!(newton_raphson(&f, &df, 0.5, None, Some(5)), Err(InletError::NotConverged { iterations: 5, .. })));
+
+        let f = |_x: f64, y: &[f64; 1]| Ok([y[0]]);
+        assert!(matches!(rk4(&f, 0.0, [1.0], 1.0, 0, &[]), Err(InletError::InvalidParameter { .. })));
+        assert!(matches!(rk45(&f, 0.0, [1.0], 1.0, 0.0, 0.1, &[]), Err(InletError::InvalidParameter { .. })));
+        assert!(matches!(rk45(&f, 0.0, [1.0], 1.0, 1e-8, f64::NAN, &[]), Err(InletError::InvalidParameter { .. })));
+    }
+}
@@ -1,33 +1,34 @@
 #![allow(dead_code)]
 
 use std::f64::consts::PI;
+use super::equilibrium::{EquilibriumAir, EquilibriumState};
+use super::gas::GasModel;
 use super::isentropic::valid_specific_heat_ratio; 
-use super::numerics::bisection;
+use super::normalshock::{self, ShockJump};
+use super::numerics::{brent, golden_section_maximum};
+use crate::error::InletError;
 
-fn calc_downstream_mach(upstream_mach: f64, shock_angle: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+fn calc_downstream_mach(upstream_mach: f64, shock_angle: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // normal shock on the velocity component normal to the shock, the tangential component
+    // being unchanged
     let normal_upstream_mach: f64 = calc_normal_upstream_mach(upstream_mach, shock_angle)?;
-
-    // this is wrong
-    let normal_downstream_mach: f64 = (
-        (1.0 + (specific_heat_ratio - 1.0) * normal_upstream_mach.powi(2) / 2.0)
-        / (specific_heat_ratio * normal_upstream_mach.powi(2) - (specific_heat_ratio - 1.0) / 2.0)
-    ).sqrt();
+    let normal_downstream_mach: f64 = normalshock::calc_downstream_mach(normal_upstream_mach, specific_heat_ratio)?;
 
     let downstream_mach: f64 = normal_downstream_mach / (shock_angle - deflection_angle).sin();
     Ok(downstream_mach)
 }
 
-pub fn calc_downstream_mach_from_shock_angle(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_downstream_mach_from_shock_angle(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     let deflection_angle: f64 = calc_deflection_angle(upstream_mach, shock_angle, specific_heat_ratio)?;
     calc_downstream_mach(upstream_mach, shock_angle, deflection_angle, specific_heat_ratio)
 }
 
-pub fn calc_downstream_mach_from_deflection_angle(upstream_mach: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_downstream_mach_from_deflection_angle(upstream_mach: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     let shock_angle: f64 = calc_shock_angle(upstream_mach, deflection_angle, specific_heat_ratio)?;
     calc_downstream_mach(upstream_mach, shock_angle, deflection_angle, specific_heat_ratio)
 }
 
-pub fn calc_deflection_angle(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_deflection_angle(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
     let tan_deflection_angle: f64 = 
         2.0 / shock_angle.tan() * 
         (upstream_mach.powi(2) * shock_angle.sin().powi(2) - 1.0) / 
@@ -35,99 +36,358 @@ pub fn calc_deflection_angle(upstream_mach: f64, shock_angle: f64, specific_heat
     Ok(tan_deflection_angle.atan())
 }
 
-pub fn calc_pressure_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
-    let pressure_ratio: f64 = 
-        (2.0 * specific_heat_ratio * upstream_mach.powi(2) 
-            * shock_angle.sin().powi(2) - (specific_heat_ratio - 1.0)) / 
-        (specific_heat_ratio + 1.0);
-    Ok(pressure_ratio)
+pub fn calc_pressure_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    normalshock::calc_pressure_ratio(calc_normal_upstream_mach(upstream_mach, shock_angle)?, specific_heat_ratio)
 }
 
-pub fn calc_density_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
-    let density_ratio: f64 = 
-    (specific_heat_ratio + 1.0) * upstream_mach.powi(2) * shock_angle.sin().powi(2) /
-    ((specific_heat_ratio - 1.0) * upstream_mach.powi(2) * shock_angle.sin().powi(2) + 2.0);
-    Ok(density_ratio)
-    
+pub fn calc_density_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    normalshock::calc_density_ratio(calc_normal_upstream_mach(upstream_mach, shock_angle)?, specific_heat_ratio)
 }
 
-pub fn calc_temperature_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
-    let pressure_ratio: f64 = calc_pressure_ratio(upstream_mach, shock_angle, specific_heat_ratio)?;
-    let density_ratio: f64 = calc_density_ratio(upstream_mach, shock_angle, specific_heat_ratio)?;
-    let temperature_ratio: f64 = pressure_ratio * (1.0 / density_ratio);
-    Ok(temperature_ratio)
+pub fn calc_temperature_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    normalshock::calc_temperature_ratio(calc_normal_upstream_mach(upstream_mach, shock_angle)?, specific_heat_ratio)
 }
 
-pub fn calc_stagnation_pressure_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
-    if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
-    }
-    let stagnation_pressure_ratio: f64 =
-        calc_density_ratio(upstream_mach, shock_angle, specific_heat_ratio)?.powf(specific_heat_ratio / (specific_heat_ratio - 1.0)) *
-        (1.0 / calc_pressure_ratio(upstream_mach, shock_angle, specific_heat_ratio)?).powf(1.0 / (specific_heat_ratio - 1.0));
-    Ok(stagnation_pressure_ratio)
+pub fn calc_stagnation_pressure_ratio(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    normalshock::calc_stagnation_pressure_ratio(calc_normal_upstream_mach(upstream_mach, shock_angle)?, specific_heat_ratio)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShockSolution {
+    Weak,   // shock angle below that of maximum deflection, usually supersonic downstream
+    Strong, // shock angle above that of maximum deflection, always subsonic downstream
+}
+
+pub fn calc_shock_angle(upstream_mach: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the weak solution, which is what an attached shock on a ramp or cone takes
+    calc_shock_angle_for_solution(upstream_mach, deflection_angle, ShockSolution::Weak, specific_heat_ratio)
 }
 
-pub fn calc_shock_angle(upstream_mach: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_shock_angle_for_solution(
+    upstream_mach: f64,
+    deflection_angle: f64,
+    solution: ShockSolution,
+    specific_heat_ratio: f64,
+) -> Result<f64, InletError> {
     if upstream_mach <= 1.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
+    }
+    if deflection_angle.is_nan() || deflection_angle < 0.0 {
+        return Err(InletError::InvalidParameter { name: "deflection angle", value: deflection_angle });
     }
     let f = |shock_angle: f64| {
-        let calculated_deflection_angle = match calc_deflection_angle(upstream_mach, shock_angle, specific_heat_ratio) {
-            Ok(value) => value,
-            Err(_) => panic!("something bad happened"),
-        };
-        return calculated_deflection_angle - deflection_angle
+        match calc_deflection_angle(upstream_mach, shock_angle, specific_heat_ratio) {
+            Ok(calculated_deflection_angle) => calculated_deflection_angle - deflection_angle,
+            Err(_) => f64::NAN,
+        }
     };
 
-    let lower_bound: f64 = deflection_angle;
-    let upper_bound: f64 = PI / 2.0;
+    // the deflection rises from zero at the mach angle to its maximum and falls back to zero
+    // for a normal shock, so the shock angle of maximum deflection separates the two roots
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    let max_shock_angle: f64 = calc_max_shock_angle(upstream_mach, specific_heat_ratio)?;
+    if f(max_shock_angle) < 0.0 {
+        return Err(InletError::DetachedShock { upstream_mach, deflection_angle });
+    }
+    if deflection_angle == 0.0 {
+        // a mach wave or a normal shock, where rounding would leave the roots unbracketed
+        return Ok(match solution {
+            ShockSolution::Weak => mach_angle,
+            ShockSolution::Strong => PI / 2.0,
+        });
+    }
+    let (lower_bound, upper_bound) = match solution {
+        ShockSolution::Weak => (mach_angle, max_shock_angle),
+        ShockSolution::Strong => (max_shock_angle, PI / 2.0),
+    };
 
-    let shock_angle: f64 = bisection(&f, lower_bound, upper_bound, None, None);
+    let shock_angle: f64 = brent(&f, lower_bound, upper_bound, Some(1e-12), None)?.root;
 
     if shock_angle.is_nan() {
-        return Err("math error");
+        return Err(InletError::NoSolution("math error"));
     }
 
     Ok(shock_angle)
 }
 
-pub fn calc_max_shock_angle(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, &'static str> {
+pub fn calc_max_deflection_angle(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // largest deflection an attached shock can turn the flow through
+    let max_shock_angle: f64 = calc_max_shock_angle(upstream_mach, specific_heat_ratio)?;
+    calc_deflection_angle(upstream_mach, max_shock_angle, specific_heat_ratio)
+}
+
+pub fn calc_sonic_shock_angle(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // shock angle leaving exactly sonic flow downstream, just below that of maximum deflection
     if !valid_specific_heat_ratio(specific_heat_ratio) {
-        return Err("invalid specific heat ratio");
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
     }
     if upstream_mach <= 1.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
+    }
+
+    let sin_sonic_shock_angle: f64 =
+        (((specific_heat_ratio + 1.0) * upstream_mach.powi(2) - (3.0 - specific_heat_ratio) +
+            ((specific_heat_ratio + 1.0) * (
+                (specific_heat_ratio + 1.0) * upstream_mach.powi(4) -
+                2.0 * (3.0 - specific_heat_ratio) * upstream_mach.powi(2) +
+                specific_heat_ratio + 9.0
+            )).sqrt()
+        ) / (4.0 * specific_heat_ratio * upstream_mach.powi(2))).sqrt();
+
+    if !(0.0..=1.0).contains(&sin_sonic_shock_angle) {
+        return Err(InletError::NoSolution("math error"));
+    }
+
+    Ok(sin_sonic_shock_angle.asin())
+}
+
+pub fn calc_sonic_deflection_angle(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // deflection beyond which the weak solution is subsonic downstream
+    let sonic_shock_angle: f64 = calc_sonic_shock_angle(upstream_mach, specific_heat_ratio)?;
+    calc_deflection_angle(upstream_mach, sonic_shock_angle, specific_heat_ratio)
+}
+
+pub fn calc_downstream_temperature(upstream_mach: f64, shock_angle: f64, upstream_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    // static temperature behind the shock from the jump solved in the gas model
+    if upstream_temperature.is_nan() || upstream_temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "temperature", value: upstream_temperature });
+    }
+    let jump: ShockJump = calc_shock_jump_for_gas(upstream_mach, shock_angle, upstream_temperature, gas)?;
+    Ok(upstream_temperature * jump.temperature_ratio)
+}
+
+pub fn calc_downstream_pressure(
+    upstream_mach: f64,
+    shock_angle: f64,
+    upstream_pressure: f64,
+    upstream_temperature: f64,
+    gas: &impl GasModel,
+) -> Result<f64, InletError> {
+    if upstream_temperature.is_nan() || upstream_temperature <= 0.0 {
+        return Err(InletError::InvalidParameter { name: "temperature", value: upstream_temperature });
+    }
+    let jump: ShockJump = calc_shock_jump_for_gas(upstream_mach, shock_angle, upstream_temperature, gas)?;
+    Ok(upstream_pressure * jump.pressure_ratio)
+}
+
+pub fn calc_shock_jump_for_gas(upstream_mach: f64, shock_angle: f64, upstream_temperature: f64, gas: &impl GasModel) -> Result<ShockJump, InletError> {
+    // the normal component passes through a normal shock while the tangential velocity is
+    // unchanged, so the flow turns until tan(beta - theta) = tan(beta) / density ratio
+    let normal_upstream_mach: f64 = calc_normal_upstream_mach(upstream_mach, shock_angle)?;
+    let normal_jump: ShockJump = normalshock::calc_shock_jump_for_gas(normal_upstream_mach, upstream_temperature, gas)?;
+    let deflection_angle: f64 = shock_angle - (shock_angle.tan() / normal_jump.density_ratio).atan();
+    let tangential_downstream_mach: f64 = upstream_mach * shock_angle.cos() / normal_jump.temperature_ratio.sqrt()
+        * (gas.specific_heat_ratio(upstream_temperature)
+            / gas.specific_heat_ratio(upstream_temperature * normal_jump.temperature_ratio)).sqrt();
+    Ok(ShockJump {
+        downstream_mach: (normal_jump.downstream_mach.powi(2) + tangential_downstream_mach.powi(2)).sqrt(),
+        deflection_angle,
+        ..normal_jump
+    })
+}
+
+pub fn calc_max_shock_angle_for_gas(upstream_mach: f64, upstream_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    // shock angle of maximum deflection, which the denser real gas shock moves away from
+    // the perfect gas value, found by golden section search between the mach angle and a
+    // normal shock, both of which leave the flow unturned
+    // the normal shock reports an invalid upstream state before the search starts
+    calc_shock_jump_for_gas(upstream_mach, PI / 2.0, upstream_temperature, gas)?;
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    let deflection = |shock_angle: f64| {
+        calc_shock_jump_for_gas(upstream_mach, shock_angle, upstream_temperature, gas)
+            .map_or(f64::NAN, |jump| jump.deflection_angle)
+    };
+    golden_section_maximum(&deflection, mach_angle, PI / 2.0, Some(1e-12), None)
+}
+
+pub fn calc_shock_angle_for_gas(upstream_mach: f64, deflection_angle: f64, upstream_temperature: f64, gas: &impl GasModel) -> Result<f64, InletError> {
+    // weak solution, bracketed by the mach angle and the angle of maximum deflection
+    if deflection_angle.is_nan() || deflection_angle < 0.0 {
+        return Err(InletError::InvalidParameter { name: "deflection angle", value: deflection_angle });
+    }
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    let max_shock_angle: f64 = calc_max_shock_angle_for_gas(upstream_mach, upstream_temperature, gas)?;
+    if deflection_angle == 0.0 {
+        return Ok(mach_angle);
+    }
+    let f = |shock_angle: f64| {
+        calc_shock_jump_for_gas(upstream_mach, shock_angle, upstream_temperature, gas)
+            .map_or(f64::NAN, |jump| jump.deflection_angle - deflection_angle)
+    };
+    if f(max_shock_angle) < 0.0 {
+        return Err(InletError::DetachedShock { upstream_mach, deflection_angle });
+    }
+    Ok(brent(&f, mach_angle, max_shock_angle, Some(1e-12), None)?.root)
+}
+
+pub fn calc_shock_jump_for_equilibrium_air(
+    upstream_mach: f64,
+    shock_angle: f64,
+    upstream: &EquilibriumState,
+    air: &EquilibriumAir,
+) -> Result<(ShockJump, EquilibriumState), InletError> {
+    // as calc_shock_jump_for_gas, with the stagnation pressures taken on the full velocities
+    let normal_upstream_mach: f64 = calc_normal_upstream_mach(upstream_mach, shock_angle)?;
+    let (normal_jump, downstream) = normalshock::calc_shock_jump_for_equilibrium_air(normal_upstream_mach, upstream, air)?;
+    let upstream_velocity: f64 = upstream_mach * air.speed_of_sound(upstream)?;
+    let downstream_velocity: f64 = (upstream_velocity * shock_angle.sin() / normal_jump.density_ratio)
+        .hypot(upstream_velocity * shock_angle.cos());
+    let stagnation_pressure_ratio: f64 = air.stagnation_state(&downstream, downstream_velocity)?.pressure
+        / air.stagnation_state(upstream, upstream_velocity)?.pressure;
+    Ok((
+        ShockJump {
+            downstream_mach: downstream_velocity / air.speed_of_sound(&downstream)?,
+            stagnation_pressure_ratio,
+            deflection_angle: shock_angle - (shock_angle.tan() / normal_jump.density_ratio).atan(),
+            ..normal_jump
+        },
+        downstream,
+    ))
+}
+
+pub fn calc_max_shock_angle_for_equilibrium_air(upstream_mach: f64, upstream: &EquilibriumState, air: &EquilibriumAir) -> Result<f64, InletError> {
+    // as calc_max_shock_angle_for_gas, the dissociating gas behind the shock moving the
+    // angle of maximum deflection further still
+
+    // the normal shock reports an invalid upstream state before the search starts
+    calc_shock_jump_for_equilibrium_air(upstream_mach, PI / 2.0, upstream, air)?;
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    let deflection = |shock_angle: f64| {
+        calc_shock_jump_for_equilibrium_air(upstream_mach, shock_angle, upstream, air)
+            .map_or(f64::NAN, |(jump, _)| jump.deflection_angle)
+    };
+    golden_section_maximum(&deflection, mach_angle, PI / 2.0, Some(1e-12), None)
+}
+
+pub fn calc_shock_angle_for_equilibrium_air(
+    upstream_mach: f64,
+    deflection_angle: f64,
+    upstream: &EquilibriumState,
+    air: &EquilibriumAir,
+) -> Result<f64, InletError> {
+    // weak solution, bracketed as for calc_shock_angle_for_gas
+    if deflection_angle.is_nan() || deflection_angle < 0.0 {
+        return Err(InletError::InvalidParameter { name: "deflection angle", value: deflection_angle });
+    }
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    let max_shock_angle: f64 = calc_max_shock_angle_for_equilibrium_air(upstream_mach, upstream, air)?;
+    if deflection_angle == 0.0 {
+        return Ok(mach_angle);
+    }
+    let f = |shock_angle: f64| {
+        calc_shock_jump_for_equilibrium_air(upstream_mach, shock_angle, upstream, air)
+            .map_or(f64::NAN, |(jump, _)| jump.deflection_angle - deflection_angle)
+    };
+    if f(max_shock_angle) < 0.0 {
+        return Err(InletError::DetachedShock { upstream_mach, deflection_angle });
+    }
+    Ok(brent(&f, mach_angle, max_shock_angle, Some(1e-12), None)?.root)
+}
+
+pub fn calc_shock_solution(upstream_mach: f64, shock_angle: f64, specific_heat_ratio: f64) -> Result<ShockSolution, InletError> {
+    // which branch of the deflection curve a shock angle lies on
+    let mach_angle: f64 = (1.0 / upstream_mach).asin();
+    if shock_angle.is_nan() || shock_angle < mach_angle || shock_angle > PI / 2.0 {
+        return Err(InletError::InvalidParameter { name: "shock angle", value: shock_angle });
+    }
+    let max_shock_angle: f64 = calc_max_shock_angle(upstream_mach, specific_heat_ratio)?;
+    if shock_angle <= max_shock_angle {
+        Ok(ShockSolution::Weak)
+    } else {
+        Ok(ShockSolution::Strong)
+    }
+}
+
+fn calc_shock_angle_from_normal_mach(upstream_mach: f64, normal_upstream_mach: f64, name: &'static str, value: f64) -> Result<f64, InletError> {
+    // each ratio fixes the normal mach number, and so a single shock angle, which may lie
+    // on either branch
+    if upstream_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
+    }
+    if normal_upstream_mach > upstream_mach {
+        return Err(InletError::InvalidParameter { name, value });
+    }
+    Ok((normal_upstream_mach / upstream_mach).asin())
+}
+
+pub fn calc_shock_angle_from_pressure_ratio(upstream_mach: f64, pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    let normal_upstream_mach: f64 = normalshock::calc_upstream_mach_from_pressure_ratio(pressure_ratio, specific_heat_ratio)?;
+    calc_shock_angle_from_normal_mach(upstream_mach, normal_upstream_mach, "pressure ratio", pressure_ratio)
+}
+
+pub fn calc_shock_angle_from_density_ratio(upstream_mach: f64, density_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    let normal_upstream_mach: f64 = normalshock::calc_upstream_mach_from_density_ratio(density_ratio, specific_heat_ratio)?;
+    calc_shock_angle_from_normal_mach(upstream_mach, normal_upstream_mach, "density ratio", density_ratio)
+}
+
+pub fn calc_shock_angle_from_stagnation_pressure_ratio(upstream_mach: f64, stagnation_pressure_ratio: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    let normal_upstream_mach: f64 = normalshock::calc_upstream_mach_from_stagnation_pressure_ratio(stagnation_pressure_ratio, specific_heat_ratio)?;
+    calc_shock_angle_from_normal_mach(upstream_mach, normal_upstream_mach, "stagnation pressure ratio", stagnation_pressure_ratio)
+}
+
+pub fn calc_upstream_mach_from_shock_angle(shock_angle: f64, deflection_angle: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    // the theta beta mach relation solved for the square of the mach number, which is unique
+    // for a given pair of angles, use calc_shock_solution for the branch it lies on
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if shock_angle.is_nan() || shock_angle <= 0.0 || shock_angle > PI / 2.0 {
+        return Err(InletError::InvalidParameter { name: "shock angle", value: shock_angle });
+    }
+    if deflection_angle.is_nan() || deflection_angle < 0.0 || deflection_angle >= shock_angle {
+        return Err(InletError::InvalidParameter { name: "deflection angle", value: deflection_angle });
+    }
+
+    let denominator: f64 =
+        (2.0 * shock_angle).sin() - deflection_angle.tan() * (specific_heat_ratio + (2.0 * shock_angle).cos());
+    if denominator <= 0.0 {
+        return Err(InletError::NoSolution("no upstream mach number turns the flow through the deflection at this shock angle"));
+    }
+    let upstream_mach_squared: f64 = 2.0 * (1.0 / shock_angle.tan() + deflection_angle.tan()) / denominator;
+    Ok(upstream_mach_squared.sqrt())
+}
+
+pub fn calc_max_shock_angle(upstream_mach: f64, specific_heat_ratio: f64) -> Result<f64, InletError> {
+    if !valid_specific_heat_ratio(specific_heat_ratio) {
+        return Err(InletError::InvalidSpecificHeatRatio { specific_heat_ratio });
+    }
+    if upstream_mach <= 1.0 {
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
     }
     
     let sin_max_shock_angle: f64 = 
         ((1.0 / (specific_heat_ratio * upstream_mach.powi(2))) * 
-        (1.0 +
+        ((specific_heat_ratio + 1.0) * upstream_mach.powi(2) / 4.0 - 1.0 +
             ((specific_heat_ratio + 1.0) * (
                 (specific_heat_ratio + 1.0) * upstream_mach.powi(4) / 16.0 +
                 (specific_heat_ratio - 1.0) * upstream_mach.powi(2) / 2.0 +
                 1.0
-            ).sqrt())
+            )).sqrt()
         )).sqrt();
 
-    if sin_max_shock_angle > 1.0 || sin_max_shock_angle < 0.0 {
-        return Err("math error");
+    if !(0.0..=1.0).contains(&sin_max_shock_angle) {
+        return Err(InletError::NoSolution("math error"));
     }
 
     let shock_angle: f64 = sin_max_shock_angle.asin();
     Ok(shock_angle)
 }
 
-pub fn calc_normal_upstream_mach(upstream_mach: f64, shock_angle: f64) -> Result<f64, &'static str> {
+pub fn calc_normal_upstream_mach(upstream_mach: f64, shock_angle: f64) -> Result<f64, InletError> {
     if upstream_mach <= 1.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number: upstream_mach });
     }
-    Ok(upstream_mach * shock_angle.sin())
+    // a shock at the mach angle is a mach wave, whose normal mach number can round to just below one
+    let normal_upstream_mach: f64 = upstream_mach * shock_angle.sin();
+    if (1.0 - normal_upstream_mach) < 1e-12 {
+        return Ok(normal_upstream_mach.max(1.0));
+    }
+    Ok(normal_upstream_mach)
 }
 
-pub fn calc_normal_downstream_mach(downstream_mach: f64, shock_angle: f64, deflection_angle: f64) -> Result<f64, &'static str> {
+pub fn calc_normal_downstream_mach(downstream_mach: f64, shock_angle: f64, deflection_angle: f64) -> Result<f64, InletError> {
     if downstream_mach <= 1.0 {
-        return Err("invalid mach number");
+        return Err(InletError::InvalidMachNumber { mach_number: downstream_mach });
     }
     Ok(downstream_mach * (shock_angle - deflection_angle).sin())
 }
@@ -135,6 +395,7 @@ pub fn calc_normal_downstream_mach(downstream_mach: f64, shock_angle: f64, defle
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{equilibrium::Species, gas::{CaloricallyPerfectGas, ThermallyPerfectGas}};
 
     #[test]
     fn test_calc_deflection_angle() {
@@ -216,6 +477,183 @@ mod tests {
         assert!((result - known_shock_angle).abs() < 1e-2);
     }
 
+    #[test]
+    fn test_weak_and_strong_solutions() {
+        // mach 2 flow turned through 10 degrees, with the roots either side of maximum deflection
+        let deflection_angle = 10.0_f64.to_radians();
+        let weak = calc_shock_angle_for_solution(2.0, deflection_angle, ShockSolution::Weak, 1.4)
+            .expect("attached shock");
+        let strong = calc_shock_angle_for_solution(2.0, deflection_angle, ShockSolution::Strong, 1.4)
+            .expect("attached shock");
+        assert!((weak.to_degrees() - 39.31).abs() < 1e-2);
+        assert!((strong.to_degrees() - 83.70).abs() < 1e-2);
+        assert_eq!(calc_shock_angle(2.0, deflection_angle, 1.4).expect("attached shock"), weak);
+        assert!(calc_downstream_mach_from_shock_angle(2.0, weak, 1.4).expect("valid shock") > 1.0);
+        assert!(calc_downstream_mach_from_shock_angle(2.0, strong, 1.4).expect("valid shock") < 1.0);
+
+        // no deflection leaves a mach wave or a normal shock
+        let mach_wave = calc_shock_angle_for_solution(2.0, 0.0, ShockSolution::Weak, 1.4).expect("attached shock");
+        let normal_shock = calc_shock_angle_for_solution(2.0, 0.0, ShockSolution::Strong, 1.4).expect("attached shock");
+        assert!((mach_wave - PI / 6.0).abs() < 1e-9);
+        assert!((normal_shock - PI / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calc_max_deflection_angle() {
+        // mach 2 turns through at most 22.97 degrees, the two solutions meeting there
+        let max_deflection_angle = calc_max_deflection_angle(2.0, 1.4).expect("calculation should succeed");
+        assert!((max_deflection_angle.to_degrees() - 22.97).abs() < 1e-2);
+        let weak = calc_shock_angle_for_solution(2.0, max_deflection_angle, ShockSolution::Weak, 1.4).expect("attached shock");
+        let strong = calc_shock_angle_for_solution(2.0, max_deflection_angle, ShockSolution::Strong, 1.4).expect("attached shock");
+        assert!((weak - strong).abs() < 1e-6);
+        assert!(calc_shock_angle(2.0, max_deflection_angle + 1e-6, 1.4).is_err());
+    }
+
+    #[test]
+    fn test_calc_sonic_shock_angle() {
+        // the downstream flow is sonic a little below the maximum deflection
+        for upstream_mach in [1.5, 2.0, 5.0] {
+            let sonic_shock_angle = calc_sonic_shock_angle(upstream_mach, 1.4).expect("calculation should succeed");
+            let downstream_mach = calc_downstream_mach_from_shock_angle(upstream_mach, sonic_shock_angle, 1.4)
+                .expect("valid shock");
+            assert!((downstream_mach - 1.0).abs() < 1e-9);
+            assert!(sonic_shock_angle < calc_max_shock_angle(upstream_mach, 1.4).expect("calculation should succeed"));
+            let sonic_deflection_angle = calc_sonic_deflection_angle(upstream_mach, 1.4).expect("calculation should succeed");
+            assert!(sonic_deflection_angle < calc_max_deflection_angle(upstream_mach, 1.4).expect("calculation should succeed"));
+        }
+    }
+
+    #[test]
+    fn test_shock_angle_from_ratios() {
+        // each ratio recovers the weak and strong shock angles that produced it
+        let deflection_angle = 10.0_f64.to_radians();
+        for solution in [ShockSolution::Weak, ShockSolution::Strong] {
+            let shock_angle = calc_shock_angle_for_solution(3.0, deflection_angle, solution, 1.4).expect("attached shock");
+            let pressure_ratio = calc_pressure_ratio(3.0, shock_angle, 1.4).expect("valid shock");
+            let density_ratio = calc_density_ratio(3.0, shock_angle, 1.4).expect("valid shock");
+            let stagnation_pressure_ratio = calc_stagnation_pressure_ratio(3.0, shock_angle, 1.4).expect("valid shock");
+            let recovered = [
+                calc_shock_angle_from_pressure_ratio(3.0, pressure_ratio, 1.4),
+                calc_shock_angle_from_density_ratio(3.0, density_ratio, 1.4),
+                calc_shock_angle_from_stagnation_pressure_ratio(3.0, stagnation_pressure_ratio, 1.4),
+            ];
+            for result in recovered {
+                let result = result.expect("valid inverse");
+                assert!((result - shock_angle).abs() < 1e-9);
+                assert_eq!(calc_shock_solution(3.0, result, 1.4).expect("valid shock"), solution);
+            }
+        }
+    }
+
+    #[test]
+    fn test_calc_upstream_mach_from_shock_angle() {
+        // both branches of the mach 2, 10 degree shock recover mach 2
+        let deflection_angle = 10.0_f64.to_radians();
+        for solution in [ShockSolution::Weak, ShockSolution::Strong] {
+            let shock_angle = calc_shock_angle_for_solution(2.0, deflection_angle, solution, 1.4).expect("attached shock");
+            let upstream_mach = calc_upstream_mach_from_shock_angle(shock_angle, deflection_angle, 1.4).expect("valid shock");
+            assert!((upstream_mach - 2.0).abs() < 1e-9);
+            assert_eq!(calc_shock_solution(upstream_mach, shock_angle, 1.4).expect("valid shock"), solution);
+        }
+
+        // no deflection is a mach wave
+        let upstream_mach = calc_upstream_mach_from_shock_angle(PI / 6.0, 0.0, 1.4).expect("valid mach wave");
+        assert!((upstream_mach - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_downstream_state() {
+        // a mach 2 normal shock in air at 300 K and 1 bar
+        let air = CaloricallyPerfectGas::air();
+        let temperature = calc_downstream_temperature(2.0, PI / 2.0, 300.0, &air).expect("valid shock");
+        let pressure = calc_downstream_pressure(2.0, PI / 2.0, 1e5, 300.0, &air).expect("valid shock");
+        assert!((temperature - 506.25).abs() < 1e-9);
+        assert!((pressure - 4.5e5).abs() < 1e-6);
+        assert!(calc_downstream_temperature(2.0, PI / 2.0, 0.0, &air).is_err());
+
+        // vibration takes up part of the energy of a strong shock, leaving the gas cooler
+        let strong = calc_downstream_temperature(8.0, PI / 3.0, 300.0, &air).expect("valid shock");
+        let vibrating = calc_downstream_temperature(8.0, PI / 3.0, 300.0, &ThermallyPerfectGas::air()).expect("valid shock");
+        assert!(vibrating < strong - 50.0);
+    }
+
+    #[test]
+    fn test_shock_jump_for_gas() {
+        // a calorically perfect gas matches the closed forms, while a thermally perfect
+        // gas at mach 8 turns the flow further through a given shock angle
+        let perfect = CaloricallyPerfectGas::air();
+        let shock_angle = 40.0_f64.to_radians();
+        let jump = calc_shock_jump_for_gas(3.0, shock_angle, 300.0, &perfect).expect("valid shock");
+        assert!((jump.deflection_angle - calc_deflection_angle(3.0, shock_angle, 1.4).expect("valid shock")).abs() < 1e-12);
+        assert!((jump.downstream_mach - calc_downstream_mach_from_shock_angle(3.0, shock_angle, 1.4).expect("valid shock")).abs() < 1e-12);
+        assert!((jump.pressure_ratio - calc_pressure_ratio(3.0, shock_angle, 1.4).expect("valid shock")).abs() < 1e-12);
+
+        let air = ThermallyPerfectGas::air();
+        let real = calc_shock_jump_for_gas(8.0, 0.5, 220.0, &air).expect("valid shock");
+        let ideal = calc_shock_jump_for_gas(8.0, 0.5, 220.0, &perfect).expect("valid shock");
+        assert!(real.deflection_angle > ideal.deflection_angle);
+        let weak = calc_shock_angle_for_gas(8.0, real.deflection_angle, 220.0, &air).expect("attached shock");
+        assert!((weak - 0.5).abs() < 1e-9);
+        assert!(calc_shock_angle_for_gas(8.0, 0.9, 220.0, &air).is_err());
+    }
+
+    #[test]
+    fn test_calc_max_shock_angle_for_gas() {
+        // a perfect gas peaks at the closed form angle, while the thermally perfect gas turns
+        // the flow beyond the perfect gas maximum, and those deflections still have a weak shock
+        let perfect = CaloricallyPerfectGas::air();
+        let max_shock_angle = calc_max_shock_angle_for_gas(3.0, 300.0, &perfect).expect("valid shock");
+        assert!((max_shock_angle - calc_max_shock_angle(3.0, 1.4).expect("valid shock")).abs() < 1e-5);
+
+        let air = ThermallyPerfectGas::air();
+        let upstream_temperature: f64 = 1500.0;
+        let real_max_shock_angle = calc_max_shock_angle_for_gas(8.0, upstream_temperature, &air).expect("valid shock");
+        let real_max = calc_shock_jump_for_gas(8.0, real_max_shock_angle, upstream_temperature, &air).expect("valid shock").deflection_angle;
+        let perfect_max_shock_angle = calc_max_shock_angle(8.0, air.specific_heat_ratio(upstream_temperature)).expect("valid shock");
+        let perfect_max = calc_shock_jump_for_gas(8.0, perfect_max_shock_angle, upstream_temperature, &air).expect("valid shock").deflection_angle;
+        assert!(real_max > perfect_max);
+        let deflection_angle: f64 = 0.5 * (real_max + perfect_max);
+        let weak = calc_shock_angle_for_gas(8.0, deflection_angle, upstream_temperature, &air).expect("attached shock");
+        assert!(weak < real_max_shock_angle);
+        let jump = calc_shock_jump_for_gas(8.0, weak, upstream_temperature, &air).expect("valid shock");
+        assert!((jump.deflection_angle - deflection_angle).abs() < 1e-9);
+        assert!(calc_shock_angle_for_gas(8.0, real_max + 1e-4, upstream_temperature, &air).is_err());
+    }
+
+    #[test]
+    fn test_shock_jump_for_equilibrium_air() {
+        // at mach 25 the dissociating gas is denser behind the shock, turning the flow further
+        // than a thermally perfect gas through the same shock angle
+        let air = EquilibriumAir::air();
+        let upstream = air.state(50.0, 250.0).expect("valid state");
+        let shock_angle: f64 = 0.6;
+        let (jump, downstream) = calc_shock_jump_for_equilibrium_air(25.0, shock_angle, &upstream, &air).expect("valid shock");
+        let frozen = calc_shock_jump_for_gas(25.0, shock_angle, 250.0, &ThermallyPerfectGas::air()).expect("valid shock");
+        assert!(jump.deflection_angle > frozen.deflection_angle);
+        assert!(jump.density_ratio > frozen.density_ratio);
+        assert!(downstream.composition.mole_fraction(Species::AtomicOxygen) > 0.05);
+        assert!(jump.downstream_mach > 1.0);
+        let weak = calc_shock_angle_for_equilibrium_air(25.0, jump.deflection_angle, &upstream, &air).expect("attached shock");
+        assert!((weak - shock_angle).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calc_max_shock_angle_for_equilibrium_air() {
+        // dissociation lets the shock turn the flow beyond the perfect gas maximum at the
+        // upstream isentropic exponent, and those deflections still have a weak shock
+        let air = EquilibriumAir::air();
+        let upstream = air.state(50.0, 250.0).expect("valid state");
+        let max_shock_angle = calc_max_shock_angle_for_equilibrium_air(25.0, &upstream, &air).expect("valid shock");
+        let (max_jump, _) = calc_shock_jump_for_equilibrium_air(25.0, max_shock_angle, &upstream, &air).expect("valid shock");
+        let perfect_max_shock_angle = calc_max_shock_angle(25.0, air.isentropic_exponent(&upstream).expect("valid state")).expect("valid shock");
+        let (perfect_jump, _) = calc_shock_jump_for_equilibrium_air(25.0, perfect_max_shock_angle, &upstream, &air).expect("valid shock");
+        assert!(max_jump.deflection_angle > perfect_jump.deflection_angle);
+        let deflection_angle: f64 = 0.5 * (max_jump.deflection_angle + perfect_jump.deflection_angle);
+        let weak = calc_shock_angle_for_equilibrium_air(25.0, deflection_angle, &upstream, &air).expect("attached shock");
+        assert!(weak < max_shock_angle);
+        assert!(calc_shock_angle_for_equilibrium_air(25.0, max_jump.deflection_angle + 1e-4, &upstream, &air).is_err());
+    }
+
     #[test]
     fn test_calc_max_shock_angle() {
         // test calc_max_shock_angle with upstream mach 3.0 and specific heat ratio 1.4
@@ -223,8 +661,8 @@ mod tests {
         let specific_heat_ratio = 1.4;
         let result = calc_max_shock_angle(upstream_mach, specific_heat_ratio)
             .expect("calculation should succeed");
-        // expected value approx asin(0.903) which is about 1.12 rad
-        let expected = 1.12;
+        // expected value approx asin(0.908) which is about 1.139 rad
+        let expected = 1.139;
         assert!((result - expected).abs() < 1e-2);
     }
 
@@ -287,5 +725,27 @@ mod tests {
         assert!(calc_normal_downstream_mach(1.0, PI / 4.0, 0.2).is_err());
         // invalid specific heat ratio for calc_stagnation_pressure_ratio
         assert!(calc_stagnation_pressure_ratio(2.0, PI / 4.0, 1.0).is_err());
+        // deflection beyond the attached shock limit for calc_shock_angle
+        assert!(matches!(calc_shock_angle(2.0, 0.5, 1.4), Err(InletError::DetachedShock { .. })));
+        assert!(matches!(calc_shock_angle_for_solution(2.0, 0.5, ShockSolution::Strong, 1.4), Err(InletError::DetachedShock { .. })));
+        // negative deflection for calc_shock_angle
+        assert!(calc_shock_angle(2.0, -0.1, 1.4).is_err());
+        // invalid upstream mach for calc_sonic_shock_angle and calc_max_deflection_angle
+        assert!(calc_sonic_shock_angle(0.9, 1.4).is_err());
+        assert!(calc_max_deflection_angle(1.0, 1.4).is_err());
+        // ratios beyond a normal shock at the upstream mach for the inverse relations
+        assert!(calc_shock_angle_from_pressure_ratio(2.0, 5.0, 1.4).is_err());
+        assert!(calc_shock_angle_from_density_ratio(2.0, 0.9, 1.4).is_err());
+        assert!(calc_shock_angle_from_stagnation_pressure_ratio(2.0, 0.5, 1.4).is_err());
+        // shock angle below the mach angle for calc_shock_solution
+        assert!(calc_shock_solution(2.0, 0.4, 1.4).is_err());
+        // deflections no upstream mach can produce for calc_upstream_mach_from_shock_angle
+        assert!(calc_upstream_mach_from_shock_angle(0.3, 0.3, 1.4).is_err());
+        assert!(calc_upstream_mach_from_shock_angle(PI / 6.0, 25.0_f64.to_radians(), 1.4).is_err());
+        // deflections beyond the attached limit in equilibrium air
+        let air = EquilibriumAir::air();
+        let upstream = air.state(50.0, 250.0).expect("valid state");
+        assert!(calc_shock_angle_for_equilibrium_air(25.0, -0.1, &upstream, &air).is_err());
+        assert!(calc_shock_angle_for_equilibrium_air(25.0, 1.2, &upstream, &air).is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,454 @@
+#![allow(dead_code)]
+
+use super::gas::GasModel;
+use crate::error::InletError;
+
+// universal gas constant in J/(mol K)
+const UNIVERSAL_GAS_CONSTANT: f64 = 8.314462618;
+
+// temperature in kelvin joining the low and high ranges of the cp polynomials, which
+// cover 200 K to 6000 K between them
+const COMMON_TEMPERATURE: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasSpecies {
+    Air,
+    Nitrogen,
+    Oxygen,
+    CarbonDioxide,
+    CarbonMonoxide,
+    Water,
+    Hydrogen,
+    Helium,
+    Argon,
+}
+
+struct SpeciesData {
+    // cp over the universal gas constant as a quartic in temperature in the form of the
+    // nasa polynomials, each range fitted to rigid rotor partition functions with
+    // anharmonic vibration for the diatomics and harmonic vibration for the triatomics,
+    // plus sutherland's law for the viscosity
+    molar_mass: f64,                                 // kg/mol
+    specific_heat_coefficients: [[f64; 5]; 2],      // below and above the common temperature
+    reference_viscosity: f64,                        // Pa s
+    reference_temperature: f64,                      // K
+    sutherland_temperature: f64,                     // K
+}
+
+const MONATOMIC_COEFFICIENTS: [[f64; 5]; 2] = [[2.5, 0.0, 0.0, 0.0, 0.0], [2.5, 0.0, 0.0, 0.0, 0.0]];
+
+const AIR: SpeciesData = SpeciesData {
+    // dry air of 78.084 % nitrogen, 20.946 % oxygen, 0.934 % argon and 0.036 % carbon dioxide
+    molar_mass: 0.0289647,
+    specific_heat_coefficients: [
+        [3.627379675e+00, -1.184912903e-03, 2.970117292e-06, -1.622155462e-09, 1.799820267e-13],
+        [3.119428418e+00, 1.191829980e-03, -4.003881082e-07, 6.330431907e-11, -3.763981388e-15],
+    ],
+    reference_viscosity: 1.716e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 110.4,
+};
+
+const NITROGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0280134,
+    specific_heat_coefficients: [
+        [3.596171456e+00, -6.869410788e-04, 1.087396664e-06, 6.050094973e-10, -6.732210860e-13],
+        [2.971691970e+00, 1.367358712e-03, -4.838134771e-07, 7.782098124e-11, -4.642733396e-15],
+    ],
+    reference_viscosity: 1.663e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 107.0,
+};
+
+const OXYGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.0319988,
+    specific_heat_coefficients: [
+        [3.796308416e+00, -3.112784962e-03, 1.014228213e-05, -1.000872629e-08, 3.371112067e-12],
+        [3.695112832e+00, 5.881260729e-04, -1.062433921e-07, 1.184133899e-11, -6.454914717e-16],
+    ],
+    reference_viscosity: 1.919e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 139.0,
+};
+
+const CARBON_DIOXIDE: SpeciesData = SpeciesData {
+    molar_mass: 0.0440095,
+    specific_heat_coefficients: [
+        [2.278872362e+00, 9.672518261e-03, -9.355369965e-06, 5.157584147e-09, -1.258760565e-12],
+        [4.678129784e+00, 2.646435054e-03, -9.818106509e-07, 1.619367185e-10, -9.846664860e-15],
+    ],
+    reference_viscosity: 1.370e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 222.0,
+};
+
+const CARBON_MONOXIDE: SpeciesData = SpeciesData {
+    molar_mass: 0.0280101,
+    specific_heat_coefficients: [
+        [3.662170420e+00, -1.316680996e-03, 2.994602684e-06, -1.351780101e-09, -2.512162314e-15],
+        [3.072015201e+00, 1.313963704e-03, -4.725079782e-07, 7.695963265e-11, -4.630714185e-15],
+    ],
+    reference_viscosity: 1.657e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 136.0,
+};
+
+const WATER: SpeciesData = SpeciesData {
+    molar_mass: 0.0180153,
+    specific_heat_coefficients: [
+        [4.213929843e+00, -2.234201805e-03, 7.005168142e-06, -5.965888468e-09, 1.914929306e-12],
+        [2.690338219e+00, 3.027778169e-03, -9.038371892e-07, 1.264294926e-10, -6.771673698e-15],
+    ],
+    reference_viscosity: 1.12e-5,
+    reference_temperature: 350.0,
+    sutherland_temperature: 1064.0,
+};
+
+const HYDROGEN: SpeciesData = SpeciesData {
+    molar_mass: 0.00201588,
+    specific_heat_coefficients: [
+        [3.470582674e+00, 2.757421554e-04, -8.450612892e-07, 9.643293659e-10, -2.695514988e-13],
+        [2.954722908e+00, 7.480520648e-04, -1.095306159e-07, 1.945355735e-12, 8.516938178e-16],
+    ],
+    reference_viscosity: 8.411e-6,
+    reference_temperature: 273.15,
+    sutherland_temperature: 97.0,
+};
+
+const HELIUM: SpeciesData = SpeciesData {
+    molar_mass: 0.004002602,
+    specific_heat_coefficients: MONATOMIC_COEFFICIENTS,
+    reference_viscosity: 1.87e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 79.4,
+};
+
+const ARGON: SpeciesData = SpeciesData {
+    molar_mass: 0.039948,
+    specific_heat_coefficients: MONATOMIC_COEFFICIENTS,
+    reference_viscosity: 2.125e-5,
+    reference_temperature: 273.15,
+    sutherland_temperature: 144.0,
+};
+
+fn enthalpy_polynomial(coefficients: &[f64; 5], temperature: f64) -> f64 {
+    // integral of cp over the gas constant from absolute zero
+    coefficients.iter().enumerate().rev()
+        .fold(0.0, |sum, (power, coefficient)| (sum + coefficient / (power + 1) as f64) * temperature)
+}
+
+fn entropy_polynomial(coefficients: &[f64; 5], temperature: f64) -> f64 {
+    // integral of cp over the gas constant and temperature, up to a constant
+    coefficients[0] * temperature.ln() + coefficients[1..].iter().enumerate().rev()
+        .fold(0.0, |sum, (power, coefficient)| (sum + coefficient / (power + 1) as f64) * temperature)
+}
+
+impl GasSpecies {
+    pub const ALL: [GasSpecies; 9] = [
+        GasSpecies::Air,
+        GasSpecies::Nitrogen,
+        GasSpecies::Oxygen,
+        GasSpecies::CarbonDioxide,
+        GasSpecies::CarbonMonoxide,
+        GasSpecies::Water,
+        GasSpecies::Hydrogen,
+        GasSpecies::Helium,
+        GasSpecies::Argon,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GasSpecies::Air => "air",
+            GasSpecies::Nitrogen => "N2",
+            GasSpecies::Oxygen => "O2",
+            GasSpecies::CarbonDioxide => "CO2",
+            GasSpecies::CarbonMonoxide => "CO",
+            GasSpecies::Water => "H2O",
+            GasSpecies::Hydrogen => "H2",
+            GasSpecies::Helium => "He",
+            GasSpecies::Argon => "Ar",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        // by formula or by name, ignoring case
+        match name.to_lowercase().as_str() {
+            "air" => Some(GasSpecies::Air),
+            "n2" | "nitrogen" => Some(GasSpecies::Nitrogen),
+            "o2" | "oxygen" => Some(GasSpecies::Oxygen),
+            "co2" | "carbon-dioxide" => Some(GasSpecies::CarbonDioxide),
+            "co" | "carbon-monoxide" => Some(GasSpecies::CarbonMonoxide),
+            "h2o" | "water" | "steam" => Some(GasSpecies::Water),
+            "h2" | "hydrogen" => Some(GasSpecies::Hydrogen),
+            "he" | "helium" => Some(GasSpecies::Helium),
+            "ar" | "argon" => Some(GasSpecies::Argon),
+            _ => None,
+        }
+    }
+
+    fn data(&self) -> &'static SpeciesData {
+        match self {
+            GasSpecies::Air => &AIR,
+            GasSpecies::Nitrogen => &NITROGEN,
+            GasSpecies::Oxygen => &OXYGEN,
+            GasSpecies::CarbonDioxide => &CARBON_DIOXIDE,
+            GasSpecies::CarbonMonoxide => &CARBON_MONOXIDE,
+            GasSpecies::Water => &WATER,
+            GasSpecies::Hydrogen => &HYDROGEN,
+            GasSpecies::Helium => &HELIUM,
+            GasSpecies::Argon => &ARGON,
+        }
+    }
+
+    pub fn molar_mass(&self) -> f64 {
+        self.data().molar_mass
+    }
+
+    pub fn specific_heat_cp(&self, temperature: f64) -> f64 {
+        // molar cp in J/(mol K), the low range extrapolating towards absolute zero
+        let coefficients: &[[f64; 5]; 2] = &self.data().specific_heat_coefficients;
+        let range: usize = if temperature <= COMMON_TEMPERATURE { 0 } else { 1 };
+        let specific_heat_cp: f64 = coefficients[range].iter().rev().fold(0.0, |sum, coefficient| sum * temperature + coefficient);
+        UNIVERSAL_GAS_CONSTANT * specific_heat_cp
+    }
+
+    pub fn enthalpy(&self, temperature: f64) -> f64 {
+        // molar sensible enthalpy in J/mol from absolute zero, the high range carrying on
+        // from the low range at the common temperature
+        let [low, high] = &self.data().specific_heat_coefficients;
+        let enthalpy: f64 = if temperature <= COMMON_TEMPERATURE {
+            enthalpy_polynomial(low, temperature)
+        } else {
+            enthalpy_polynomial(low, COMMON_TEMPERATURE) + enthalpy_polynomial(high, temperature)
+                - enthalpy_polynomial(high, COMMON_TEMPERATURE)
+        };
+        UNIVERSAL_GAS_CONSTANT * enthalpy
+    }
+
+    pub fn entropy_function(&self, temperature: f64) -> f64 {
+        // temperature dependent part of the molar entropy in J/(mol K)
+        let [low, high] = &self.data().specific_heat_coefficients;
+        let entropy: f64 = if temperature <= COMMON_TEMPERATURE {
+            entropy_polynomial(low, temperature)
+        } else {
+            entropy_polynomial(low, COMMON_TEMPERATURE) + entropy_polynomial(high, temperature)
+                - entropy_polynomial(high, COMMON_TEMPERATURE)
+        };
+        UNIVERSAL_GAS_CONSTANT * entropy
+    }
+
+    pub fn viscosity(&self, temperature: f64) -> f64 {
+        // sutherland's law in Pa s
+        let data: &SpeciesData = self.data();
+        data.reference_viscosity * (temperature / data.reference_temperature).powf(1.5)
+            * (data.reference_temperature + data.sutherland_temperature) / (temperature + data.sutherland_temperature)
+    }
+
+    pub fn thermal_conductivity(&self, temperature: f64) -> f64 {
+        // the modified eucken relation in W/(m K), exact for the monatomic gases
+        let gas_constant: f64 = UNIVERSAL_GAS_CONSTANT / self.molar_mass();
+        let specific_heat_cv: f64 = self.specific_heat_cp(temperature) / self.molar_mass() - gas_constant;
+        self.viscosity(temperature) * (1.32 * specific_heat_cv + 1.77 * gas_constant)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasMixture {
+    // a thermally perfect mixture of fixed composition, held as normalised mole fractions
+    components: Vec<(GasSpecies, f64)>,
+}
+
+impl GasMixture {
+    pub fn new(mole_fractions: &[(GasSpecies, f64)]) -> Result<Self, InletError> {
+        // the fractions are normalised, so they may be given as percentages or parts
+        if let Some((_, fraction)) = mole_fractions.iter().find(|(_, fraction)| fraction.is_nan() || *fraction < 0.0) {
+            return Err(InletError::InvalidParameter { name: "mole fraction", value: *fraction });
+        }
+        let total: f64 = mole_fractions.iter().map(|(_, fraction)| fraction).sum();
+        if !total.is_finite() || total <= 0.0 {
+            return Err(InletError::InvalidParameter { name: "total mole fraction", value: total });
+        }
+        let components: Vec<(GasSpecies, f64)> = mole_fractions.iter()
+            .filter(|(_, fraction)| *fraction > 0.0)
+            .map(|(species, fraction)| (*species, fraction / total))
+            .collect();
+        Ok(GasMixture { components })
+    }
+
+    pub fn pure(species: GasSpecies) -> Self {
+        GasMixture { components: vec![(species, 1.0)] }
+    }
+
+    pub fn mole_fraction(&self, species: GasSpecies) -> f64 {
+        self.components.iter().filter(|(component, _)| *component == species).map(|(_, fraction)| fraction).sum()
+    }
+
+    pub fn mass_fraction(&self, species: GasSpecies) -> f64 {
+        self.mole_fraction(species) * species.molar_mass() / self.molar_mass()
+    }
+
+    pub fn molar_mass(&self) -> f64 {
+        self.molar_average(|species| species.molar_mass())
+    }
+
+    fn molar_average(&self, property: impl Fn(GasSpecies) -> f64) -> f64 {
+        self.components.iter().map(|(species, fraction)| fraction * property(*species)).sum()
+    }
+
+    fn wilke_average(&self, temperature: f64, property: impl Fn(GasSpecies) -> f64) -> f64 {
+        // wilke's mixing rule for the viscosity, which with the same weights is the mason
+        // and saxena form of the wassiljewa rule for the thermal conductivity
+        self.components.iter().map(|(species, fraction)| {
+            let weight: f64 = self.components.iter().map(|(other, other_fraction)| {
+                let viscosity_ratio: f64 = species.viscosity(temperature) / other.viscosity(temperature);
+                let mass_ratio: f64 = species.molar_mass() / other.molar_mass();
+                other_fraction * (1.0 + viscosity_ratio.sqrt() * mass_ratio.powf(-0.25)).powi(2)
+                    / (8.0 * (1.0 + mass_ratio)).sqrt()
+            }).sum();
+            fraction * property(*species) / weight
+        }).sum()
+    }
+
+    pub fn viscosity(&self, temperature: f64) -> f64 {
+        self.wilke_average(temperature, |species| species.viscosity(temperature))
+    }
+
+    pub fn thermal_conductivity(&self, temperature: f64) -> f64 {
+        self.wilke_average(temperature, |species| species.thermal_conductivity(temperature))
+    }
+
+    pub fn prandtl_number(&self, temperature: f64) -> f64 {
+        self.viscosity(temperature) * self.specific_heat_cp(temperature) / self.thermal_conductivity(temperature)
+    }
+}
+
+impl GasModel for GasMixture {
+    // per unit mass, the molar properties averaged over the mole fractions, leaving out the
+    // entropy of mixing which is constant for a fixed composition
+    fn specific_heat_ratio(&self, temperature: f64) -> f64 {
+        let specific_heat_cp: f64 = self.specific_heat_cp(temperature);
+        specific_heat_cp / (specific_heat_cp - self.gas_constant())
+    }
+
+    fn gas_constant(&self) -> f64 {
+        UNIVERSAL_GAS_CONSTANT / self.molar_mass()
+    }
+
+    fn specific_heat_cp(&self, temperature: f64) -> f64 {
+        self.molar_average(|species| species.specific_heat_cp(temperature)) / self.molar_mass()
+    }
+
+    fn enthalpy(&self, temperature: f64) -> f64 {
+        self.molar_average(|species| species.enthalpy(temperature)) / self.molar_mass()
+    }
+
+    fn entropy_function(&self, temperature: f64) -> f64 {
+        self.molar_average(|species| species.entropy_function(temperature)) / self.molar_mass()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::gas::{ThermallyPerfectGas, AIR_GAS_CONSTANT, STANDARD_TEMPERATURE};
+
+    #[test]
+    fn test_species_properties() {
+        // molar cp against the janaf tables at 298.15 K, 1000 K and 2000 K
+        let tables: [(GasSpecies, [f64; 3]); 7] = [
+            (GasSpecies::Nitrogen, [29.124, 32.697, 36.006]),
+            (GasSpecies::Oxygen, [29.376, 34.870, 37.741]),
+            (GasSpecies::CarbonDioxide, [37.129, 54.308, 60.350]),
+            (GasSpecies::CarbonMonoxide, [29.142, 33.180, 36.250]),
+            (GasSpecies::Water, [33.590, 41.268, 51.180]),
+            (GasSpecies::Hydrogen, [28.836, 30.204, 34.280]),
+            (GasSpecies::Argon, [20.786, 20.786, 20.786]),
+        ];
+        for (species, specific_heats) in tables {
+            for (temperature, specific_heat_cp) in [298.15, 1000.0, 2000.0].into_iter().zip(specific_heats) {
+                assert!((species.specific_heat_cp(temperature) / specific_heat_cp - 1.0).abs() < 2e-2);
+            }
+        }
+
+        // the enthalpy and entropy function are consistent with cp on both sides of the
+        // common temperature, across which all three are continuous
+        for species in GasSpecies::ALL {
+            for temperature in [300.0, 999.0, 1001.0, 3000.0] {
+                let step: f64 = 1e-4 * temperature;
+                let enthalpy_slope = (species.enthalpy(temperature + step) - species.enthalpy(temperature - step)) / (2.0 * step);
+                let entropy_slope = (species.entropy_function(temperature + step) - species.entropy_function(temperature - step)) / (2.0 * step);
+                assert!((enthalpy_slope / species.specific_heat_cp(temperature) - 1.0).abs() < 1e-6);
+                assert!((entropy_slope * temperature / species.specific_heat_cp(temperature) - 1.0).abs() < 1e-6);
+            }
+            let below: f64 = COMMON_TEMPERATURE * (1.0 - 1e-12);
+            let above: f64 = COMMON_TEMPERATURE * (1.0 + 1e-12);
+            assert!((species.specific_heat_cp(below) / species.specific_heat_cp(above) - 1.0).abs() < 1e-9);
+            assert!((species.enthalpy(below) / species.enthalpy(above) - 1.0).abs() < 1e-9);
+            assert!((species.entropy_function(below) / species.entropy_function(above) - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_transport_properties() {
+        // air at 300 K has a viscosity of 1.846e-5 Pa s, and helium a thermal conductivity
+        // of 0.155 W/(m K)
+        assert!((GasSpecies::Air.viscosity(300.0) / 1.846e-5 - 1.0).abs() < 1e-2);
+        assert!((GasSpecies::Helium.thermal_conductivity(300.0) / 0.155 - 1.0).abs() < 2e-2);
+        assert!((GasSpecies::Nitrogen.thermal_conductivity(300.0) / 0.0259 - 1.0).abs() < 5e-2);
+
+        // a pure gas mixes to itself, and air mixed from its constituents matches the
+        // tabulated air
+        let nitrogen = GasMixture::pure(GasSpecies::Nitrogen);
+        assert!((nitrogen.viscosity(500.0) / GasSpecies::Nitrogen.viscosity(500.0) - 1.0).abs() < 1e-12);
+        let mixed_air = GasMixture::new(&[
+            (GasSpecies::Nitrogen, 78.084),
+            (GasSpecies::Oxygen, 20.946),
+            (GasSpecies::Argon, 0.934),
+            (GasSpecies::CarbonDioxide, 0.036),
+        ]).expect("valid mixture");
+        let air = GasMixture::pure(GasSpecies::Air);
+        for temperature in [250.0, 800.0, 2000.0] {
+            assert!((mixed_air.viscosity(temperature) / air.viscosity(temperature) - 1.0).abs() < 3e-2);
+            assert!((mixed_air.specific_heat_cp(temperature) / air.specific_heat_cp(temperature) - 1.0).abs() < 1e-3);
+        }
+        assert!((air.prandtl_number(300.0) - 0.71).abs() < 3e-2);
+    }
+
+    #[test]
+    fn test_gas_mixture() {
+        // air from the database agrees with the crate's air models
+        let air = GasMixture::pure(GasSpecies::Air);
+        assert!((air.gas_constant() / AIR_GAS_CONSTANT - 1.0).abs() < 1e-4);
+        assert!((air.specific_heat_ratio(STANDARD_TEMPERATURE) - 1.4).abs() < 2e-3);
+        let vibrating = ThermallyPerfectGas::air();
+        assert!((air.specific_heat_ratio(2000.0) - vibrating.specific_heat_ratio(2000.0)).abs() < 1e-2);
+        let recovered = air.temperature_from_enthalpy(air.enthalpy(1500.0)).expect("valid enthalpy");
+        assert!((recovered - 1500.0).abs() < 1e-9 * 1500.0);
+
+        // helium is monatomic, and the martian atmosphere is mostly carbon dioxide
+        let helium = GasMixture::pure(GasSpecies::Helium);
+        assert!((helium.specific_heat_ratio(STANDARD_TEMPERATURE) - 5.0 / 3.0).abs() < 1e-12);
+        assert!((helium.gas_constant() - 2077.26).abs() < 1e-1);
+        let mars = GasMixture::new(&[
+            (GasSpecies::CarbonDioxide, 0.9532),
+            (GasSpecies::Nitrogen, 0.027),
+            (GasSpecies::Argon, 0.016),
+            (GasSpecies::Oxygen, 0.0013),
+            (GasSpecies::CarbonMonoxide, 0.0007),
+        ]).expect("valid mixture");
+        assert!((mars.molar_mass() - 0.04348).abs() < 1e-5);
+        assert!((mars.specific_heat_ratio(210.0) - 1.344).abs() < 1e-2);
+        assert!((mars.mass_fraction(GasSpecies::CarbonDioxide) - 0.9664).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        // test error conditions for invalid inputs
+        assert!(GasMixture::new(&[]).is_err());
+        assert!(GasMixture::new(&[(GasSpecies::Nitrogen, 0.0)]).is_err());
+        assert!(GasMixture::new(&[(GasSpecies::Nitrogen, 1.0), (GasSpecies::Oxygen, -0.1)]).is_err());
+        assert!(GasMixture::new(&[(GasSpecies::Nitrogen, f64::NAN)]).is_err());
+        assert!(GasMixture::pure(GasSpecies::Argon).temperature_from_enthalpy(-1.0).is_err());
+        assert!(GasSpecies::parse("methane").is_none());
+    }
+}